@@ -0,0 +1,146 @@
+//! Automatic differentiation of functions written against `Matrix`.
+//!
+//! The forward mode evaluates the function on `Dual` numbers, seeding one input
+//! at a time, and yields exact derivatives. The reverse mode records matrix
+//! operations on a `Tape` and obtains the gradients of all inputs in one pass.
+mod tape;
+pub use tape::{Gradients, Tape, Var};
+use crate::matrix::dual::Dual;
+use crate::matrix::num::Real;
+use crate::Matrix;
+
+// Creates a copy of `x` over dual numbers whose derivative part is one at the
+// row-major index `k` and zero elsewhere.
+fn seed<T: Real>(x: &Matrix<T>, k: usize) -> Matrix<Dual<T>> {
+  let n_cols = x.n_cols();
+  let data = x
+    .iter()
+    .map(|(e, i, j)| if i * n_cols + j == k { Dual::variable(*e) } else { Dual::constant(*e) })
+    .collect();
+  Matrix::create_from_data(data, x.n_rows(), n_cols)
+}
+
+/// Computes the Jacobian matrix of `f` at `x`. Inputs and outputs are taken as
+/// vectors of their elements in row-major order, so the result has one row per
+/// output element and one column per input element.
+pub fn jacobian<T, F>(f: F, x: &Matrix<T>) -> Matrix<T>
+where
+  T: Real,
+  F: Fn(&Matrix<Dual<T>>) -> Matrix<Dual<T>>,
+{
+  let n = x.n_rows() * x.n_cols();
+  let columns = (0..n)
+    .map(|k| f(&seed(x, k)).iter().map(|(y, _, _)| y.eps).collect::<Vec<T>>())
+    .collect::<Vec<Vec<T>>>();
+  let m = match columns.first() {
+    Some(c) => c.len(),
+    None => {
+      let y = f(&x.map(Dual::constant));
+      y.n_rows() * y.n_cols()
+    }
+  };
+  let mut data = Vec::with_capacity(m * n);
+  for i in 0..m {
+    for c in &columns {
+      data.push(c[i]);
+    }
+  }
+  Matrix::create_from_data(data, m, n)
+}
+
+/// Computes the gradient of the scalar function `f` at `x`, returned as a matrix
+/// having the shape of `x`.
+pub fn gradient<T, F>(f: F, x: &Matrix<T>) -> Matrix<T>
+where
+  T: Real,
+  F: Fn(&Matrix<Dual<T>>) -> Dual<T>,
+{
+  let n = x.n_rows() * x.n_cols();
+  let data = (0..n).map(|k| f(&seed(x, k)).eps).collect();
+  Matrix::create_from_data(data, x.n_rows(), x.n_cols())
+}
+
+/// Computes the Hessian matrix of the scalar function `f` at `x` by evaluating it
+/// on hyper-dual numbers. The inputs are taken in row-major order.
+pub fn hessian<T, F>(f: F, x: &Matrix<T>) -> Matrix<T>
+where
+  T: Real,
+  F: Fn(&Matrix<Dual<Dual<T>>>) -> Dual<Dual<T>>,
+{
+  let n = x.n_rows() * x.n_cols();
+  let mut h = Matrix::zeros(n, n);
+  for i in 0..n {
+    for j in i..n {
+      let data = x
+        .iter()
+        .map(|(e, r, c)| {
+          let k = r * x.n_cols() + c;
+          let di = if k == i { T::one() } else { T::zero() };
+          let dj = if k == j { T::one() } else { T::zero() };
+          Dual::new(Dual::new(*e, di), Dual::constant(dj))
+        })
+        .collect();
+      let d = f(&Matrix::create_from_data(data, x.n_rows(), x.n_cols())).eps.eps;
+      h.set(i, j, d);
+      h.set(j, i, d);
+    }
+  }
+  h
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rosenbrock<T: Real>(x: &Matrix<T>) -> T {
+    let (a, b) = (x.get(0, 0), x.get(1, 0));
+    let one = T::one();
+    let hundred = T::from_f64(100.0);
+    (one - a) * (one - a) + hundred * (b - a * a) * (b - a * a)
+  }
+
+  #[test]
+  fn test_jacobian() {
+    let f = |v: &Matrix<Dual<f64>>| {
+      let (x, y) = (v.get(0, 0), v.get(1, 0));
+      Matrix::new(vec![vec![x * x * y], vec![Dual::constant(5.0) * x + y.sin()]])
+    };
+    let j = jacobian(f, &Matrix::new(vec![vec![1.0], vec![2.0]]));
+    assert_eq!(j, Matrix::new(vec![vec![4.0, 1.0], vec![5.0, 2f64.cos()]]));
+  }
+
+  #[test]
+  fn test_jacobian_of_matrix_product() {
+    let a = Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]);
+    let lifted = a.map(Dual::constant);
+    let j = jacobian(|x| &lifted * x, &Matrix::new(vec![vec![0.5], vec![-1.0]]));
+    assert_eq!(j, a);
+  }
+
+  #[test]
+  fn test_gradient() {
+    let g = gradient(rosenbrock, &Matrix::new(vec![vec![0.0], vec![1.0]]));
+    assert_eq!(g, Matrix::new(vec![vec![-2.0], vec![200.0]]));
+    let g = gradient(rosenbrock, &Matrix::new(vec![vec![1.0], vec![1.0]]));
+    assert_eq!(g, Matrix::zeros(2, 1));
+  }
+
+  #[test]
+  fn test_hessian() {
+    let h = hessian(rosenbrock, &Matrix::new(vec![vec![1.0], vec![1.0]]));
+    assert_eq!(h, Matrix::new(vec![vec![802.0, -400.0], vec![-400.0, 200.0]]));
+  }
+
+  #[test]
+  fn test_newton_step() {
+    // One Newton step on a quadratic lands in its minimum.
+    fn quadratic<T: Real>(v: &Matrix<T>) -> T {
+      let (x, y) = (v.get(0, 0), v.get(1, 0));
+      x * x + T::from_f64(3.0) * y * y - x * y
+    }
+    let x: Matrix<f64> = Matrix::new(vec![vec![1.0], vec![2.0]]);
+    let step = &hessian(quadratic, &x).inverse().unwrap() * &gradient(quadratic, &x);
+    let minimum = &x - &step;
+    assert!(minimum.iter().all(|(e, _, _)| e.abs() < 1e-12));
+  }
+}
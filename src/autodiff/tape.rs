@@ -0,0 +1,339 @@
+use crate::matrix::func::Func;
+use crate::matrix::num::Field;
+use crate::Matrix;
+use core::cell::RefCell;
+use core::ops::{Add, Mul, Rem, Sub};
+
+enum Op<T: Clone + PartialEq> {
+  Leaf,
+  MatMul(usize, usize),
+  Add(usize, usize),
+  Sub(usize, usize),
+  Hadamard(usize, usize),
+  Trans(usize),
+  // Holds the derivative of the function evaluated at the argument.
+  Apply(usize, Matrix<T>),
+  Sum(usize),
+  Mean(usize),
+}
+
+struct Node<T: Clone + PartialEq> {
+  value: Matrix<T>,
+  op: Op<T>,
+}
+
+/// Records matrix operations on `Var`s so that gradients can be computed by
+/// reverse-mode differentiation. Every operation appends a node; the tape
+/// therefore should be dropped or cleared after each `backward` pass.
+pub struct Tape<T: Clone + PartialEq> {
+  nodes: RefCell<Vec<Node<T>>>,
+}
+
+/// A matrix valued variable recorded on a `Tape`.
+#[derive(Clone, Copy)]
+pub struct Var<'t, T: Clone + PartialEq> {
+  tape: &'t Tape<T>,
+  index: usize,
+}
+
+/// The gradients computed by `Var::backward`.
+pub struct Gradients<T: Clone + PartialEq> {
+  grads: Vec<Option<Matrix<T>>>,
+  shapes: Vec<(usize, usize)>,
+}
+
+impl<T: Field> Default for Tape<T> {
+  fn default() -> Tape<T> {
+    Tape::new()
+  }
+}
+
+impl<T: Field> Tape<T> {
+  /// Creates an empty tape.
+  pub fn new() -> Tape<T> {
+    Tape {
+      nodes: RefCell::new(vec![]),
+    }
+  }
+
+  /// Records the leaf variable having the value `m`.
+  pub fn var(&self, m: Matrix<T>) -> Var<'_, T> {
+    self.push(m, Op::Leaf)
+  }
+
+  /// Removes all recorded nodes. As this borrows the tape mutably, no variable
+  /// recorded before can be used afterwards.
+  ///
+  /// ```compile_fail
+  /// use matrix_algebra::autodiff::Tape;
+  /// use matrix_algebra::Matrix;
+  ///
+  /// let mut tape = Tape::new();
+  /// let x = tape.var(Matrix::new(vec![vec![1.0]]));
+  /// tape.clear();
+  /// let y = x.sum();
+  /// ```
+  pub fn clear(&mut self) {
+    self.nodes.get_mut().clear();
+  }
+
+  fn push(&self, value: Matrix<T>, op: Op<T>) -> Var<'_, T> {
+    let mut nodes = self.nodes.borrow_mut();
+    nodes.push(Node { value, op });
+    Var {
+      tape: self,
+      index: nodes.len() - 1,
+    }
+  }
+
+  fn value(&self, index: usize) -> Matrix<T> {
+    self.nodes.borrow()[index].value.clone()
+  }
+}
+
+fn ones<T: Field>(n_rows: usize, n_cols: usize) -> Matrix<T> {
+  Matrix::create_from_data(vec![T::one(); n_rows * n_cols], n_rows, n_cols)
+}
+
+fn accumulate<T: Field>(grads: &mut [Option<Matrix<T>>], index: usize, g: Matrix<T>) {
+  grads[index] = match grads[index].take() {
+    Some(acc) => Some(acc + g),
+    None => Some(g),
+  };
+}
+
+impl<'t, T: Field> Var<'t, T> {
+  /// Returns the current value.
+  pub fn value(&self) -> Matrix<T> {
+    self.tape.value(self.index)
+  }
+
+  /// Records the transpose.
+  pub fn trans(&self) -> Var<'t, T> {
+    let value = self.value().trans();
+    self.tape.push(value, Op::Trans(self.index))
+  }
+
+  /// Records the element-wise application of `f`, whose derivative is `df`.
+  pub fn apply(&self, f: &Func<T>, df: &Func<T>) -> Var<'t, T> {
+    let x = self.value();
+    let value = f % &x;
+    self.tape.push(value, Op::Apply(self.index, df % &x))
+  }
+
+  /// Records the sum of all elements as a 1 x 1 matrix.
+  pub fn sum(&self) -> Var<'t, T> {
+    let x = self.value();
+    let s = x.iter().fold(T::zero(), |acc, (e, _, _)| acc + e.clone());
+    self.tape.push(Matrix::new(vec![vec![s]]), Op::Sum(self.index))
+  }
+
+  /// Records the mean of all elements as a 1 x 1 matrix.
+  pub fn mean(&self) -> Var<'t, T> {
+    let x = self.value();
+    let s = x.iter().fold(T::zero(), |acc, (e, _, _)| acc + e.clone());
+    let n = x.iter().fold(T::zero(), |acc, _| acc + T::one());
+    self.tape.push(Matrix::new(vec![vec![s / n]]), Op::Mean(self.index))
+  }
+
+  /// Computes the gradients of the sum of all elements of `self` with respect to
+  /// every variable recorded before it.
+  pub fn backward(&self) -> Gradients<T> {
+    let nodes = self.tape.nodes.borrow();
+    let mut grads: Vec<Option<Matrix<T>>> = vec![None; self.index + 1];
+    let value = &nodes[self.index].value;
+    grads[self.index] = Some(ones(value.n_rows(), value.n_cols()));
+    for i in (0..=self.index).rev() {
+      let g = match grads[i].take() {
+        Some(g) => g,
+        None => continue,
+      };
+      match &nodes[i].op {
+        Op::Leaf => {}
+        Op::MatMul(a, b) => {
+          accumulate(&mut grads, *a, &g * &nodes[*b].value.trans());
+          accumulate(&mut grads, *b, &nodes[*a].value.trans() * &g);
+        }
+        Op::Add(a, b) => {
+          accumulate(&mut grads, *a, g.clone());
+          accumulate(&mut grads, *b, g.clone());
+        }
+        Op::Sub(a, b) => {
+          accumulate(&mut grads, *a, g.clone());
+          accumulate(&mut grads, *b, -g.clone());
+        }
+        Op::Hadamard(a, b) => {
+          accumulate(&mut grads, *a, &g % &nodes[*b].value);
+          accumulate(&mut grads, *b, &g % &nodes[*a].value);
+        }
+        Op::Trans(a) => accumulate(&mut grads, *a, g.trans()),
+        Op::Apply(a, d) => accumulate(&mut grads, *a, &g % d),
+        Op::Sum(a) => {
+          let x = &nodes[*a].value;
+          accumulate(&mut grads, *a, ones(x.n_rows(), x.n_cols()) * g.get(0, 0));
+        }
+        Op::Mean(a) => {
+          let x = &nodes[*a].value;
+          let n = x.iter().fold(T::zero(), |acc, _| acc + T::one());
+          accumulate(&mut grads, *a, ones(x.n_rows(), x.n_cols()) * (g.get(0, 0) / n));
+        }
+      }
+      grads[i] = Some(g);
+    }
+    let shapes = nodes.iter().map(|n| (n.value.n_rows(), n.value.n_cols())).collect();
+    Gradients { grads, shapes }
+  }
+}
+
+impl<T: Field> Gradients<T> {
+  /// Returns the gradient with respect to `v`, which is zero if the result
+  /// does not depend on `v`.
+  pub fn wrt(&self, v: &Var<T>) -> Matrix<T> {
+    match self.grads.get(v.index) {
+      Some(Some(g)) => g.clone(),
+      _ => {
+        let (n_rows, n_cols) = self.shapes[v.index];
+        Matrix::zeros(n_rows, n_cols)
+      }
+    }
+  }
+}
+
+macro_rules! var_op {
+  ($Trait:ident, $method:ident, $Op:ident, $LHS:ty, $RHS:ty) => {
+    impl<'t, T: Field> $Trait<$RHS> for $LHS {
+      type Output = Var<'t, T>;
+      fn $method(self, rhs: $RHS) -> Self::Output {
+        if !core::ptr::eq(self.tape, rhs.tape) {
+          panic!("variables are recorded on different tapes!");
+        }
+        let value = (&self.value()).$method(&rhs.value());
+        return self.tape.push(value, Op::$Op(self.index, rhs.index));
+      }
+    }
+  };
+}
+var_op!(Mul, mul, MatMul, Var<'t, T>, Var<'t, T>);
+var_op!(Mul, mul, MatMul, &Var<'t, T>, Var<'t, T>);
+var_op!(Mul, mul, MatMul, &Var<'t, T>, &Var<'t, T>);
+var_op!(Mul, mul, MatMul, Var<'t, T>, &Var<'t, T>);
+var_op!(Add, add, Add, Var<'t, T>, Var<'t, T>);
+var_op!(Add, add, Add, &Var<'t, T>, Var<'t, T>);
+var_op!(Add, add, Add, &Var<'t, T>, &Var<'t, T>);
+var_op!(Add, add, Add, Var<'t, T>, &Var<'t, T>);
+var_op!(Sub, sub, Sub, Var<'t, T>, Var<'t, T>);
+var_op!(Sub, sub, Sub, &Var<'t, T>, Var<'t, T>);
+var_op!(Sub, sub, Sub, &Var<'t, T>, &Var<'t, T>);
+var_op!(Sub, sub, Sub, Var<'t, T>, &Var<'t, T>);
+var_op!(Rem, rem, Hadamard, Var<'t, T>, Var<'t, T>);
+var_op!(Rem, rem, Hadamard, &Var<'t, T>, Var<'t, T>);
+var_op!(Rem, rem, Hadamard, &Var<'t, T>, &Var<'t, T>);
+var_op!(Rem, rem, Hadamard, Var<'t, T>, &Var<'t, T>);
+
+#[cfg(test)]
+#[allow(clippy::op_ref)]
+mod tests {
+  use super::*;
+
+  fn sigmoid() -> (Func<f64>, Func<f64>) {
+    let f = Func::new(|x: f64| 1.0 / (1.0 + (-x).exp()));
+    let df = Func::new(|x: f64| {
+      let s = 1.0 / (1.0 + (-x).exp());
+      s * (1.0 - s)
+    });
+    (f, df)
+  }
+
+  #[test]
+  fn test_matmul_gradient() {
+    let tape = Tape::new();
+    let a = tape.var(Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]));
+    let x = tape.var(Matrix::new(vec![vec![5.0], vec![6.0]]));
+    let y = (&a * &x).sum();
+    assert_eq!(y.value(), Matrix::new(vec![vec![56.0]]));
+    let grads = y.backward();
+    assert_eq!(grads.wrt(&a), Matrix::new(vec![vec![5.0, 6.0], vec![5.0, 6.0]]));
+    assert_eq!(grads.wrt(&x), Matrix::new(vec![vec![4.0], vec![6.0]]));
+  }
+
+  #[test]
+  fn test_shared_variable() {
+    // d/dx sum(x % x + x^T^T) = 2 x + 1
+    let tape = Tape::new();
+    let x = tape.var(Matrix::new(vec![vec![1.0, -2.0, 3.0]]));
+    let y = (x % x + x.trans().trans()).sum();
+    let grads = y.backward();
+    assert_eq!(grads.wrt(&x), Matrix::new(vec![vec![3.0, -3.0, 7.0]]));
+  }
+
+  #[test]
+  fn test_mean_squared_error() {
+    let tape = Tape::new();
+    let w = tape.var(Matrix::new(vec![vec![0.5], vec![-1.0]]));
+    let x = tape.var(Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]));
+    let t = tape.var(Matrix::new(vec![vec![1.0], vec![0.0], vec![-1.0]]));
+    let r = &x * &w - t;
+    let loss = (r % r).mean();
+    let grads = loss.backward();
+    // The gradient of mean((X w - t)^2) is 2/n X^T (X w - t).
+    let residual = r.value();
+    let expected = &x.value().trans() * &residual * (2.0 / 3.0);
+    let g: Matrix<f64> = grads.wrt(&w);
+    assert!((&g - &expected).iter().all(|(e, _, _)| e.abs() < 1e-12));
+    assert_eq!(grads.wrt(&t), &residual * (-2.0 / 3.0));
+  }
+
+  #[test]
+  fn test_apply_against_finite_differences() {
+    let (f, df) = sigmoid();
+    let w0 = Matrix::new(vec![vec![0.3, -0.2], vec![0.1, 0.4]]);
+    let x0 = Matrix::new(vec![vec![1.0], vec![-1.5]]);
+    let loss = |w: &Matrix<f64>| {
+      let h = &f % &(w * &x0);
+      h.iter().fold(0.0, |acc, (e, _, _)| acc + e)
+    };
+    let tape = Tape::new();
+    let w = tape.var(w0.clone());
+    let x = tape.var(x0.clone());
+    let grads = (&w * &x).apply(&f, &df).sum().backward();
+    let g = grads.wrt(&w);
+    let h = 1e-6;
+    for i in 0..2 {
+      for j in 0..2 {
+        let (mut plus, mut minus) = (w0.clone(), w0.clone());
+        plus.set(i, j, w0.get(i, j) + h);
+        minus.set(i, j, w0.get(i, j) - h);
+        let numeric = (loss(&plus) - loss(&minus)) / (2.0 * h);
+        assert!((g.get(i, j) - numeric).abs() < 1e-8);
+      }
+    }
+  }
+
+  #[test]
+  fn test_unused_variable() {
+    let tape = Tape::new();
+    let a = tape.var(Matrix::new(vec![vec![1.0, 2.0]]));
+    let b = tape.var(Matrix::new(vec![vec![3.0]]));
+    let grads = a.mean().backward();
+    assert_eq!(grads.wrt(&a), Matrix::new(vec![vec![0.5, 0.5]]));
+    assert_eq!(grads.wrt(&b), Matrix::zeros(1, 1));
+  }
+
+  #[test]
+  fn test_clear() {
+    let mut tape = Tape::new();
+    let x = tape.var(Matrix::new(vec![vec![1.0, 2.0]]));
+    assert_eq!(x.sum().value(), Matrix::new(vec![vec![3.0]]));
+    tape.clear();
+    let y = tape.var(Matrix::new(vec![vec![4.0]]));
+    assert_eq!(y.index, 0);
+    assert_eq!(y.mean().backward().wrt(&y), Matrix::new(vec![vec![1.0]]));
+  }
+
+  #[test]
+  #[should_panic(expected = "different tapes")]
+  fn test_different_tapes() {
+    let (a, b) = (Tape::new(), Tape::new());
+    let _ = a.var(Matrix::new(vec![vec![1.0]])) + b.var(Matrix::new(vec![vec![2.0]]));
+  }
+}
@@ -0,0 +1,438 @@
+//! Linear time-invariant systems in state-space form
+//!
+//! `x' = A x + B u`, `y = C x + D u`
+//!
+//! in continuous time, or `x[k + 1] = A x[k] + B u[k]` in discrete time. States,
+//! inputs and outputs are column vectors, while signals over time are stored as
+//! matrices holding one time step per row.
+mod kalman;
+pub use kalman::{ExtendedKalmanFilter, KalmanFilter, UnscentedKalmanFilter};
+use crate::equations::{solve_care, solve_dare};
+use crate::matrix::num::Real;
+use crate::Matrix;
+
+/// A state-space model. `dt` is the sampling time of a discrete-time model and
+/// `None` for a continuous-time model. Only discrete-time models can be
+/// simulated; use `c2d` to discretize a continuous-time model first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateSpace<T: Real> {
+  pub a: Matrix<T>,
+  pub b: Matrix<T>,
+  pub c: Matrix<T>,
+  pub d: Matrix<T>,
+  pub dt: Option<T>,
+}
+
+impl<T: Real> StateSpace<T> {
+  /// Creates a continuous-time model.
+  pub fn new(a: Matrix<T>, b: Matrix<T>, c: Matrix<T>, d: Matrix<T>) -> StateSpace<T> {
+    let n = a.n_rows();
+    if a.n_cols() != n {
+      panic!("matrix must be square!");
+    }
+    if b.n_rows() != n || c.n_cols() != n || d.n_rows() != c.n_rows() || d.n_cols() != b.n_cols() {
+      panic!("dimensions do not match!");
+    }
+    StateSpace { a, b, c, d, dt: None }
+  }
+
+  /// Creates a discrete-time model with sampling time `dt`.
+  pub fn new_discrete(
+    a: Matrix<T>,
+    b: Matrix<T>,
+    c: Matrix<T>,
+    d: Matrix<T>,
+    dt: T,
+  ) -> StateSpace<T> {
+    StateSpace {
+      dt: Some(dt),
+      ..StateSpace::new(a, b, c, d)
+    }
+  }
+
+  pub fn n_states(&self) -> usize {
+    self.a.n_rows()
+  }
+
+  pub fn n_inputs(&self) -> usize {
+    self.b.n_cols()
+  }
+
+  pub fn n_outputs(&self) -> usize {
+    self.c.n_rows()
+  }
+
+  pub fn is_discrete(&self) -> bool {
+    self.dt.is_some()
+  }
+
+  /// Discretizes a continuous-time model with a zero-order hold on the input,
+  /// i.e. the input is constant between samples. The discrete matrices are read
+  /// off `expm([[A, B], [0, 0]] * dt) = [[Ad, Bd], [0, I]]`.
+  pub fn c2d(&self, dt: T) -> StateSpace<T> {
+    if self.is_discrete() {
+      panic!("model must be continuous!");
+    }
+    let (n, m) = (self.n_states(), self.n_inputs());
+    let mut augmented = Matrix::zeros(n + m, n + m);
+    for (e, i, j) in self.a.iter() {
+      augmented.set(i, j, *e * dt);
+    }
+    for (e, i, j) in self.b.iter() {
+      augmented.set(i, n + j, *e * dt);
+    }
+    let e = augmented.expm();
+    let mut ad = Matrix::zeros(n, n);
+    let mut bd = Matrix::zeros(n, m);
+    for i in 0..n {
+      for j in 0..n + m {
+        if j < n {
+          ad.set(i, j, e.get(i, j));
+        } else {
+          bd.set(i, j - n, e.get(i, j));
+        }
+      }
+    }
+    StateSpace::new_discrete(ad, bd, self.c.clone(), self.d.clone(), dt)
+  }
+
+  /// Simulates a discrete-time model from the initial state `x0` for the input
+  /// `u`, which holds one time step per row. Returns the outputs, again one time
+  /// step per row, or `None` for a continuous-time model, which has to be
+  /// discretized by `c2d` first.
+  pub fn simulate(&self, u: &Matrix<T>, x0: &Matrix<T>) -> Option<Matrix<T>> {
+    if !self.is_discrete() {
+      return None;
+    }
+    if u.n_cols() != self.n_inputs() || x0.n_rows() != self.n_states() || x0.n_cols() != 1 {
+      panic!("dimensions do not match!");
+    }
+    let mut y = Matrix::zeros(u.n_rows(), self.n_outputs());
+    let mut x = x0.clone();
+    for k in 0..u.n_rows() {
+      let u_k = u.get_row(k).trans();
+      let y_k = &(&self.c * &x) + &(&self.d * &u_k);
+      for i in 0..self.n_outputs() {
+        y.set(k, i, y_k.get(i, 0));
+      }
+      x = &(&self.a * &x) + &(&self.b * &u_k);
+    }
+    Some(y)
+  }
+
+  // Simulates from rest with each input channel driven by `signal` in turn.
+  fn channel_responses<F>(&self, steps: usize, signal: F) -> Option<Vec<Matrix<T>>>
+  where
+    F: Fn(usize) -> T,
+  {
+    if !self.is_discrete() {
+      return None;
+    }
+    let x0 = Matrix::zeros(self.n_states(), 1);
+    (0..self.n_inputs())
+      .map(|channel| {
+        let mut u = Matrix::zeros(steps, self.n_inputs());
+        for k in 0..steps {
+          u.set(k, channel, signal(k));
+        }
+        self.simulate(&u, &x0)
+      })
+      .collect()
+  }
+
+  /// Computes the response of a discrete-time model at rest to a unit step on each
+  /// input, returning one `steps` x `n_outputs` matrix per input. Returns `None`
+  /// for a continuous-time model.
+  pub fn step_response(&self, steps: usize) -> Option<Vec<Matrix<T>>> {
+    self.channel_responses(steps, |_| T::one())
+  }
+
+  /// Computes the response of a discrete-time model at rest to a unit pulse at time
+  /// zero on each input, returning one `steps` x `n_outputs` matrix per input.
+  /// Returns `None` for a continuous-time model.
+  pub fn impulse_response(&self, steps: usize) -> Option<Vec<Matrix<T>>> {
+    self.channel_responses(steps, |k| if k == 0 { T::one() } else { T::zero() })
+  }
+
+  /// Computes the controllability matrix `[B, A B, ..., A^(n-1) B]`.
+  pub fn controllability_matrix(&self) -> Matrix<T> {
+    let (n, m) = (self.n_states(), self.n_inputs());
+    let mut res = Matrix::zeros(n, n * m);
+    let mut block = self.b.clone();
+    for k in 0..n {
+      for (e, i, j) in block.iter() {
+        res.set(i, k * m + j, *e);
+      }
+      block = &self.a * &block;
+    }
+    res
+  }
+
+  /// Computes the observability matrix `[C; C A; ...; C A^(n-1)]`.
+  pub fn observability_matrix(&self) -> Matrix<T> {
+    let dual = StateSpace::new(
+      self.a.trans(),
+      self.c.trans(),
+      self.b.trans(),
+      self.d.trans(),
+    );
+    dual.controllability_matrix().trans()
+  }
+
+  /// Computes the rank of the controllability matrix. The rank is the number of
+  /// singular values above a tolerance relative to the largest one.
+  pub fn controllability_rank(&self) -> usize {
+    numerical_rank(&self.controllability_matrix())
+  }
+
+  /// Computes the rank of the observability matrix.
+  pub fn observability_rank(&self) -> usize {
+    numerical_rank(&self.observability_matrix())
+  }
+
+  pub fn is_controllable(&self) -> bool {
+    self.controllability_rank() == self.n_states()
+  }
+
+  pub fn is_observable(&self) -> bool {
+    self.observability_rank() == self.n_states()
+  }
+
+  /// Computes the gain `K` of the state feedback `u = -K x` that places the
+  /// eigenvalues of `A - B K` at `poles`, given as pairs of real and imaginary
+  /// parts, by Ackermann's formula. Complex poles must come in conjugate pairs.
+  /// Only single-input models are supported. Returns `None` if the model is not
+  /// controllable.
+  pub fn place_poles(&self, poles: &[(T, T)]) -> Option<Matrix<T>> {
+    let n = self.n_states();
+    if self.n_inputs() != 1 {
+      panic!("pole placement needs a single input!");
+    }
+    if poles.len() != n {
+      panic!("dimensions do not match!");
+    }
+    if n == 0 {
+      return Some(Matrix::zeros(1, 0));
+    }
+    // Multiplies out prod (x - p) in complex arithmetic, in ascending order.
+    let mut coeffs = vec![(T::one(), T::zero())];
+    for &(re, im) in poles {
+      let mut next = vec![(T::zero(), T::zero()); coeffs.len() + 1];
+      for (k, &(c_re, c_im)) in coeffs.iter().enumerate() {
+        next[k + 1] = (next[k + 1].0 + c_re, next[k + 1].1 + c_im);
+        next[k] = (next[k].0 - (re * c_re - im * c_im), next[k].1 - (re * c_im + im * c_re));
+      }
+      coeffs = next;
+    }
+    let coeffs = coeffs.iter().map(|c| c.0).collect::<Vec<T>>();
+    let w = self.controllability_matrix();
+    if numerical_rank(&w) < n {
+      return None;
+    }
+    let mut last = Matrix::zeros(1, n);
+    last.set(0, n - 1, T::one());
+    // The last row of W^-1 solves W^T z = e_n.
+    let z = w.trans().lu_solve(&last.trans())?;
+    Some(&z.trans() * &self.a.polyval(&coeffs))
+  }
+
+  /// Computes the gain `K` of the state feedback `u = -K x` minimizing the
+  /// quadratic cost with state weight `Q` and input weight `R`, from the
+  /// continuous or discrete algebraic Riccati equation.
+  pub fn lqr(&self, q: &Matrix<T>, r: &Matrix<T>) -> Option<Matrix<T>> {
+    if self.is_discrete() {
+      let x = solve_dare(&self.a, &self.b, q, r)?;
+      let bx = &self.b.trans() * &x;
+      (r + &(&bx * &self.b)).lu_solve(&(&bx * &self.a))
+    } else {
+      let x = solve_care(&self.a, &self.b, q, r)?;
+      r.lu_solve(&(&self.b.trans() * &x))
+    }
+  }
+
+  /// Computes the steady-state Kalman gain `L` for the process noise covariance
+  /// `Qn` (entering the state directly) and the measurement noise covariance
+  /// `Rn`. In continuous time the estimator is `x' = A x + B u + L (y - C x)`;
+  /// in discrete time `L` corrects the predicted state by the innovation,
+  /// `x = x_pred + L (y - C x_pred)`.
+  pub fn kalman_gain(&self, qn: &Matrix<T>, rn: &Matrix<T>) -> Option<Matrix<T>> {
+    let (at, ct) = (self.a.trans(), self.c.trans());
+    if self.is_discrete() {
+      let p = solve_dare(&at, &ct, qn, rn)?;
+      let pct = &p * &ct;
+      let s = rn + &(&self.c * &pct);
+      Some(s.trans().lu_solve(&pct.trans())?.trans())
+    } else {
+      let p = solve_care(&at, &ct, qn, rn)?;
+      Some(rn.lu_solve(&(&self.c * &p))?.trans())
+    }
+  }
+}
+
+// The number of singular values of `m` above `n * eps * sigma_max`.
+fn numerical_rank<T: Real>(m: &Matrix<T>) -> usize {
+  let values = singular_values(m);
+  let largest = values.iter().fold(T::zero(), |acc, &v| if v > acc { v } else { acc });
+  let size = m.n_rows().max(m.n_cols());
+  let tol = T::from_f64(size as f64) * T::epsilon() * largest;
+  values.iter().filter(|&&v| v > tol).count()
+}
+
+// Computes the singular values of `m` by one-sided Jacobi rotations, which make
+// the rows mutually orthogonal such that their norms are the singular values.
+// Unlike the eigenvalues of the Gram matrix `M M^T`, this keeps small singular
+// values accurate relative to the largest one.
+fn singular_values<T: Real>(m: &Matrix<T>) -> Vec<T> {
+  let mut rows = (0..m.n_rows()).map(|i| m.get_row(i)).collect::<Vec<Matrix<T>>>();
+  let dot = |x: &Matrix<T>, y: &Matrix<T>| x.iter().zip(y.iter()).fold(T::zero(), |acc, (a, b)| {
+    acc + *a.0 * *b.0
+  });
+  let two = T::one() + T::one();
+  for _ in 0..100 {
+    let mut rotated = false;
+    for p in 0..rows.len() {
+      for q in p + 1..rows.len() {
+        let (alpha, beta) = (dot(&rows[p], &rows[p]), dot(&rows[q], &rows[q]));
+        let gamma = dot(&rows[p], &rows[q]);
+        if gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+          continue;
+        }
+        rotated = true;
+        let zeta = (beta - alpha) / (two * gamma);
+        let t = T::one() / (zeta.abs() + (zeta * zeta + T::one()).sqrt());
+        let t = if zeta < T::zero() { -t } else { t };
+        let c = T::one() / (t * t + T::one()).sqrt();
+        let s = c * t;
+        let (x, y) = (rows[p].clone(), rows[q].clone());
+        rows[p] = &(&x * c) - &(&y * s);
+        rows[q] = &(&x * s) + &(&y * c);
+      }
+    }
+    if !rotated {
+      break;
+    }
+  }
+  rows.iter().map(|row| dot(row, row).sqrt()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>, tol: f64) {
+    assert!((a - b).iter().all(|(e, _, _)| e.abs() < tol), "{:?} != {:?}", a, b);
+  }
+
+  fn double_integrator() -> StateSpace<f64> {
+    StateSpace::new(
+      Matrix::new(vec![vec![0.0, 1.0], vec![0.0, 0.0]]),
+      Matrix::new(vec![vec![0.0], vec![1.0]]),
+      Matrix::new(vec![vec![1.0, 0.0]]),
+      Matrix::zeros(1, 1),
+    )
+  }
+
+  #[test]
+  fn test_c2d() {
+    let d = double_integrator().c2d(0.1);
+    assert_eq!(d.dt, Some(0.1));
+    assert_close(&d.a, &Matrix::new(vec![vec![1.0, 0.1], vec![0.0, 1.0]]), 1e-15);
+    assert_close(&d.b, &Matrix::new(vec![vec![0.005], vec![0.1]]), 1e-15);
+  }
+
+  #[test]
+  fn test_responses() {
+    // y[k] = x[k], x[k + 1] = 0.5 x[k] + u[k].
+    let one = |v: f64| Matrix::new(vec![vec![v]]);
+    let sys = StateSpace::new_discrete(one(0.5), one(1.0), one(1.0), one(0.0), 1.0);
+    let step = sys.step_response(4).unwrap();
+    assert_eq!(step.len(), 1);
+    assert_eq!(step[0], Matrix::new(vec![vec![0.0], vec![1.0], vec![1.5], vec![1.75]]));
+    let impulse = sys.impulse_response(4).unwrap();
+    assert_eq!(impulse[0], Matrix::new(vec![vec![0.0], vec![1.0], vec![0.5], vec![0.25]]));
+    let u = Matrix::new(vec![vec![0.0], vec![2.0], vec![0.0]]);
+    let y = sys.simulate(&u, &one(4.0)).unwrap();
+    assert_eq!(y, Matrix::new(vec![vec![4.0], vec![2.0], vec![3.0]]));
+    assert_eq!(double_integrator().step_response(4), None);
+    assert_eq!(double_integrator().impulse_response(4), None);
+    assert_eq!(double_integrator().simulate(&Matrix::zeros(3, 1), &Matrix::zeros(2, 1)), None);
+  }
+
+  #[test]
+  fn test_controllability_observability() {
+    let sys = double_integrator();
+    assert_eq!(sys.controllability_matrix(), Matrix::new(vec![vec![0.0, 1.0], vec![1.0, 0.0]]));
+    assert_eq!(sys.observability_matrix(), Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 1.0]]));
+    assert!(sys.is_controllable() && sys.is_observable());
+    // Measuring only the velocity loses the position.
+    let velocity = StateSpace {
+      c: Matrix::new(vec![vec![0.0, 1.0]]),
+      ..double_integrator()
+    };
+    assert_eq!(velocity.observability_rank(), 1);
+    assert!(!velocity.is_observable());
+  }
+
+  #[test]
+  fn test_place_poles() {
+    let sys = double_integrator();
+    let k = sys.place_poles(&[(-1.0, 0.0), (-2.0, 0.0)]).unwrap();
+    assert_close(&k, &Matrix::new(vec![vec![2.0, 3.0]]), 1e-12);
+    let k = sys.place_poles(&[(-1.0, 2.0), (-1.0, -2.0)]).unwrap();
+    let closed = &sys.a - &(&sys.b * &k);
+    for (re, im) in closed.eigenvalues().unwrap() {
+      assert!((re + 1.0).abs() < 1e-12 && (im.abs() - 2.0).abs() < 1e-12);
+    }
+    let uncontrollable = StateSpace {
+      b: Matrix::new(vec![vec![1.0], vec![0.0]]),
+      ..double_integrator()
+    };
+    assert_eq!(uncontrollable.place_poles(&[(-1.0, 0.0), (-2.0, 0.0)]), None);
+    let empty = StateSpace::new(
+      Matrix::<f64>::zeros(0, 0),
+      Matrix::zeros(0, 1),
+      Matrix::zeros(1, 0),
+      Matrix::zeros(1, 1),
+    );
+    assert_eq!(empty.place_poles(&[]), Some(Matrix::zeros(1, 0)));
+  }
+
+  #[test]
+  fn test_uncontrollable() {
+    // The third mode is not driven by the input, and a Householder reflection
+    // hides this structure in every entry.
+    let q = &Matrix::new(vec![vec![7.0, -4.0, -4.0], vec![-4.0, 1.0, -8.0], vec![-4.0, -8.0, 1.0]])
+      * (1.0 / 9.0);
+    let a0 = Matrix::new(vec![vec![0.3, 1.3, 0.0], vec![0.0, -2.5, 0.0], vec![0.0, 0.0, 4.0]]);
+    let b0 = Matrix::new(vec![vec![1.0], vec![1.0], vec![0.0]]);
+    let sys = StateSpace::new(&(&q * &a0) * &q, &q * &b0, Matrix::identity(3), Matrix::zeros(3, 1));
+    assert_eq!(sys.controllability_rank(), 2);
+    assert!(!sys.is_controllable());
+    assert_eq!(sys.place_poles(&[(-1.0, 0.0), (-2.0, 0.0), (-3.0, 0.0)]), None);
+  }
+
+  #[test]
+  fn test_lqr() {
+    let sys = double_integrator();
+    let (q, r) = (Matrix::identity(2), Matrix::identity(1));
+    let k = sys.lqr(&q, &r).unwrap();
+    assert_close(&k, &Matrix::new(vec![vec![1.0, 3f64.sqrt()]]), 1e-9);
+    let discrete = sys.c2d(0.1);
+    let k = discrete.lqr(&q, &r).unwrap();
+    let closed = &discrete.a - &(&discrete.b * &k);
+    assert!(closed.eigenvalues().unwrap().iter().all(|v| v.0.hypot(v.1) < 1.0));
+  }
+
+  #[test]
+  fn test_kalman_gain() {
+    let sys = double_integrator();
+    let (qn, rn) = (Matrix::identity(2), Matrix::identity(1));
+    let l = sys.kalman_gain(&qn, &rn).unwrap();
+    // By duality the gain is the transposed LQR gain of (A^T, C^T).
+    assert_close(&l, &Matrix::new(vec![vec![3f64.sqrt()], vec![1.0]]), 1e-9);
+    let discrete = sys.c2d(0.1);
+    let l = discrete.kalman_gain(&qn, &rn).unwrap();
+    let closed = &discrete.a - &(&(&discrete.a * &l) * &discrete.c);
+    assert!(closed.eigenvalues().unwrap().iter().all(|v| v.0.hypot(v.1) < 1.0));
+  }
+}
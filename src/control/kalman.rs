@@ -0,0 +1,442 @@
+use crate::matrix::num::Real;
+use crate::Matrix;
+
+// The Kalman gain K = P H^T S^-1 for the innovation covariance S = H P H^T + R,
+// or `None` if S is singular.
+fn gain<T: Real>(p_ht: &Matrix<T>, s: &Matrix<T>) -> Option<Matrix<T>> {
+  s.trans().lu_solve(&p_ht.trans()).map(|k| k.trans())
+}
+
+// Updates the covariance in Joseph form, (I - K H) P (I - K H)^T + K R K^T, which
+// keeps it positive semidefinite despite rounding errors.
+fn joseph<T: Real>(p: &Matrix<T>, k: &Matrix<T>, h: &Matrix<T>, r: &Matrix<T>) -> Matrix<T> {
+  let i_kh = &Matrix::identity(p.n_rows()) - &(k * h);
+  &(&(&i_kh * p) * &i_kh.trans()) + &(&(k * r) * &k.trans())
+}
+
+/// The linear Kalman filter for `x[k + 1] = F x[k] + w` and `z[k] = H x[k] + v`,
+/// with process noise `w` of covariance `Q` and measurement noise `v` of
+/// covariance `R`. The state estimate `x` is a column vector with covariance `p`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KalmanFilter<T: Real> {
+  pub x: Matrix<T>,
+  pub p: Matrix<T>,
+  pub f: Matrix<T>,
+  pub h: Matrix<T>,
+  pub q: Matrix<T>,
+  pub r: Matrix<T>,
+}
+
+impl<T: Real> KalmanFilter<T> {
+  pub fn new(
+    x: Matrix<T>,
+    p: Matrix<T>,
+    f: Matrix<T>,
+    h: Matrix<T>,
+    q: Matrix<T>,
+    r: Matrix<T>,
+  ) -> KalmanFilter<T> {
+    let (n, m) = (x.n_rows(), h.n_rows());
+    if x.n_cols() != 1
+      || p.n_rows() != n
+      || p.n_cols() != n
+      || f.n_rows() != n
+      || f.n_cols() != n
+      || h.n_cols() != n
+      || q.n_rows() != n
+      || q.n_cols() != n
+      || r.n_rows() != m
+      || r.n_cols() != m
+    {
+      panic!("dimensions do not match!");
+    }
+    KalmanFilter { x, p, f, h, q, r }
+  }
+
+  /// Propagates the estimate one time step through the dynamics.
+  pub fn predict(&mut self) {
+    self.x = &self.f * &self.x;
+    self.p = &(&(&self.f * &self.p) * &self.f.trans()) + &self.q;
+  }
+
+  /// Corrects the estimate by the measurement `z` and returns the innovation
+  /// `z - H x` of the predicted state. Returns `None`, leaving the estimate
+  /// unchanged, if the innovation covariance is singular.
+  pub fn update(&mut self, z: &Matrix<T>) -> Option<Matrix<T>> {
+    let y = z - &(&self.h * &self.x);
+    let p_ht = &self.p * &self.h.trans();
+    let s = &(&self.h * &p_ht) + &self.r;
+    let k = gain(&p_ht, &s)?;
+    self.x = &self.x + &(&k * &y);
+    self.p = joseph(&self.p, &k, &self.h, &self.r);
+    Some(y)
+  }
+
+  /// Runs `predict` and `update` for each measurement in turn and returns the
+  /// filtered estimates as pairs of state and covariance, or `None` if an
+  /// update fails.
+  pub fn filter(&mut self, measurements: &[Matrix<T>]) -> Option<Vec<(Matrix<T>, Matrix<T>)>> {
+    let mut estimates = Vec::with_capacity(measurements.len());
+    for z in measurements {
+      self.predict();
+      self.update(z)?;
+      estimates.push((self.x.clone(), self.p.clone()));
+    }
+    Some(estimates)
+  }
+
+  /// Smooths filtered estimates, as returned by `filter`, by the
+  /// Rauch–Tung–Striebel backward pass, so that every estimate is conditioned on
+  /// all measurements. Returns `None` if a predicted covariance is singular.
+  pub fn rts_smooth(
+    &self,
+    filtered: &[(Matrix<T>, Matrix<T>)],
+  ) -> Option<Vec<(Matrix<T>, Matrix<T>)>> {
+    let mut smoothed = filtered.to_vec();
+    for k in (0..filtered.len().saturating_sub(1)).rev() {
+      let (x, p) = &filtered[k];
+      let x_pred = &self.f * x;
+      let p_pred = &(&(&self.f * p) * &self.f.trans()) + &self.q;
+      // The smoother gain C = P F^T P_pred^-1.
+      let c = gain(&(p * &self.f.trans()), &p_pred)?;
+      let (x_next, p_next) = &smoothed[k + 1];
+      let x_smooth = x + &(&c * &(x_next - &x_pred));
+      let p_smooth = p + &(&(&c * &(p_next - &p_pred)) * &c.trans());
+      smoothed[k] = (x_smooth, p_smooth);
+    }
+    Some(smoothed)
+  }
+}
+
+/// The extended Kalman filter for nonlinear dynamics `x[k + 1] = f(x[k]) + w` and
+/// measurements `z[k] = h(x[k]) + v`. The models and their Jacobians are passed as
+/// closures; the Jacobians can be obtained from `autodiff::jacobian`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedKalmanFilter<T: Real> {
+  pub x: Matrix<T>,
+  pub p: Matrix<T>,
+  pub q: Matrix<T>,
+  pub r: Matrix<T>,
+}
+
+impl<T: Real> ExtendedKalmanFilter<T> {
+  pub fn new(x: Matrix<T>, p: Matrix<T>, q: Matrix<T>, r: Matrix<T>) -> ExtendedKalmanFilter<T> {
+    check_dimensions(&x, &p, &q, &r);
+    ExtendedKalmanFilter { x, p, q, r }
+  }
+
+  /// Propagates the estimate through the dynamics `f`, linearized by its
+  /// Jacobian `df` at the current estimate.
+  pub fn predict<F, J>(&mut self, f: F, df: J)
+  where
+    F: Fn(&Matrix<T>) -> Matrix<T>,
+    J: Fn(&Matrix<T>) -> Matrix<T>,
+  {
+    let jf = df(&self.x);
+    self.x = f(&self.x);
+    self.p = &(&(&jf * &self.p) * &jf.trans()) + &self.q;
+  }
+
+  /// Corrects the estimate by the measurement `z` of the model `h` with Jacobian
+  /// `dh` and returns the innovation `z - h(x)`. Returns `None`, leaving the
+  /// estimate unchanged, if the innovation covariance is singular.
+  pub fn update<H, J>(&mut self, z: &Matrix<T>, h: H, dh: J) -> Option<Matrix<T>>
+  where
+    H: Fn(&Matrix<T>) -> Matrix<T>,
+    J: Fn(&Matrix<T>) -> Matrix<T>,
+  {
+    let jh = dh(&self.x);
+    let y = z - &h(&self.x);
+    let p_ht = &self.p * &jh.trans();
+    let s = &(&jh * &p_ht) + &self.r;
+    let k = gain(&p_ht, &s)?;
+    self.x = &self.x + &(&k * &y);
+    self.p = joseph(&self.p, &k, &jh, &self.r);
+    Some(y)
+  }
+}
+
+// Checks that `p` and `q` match the state `x` and that `r` is square; the
+// measurement size is only known once the measurement model is applied.
+fn check_dimensions<T: Real>(x: &Matrix<T>, p: &Matrix<T>, q: &Matrix<T>, r: &Matrix<T>) {
+  let n = x.n_rows();
+  if x.n_cols() != 1
+    || p.n_rows() != n
+    || p.n_cols() != n
+    || q.n_rows() != n
+    || q.n_cols() != n
+    || r.n_rows() != r.n_cols()
+  {
+    panic!("dimensions do not match!");
+  }
+}
+
+// Sigma points with their weights for the mean and the covariance.
+type SigmaPoints<T> = (Vec<Matrix<T>>, Vec<T>, Vec<T>);
+
+/// The unscented Kalman filter for the same models as `ExtendedKalmanFilter`. It
+/// propagates `2n + 1` sigma points through the models instead of linearizing
+/// them, so no Jacobians are needed. The spread of the sigma points is set by
+/// `alpha`, `beta` and `kappa` of the scaled unscented transform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnscentedKalmanFilter<T: Real> {
+  pub x: Matrix<T>,
+  pub p: Matrix<T>,
+  pub q: Matrix<T>,
+  pub r: Matrix<T>,
+  pub alpha: T,
+  pub beta: T,
+  pub kappa: T,
+}
+
+impl<T: Real> UnscentedKalmanFilter<T> {
+  /// Creates the filter with the usual parameters `alpha = 1e-3`, `beta = 2`
+  /// (optimal for Gaussian distributions) and `kappa = 0`.
+  pub fn new(x: Matrix<T>, p: Matrix<T>, q: Matrix<T>, r: Matrix<T>) -> UnscentedKalmanFilter<T> {
+    check_dimensions(&x, &p, &q, &r);
+    UnscentedKalmanFilter {
+      x,
+      p,
+      q,
+      r,
+      alpha: T::from_f64(1e-3),
+      beta: T::from_f64(2.0),
+      kappa: T::zero(),
+    }
+  }
+
+  // Returns the sigma points of the current estimate with their weights for the
+  // mean and the covariance, or `None` if the covariance is not positive definite.
+  fn sigma_points(&self) -> Option<SigmaPoints<T>> {
+    let n = self.x.n_rows();
+    let n_t = T::from_f64(n as f64);
+    let lambda = self.alpha * self.alpha * (n_t + self.kappa) - n_t;
+    let l = (&self.p * (n_t + lambda)).cholesky()?;
+    let mut points = vec![self.x.clone()];
+    for sign in [T::one(), -T::one()] {
+      for j in 0..n {
+        points.push(&self.x + &(&l.get_col(j) * sign));
+      }
+    }
+    let w = T::one() / (T::from_f64(2.0) * (n_t + lambda));
+    let mut wm = vec![w; 2 * n + 1];
+    let mut wc = wm.clone();
+    wm[0] = lambda / (n_t + lambda);
+    wc[0] = wm[0] + T::one() - self.alpha * self.alpha + self.beta;
+    Some((points, wm, wc))
+  }
+
+  /// Propagates the estimate through the dynamics `f`. Returns `None`, leaving the
+  /// estimate unchanged, if the covariance is not positive definite.
+  pub fn predict<F: Fn(&Matrix<T>) -> Matrix<T>>(&mut self, f: F) -> Option<()> {
+    let (points, wm, wc) = self.sigma_points()?;
+    let points = points.iter().map(f).collect::<Vec<Matrix<T>>>();
+    let (x, p) = unscented_moments(&points, &wm, &wc);
+    self.x = x;
+    self.p = &p + &self.q;
+    Some(())
+  }
+
+  /// Corrects the estimate by the measurement `z` of the model `h` and returns the
+  /// innovation `z - z_pred`. Returns `None`, leaving the estimate unchanged, if
+  /// the covariance is not positive definite or the innovation covariance is
+  /// singular.
+  pub fn update<H>(&mut self, z: &Matrix<T>, h: H) -> Option<Matrix<T>>
+  where
+    H: Fn(&Matrix<T>) -> Matrix<T>,
+  {
+    let (points, wm, wc) = self.sigma_points()?;
+    let measured = points.iter().map(h).collect::<Vec<Matrix<T>>>();
+    let (z_pred, s) = unscented_moments(&measured, &wm, &wc);
+    let s = &s + &self.r;
+    let mut cross = Matrix::zeros(self.x.n_rows(), z.n_rows());
+    for ((point, m), &w) in points.iter().zip(measured.iter()).zip(wc.iter()) {
+      let dx = point - &self.x;
+      let dz = m - &z_pred;
+      cross = &cross + &(&(&dx * &dz.trans()) * w);
+    }
+    let k = gain(&cross, &s)?;
+    let y = z - &z_pred;
+    self.x = &self.x + &(&k * &y);
+    // P - K S K^T loses its symmetry to rounding, which the Cholesky factorization
+    // of the next sigma points relies on.
+    let p = &self.p - &(&(&k * &s) * &k.trans());
+    self.p = &(&p + &p.trans()) * T::from_f64(0.5);
+    Some(y)
+  }
+}
+
+// Computes the weighted mean and covariance of transformed sigma points.
+fn unscented_moments<T: Real>(points: &[Matrix<T>], wm: &[T], wc: &[T]) -> (Matrix<T>, Matrix<T>) {
+  let d = points[0].n_rows();
+  let mut mean = Matrix::zeros(d, 1);
+  for (point, &w) in points.iter().zip(wm.iter()) {
+    mean = &mean + &(point * w);
+  }
+  let mut cov = Matrix::zeros(d, d);
+  for (point, &w) in points.iter().zip(wc.iter()) {
+    let diff = point - &mean;
+    cov = &cov + &(&(&diff * &diff.trans()) * w);
+  }
+  (mean, cov)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::autodiff::jacobian;
+  use crate::nn::Rng;
+
+  fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>, tol: f64) {
+    assert!((a - b).iter().all(|(e, _, _)| e.abs() < tol), "{:?} != {:?}", a, b);
+  }
+
+  // A constant velocity target observed by noisy position measurements.
+  fn tracker() -> KalmanFilter<f64> {
+    let dt = 0.1;
+    KalmanFilter::new(
+      Matrix::zeros(2, 1),
+      &Matrix::identity(2) * 10.0,
+      Matrix::new(vec![vec![1.0, dt], vec![0.0, 1.0]]),
+      Matrix::new(vec![vec![1.0, 0.0]]),
+      &Matrix::identity(2) * 1e-4,
+      Matrix::new(vec![vec![0.25]]),
+    )
+  }
+
+  fn measurements(rng: &mut Rng, steps: usize) -> (Vec<f64>, Vec<Matrix<f64>>) {
+    let truth = (1..=steps).map(|k| 1.0 + 2.0 * 0.1 * k as f64).collect::<Vec<f64>>();
+    let z = truth.iter().map(|&t| Matrix::new(vec![vec![t + 0.5 * rng.normal()]]));
+    (truth.clone(), z.collect())
+  }
+
+  #[test]
+  fn test_kalman_filter() {
+    let mut rng = Rng::new(3);
+    let (truth, z) = measurements(&mut rng, 200);
+    let mut kf = tracker();
+    let estimates = kf.filter(&z).unwrap();
+    let (x, p) = estimates.last().unwrap();
+    assert!((x.get(0, 0) - truth[199]).abs() < 0.3);
+    assert!((x.get(1, 0) - 2.0).abs() < 0.3);
+    assert!(p.cholesky().is_some());
+    // A single step by hand: the gain is P H^T / (H P H^T + R).
+    let mut kf = tracker();
+    kf.predict();
+    let innovation = kf.update(&Matrix::new(vec![vec![1.0]])).unwrap();
+    assert_eq!(innovation, Matrix::new(vec![vec![1.0]]));
+    let k0 = 10.1001 / (10.1001 + 0.25);
+    assert!((kf.x.get(0, 0) - k0).abs() < 1e-12);
+  }
+
+  #[test]
+  fn test_rts_smooth() {
+    let mut rng = Rng::new(4);
+    let (truth, z) = measurements(&mut rng, 100);
+    let mut kf = tracker();
+    let filtered = kf.filter(&z).unwrap();
+    let smoothed = kf.rts_smooth(&filtered).unwrap();
+    assert_eq!(smoothed.last(), filtered.last());
+    let error = |e: &[(Matrix<f64>, Matrix<f64>)]| {
+      (10..100).map(|k| (e[k].0.get(0, 0) - truth[k]).powi(2)).sum::<f64>()
+    };
+    assert!(error(&smoothed) < error(&filtered));
+    assert!(smoothed[50].1.get(0, 0) < filtered[50].1.get(0, 0));
+  }
+
+  #[test]
+  fn test_extended_kalman_filter() {
+    // On a linear model the extended filter agrees with the linear one.
+    let mut kf = tracker();
+    let mut ekf = ExtendedKalmanFilter::new(kf.x.clone(), kf.p.clone(), kf.q.clone(), kf.r.clone());
+    let (f, h) = (kf.f.clone(), kf.h.clone());
+    let z = Matrix::new(vec![vec![0.7]]);
+    kf.predict();
+    kf.update(&z).unwrap();
+    ekf.predict(|x| &f * x, |_| f.clone());
+    ekf.update(&z, |x| &h * x, |_| h.clone()).unwrap();
+    assert_close(&ekf.x, &kf.x, 1e-12);
+    assert_close(&ekf.p, &kf.p, 1e-12);
+  }
+
+  #[test]
+  fn test_range_tracking() {
+    // A static target at (3, 4) observed by its distances to beacons at (0, 0)
+    // and (10, 0).
+    fn observe<T: Real>(x: &Matrix<T>) -> Matrix<T> {
+      let (px, py) = (x.get(0, 0), x.get(1, 0));
+      let dx = px - T::from_f64(10.0);
+      Matrix::new(vec![vec![(px * px + py * py).sqrt()], vec![(dx * dx + py * py).sqrt()]])
+    }
+    let mut rng = Rng::new(9);
+    let target = Matrix::new(vec![vec![3.0], vec![4.0]]);
+    let x0 = Matrix::new(vec![vec![2.0], vec![5.0]]);
+    let (q, r) = (&Matrix::identity(2) * 1e-6, &Matrix::identity(2) * 1e-2);
+    let mut ekf = ExtendedKalmanFilter::new(x0.clone(), Matrix::identity(2), q.clone(), r.clone());
+    let mut ukf = UnscentedKalmanFilter::new(x0, Matrix::identity(2), q, r);
+    for _ in 0..50 {
+      let noise = Matrix::new(vec![vec![0.1 * rng.normal()], vec![0.1 * rng.normal()]]);
+      let z = &observe(&target) + &noise;
+      ekf.predict(|x| x.clone(), |_| Matrix::identity(2));
+      ekf.update(&z, observe, |x| jacobian(observe, x)).unwrap();
+      ukf.predict(|x| x.clone()).unwrap();
+      ukf.update(&z, observe).unwrap();
+      assert_eq!(ukf.p, ukf.p.trans());
+    }
+    assert_close(&ekf.x, &target, 0.05);
+    assert_close(&ukf.x, &target, 0.05);
+  }
+
+  #[test]
+  fn test_unscented_transform() {
+    // The unscented transform is exact for linear models.
+    let mut ukf = UnscentedKalmanFilter::new(
+      Matrix::new(vec![vec![1.0], vec![-1.0]]),
+      Matrix::new(vec![vec![2.0, 0.5], vec![0.5, 1.0]]),
+      Matrix::zeros(2, 2),
+      Matrix::identity(1),
+    );
+    let f = Matrix::new(vec![vec![1.0, 0.1], vec![0.0, 1.0]]);
+    let expected = &(&f * &ukf.p) * &f.trans();
+    ukf.predict(|x| &f * x).unwrap();
+    assert_close(&ukf.x, &Matrix::new(vec![vec![0.9], vec![-1.0]]), 1e-9);
+    assert_close(&ukf.p, &expected, 1e-9);
+  }
+
+  #[test]
+  fn test_degenerate_covariance() {
+    // Without any noise, a measurement of nothing has a zero innovation
+    // covariance and a zero covariance predicts to a zero covariance.
+    let mut kf = tracker();
+    kf.h = Matrix::zeros(1, 2);
+    kf.q = Matrix::zeros(2, 2);
+    kf.r = Matrix::zeros(1, 1);
+    let (x, z) = (kf.x.clone(), Matrix::new(vec![vec![1.0]]));
+    assert_eq!(kf.update(&z), None);
+    assert_eq!(kf.x, x);
+    assert_eq!(kf.rts_smooth(&[(x.clone(), Matrix::zeros(2, 2)), (x.clone(), kf.q.clone())]), None);
+    let mut ekf = ExtendedKalmanFilter::new(x.clone(), kf.p.clone(), kf.q.clone(), kf.r.clone());
+    assert_eq!(ekf.update(&z, |x| &kf.h * x, |_| kf.h.clone()), None);
+    let mut ukf = UnscentedKalmanFilter::new(x, -&Matrix::identity(2), kf.q, kf.r);
+    assert_eq!(ukf.predict(|x| x.clone()), None);
+    assert_eq!(ukf.update(&z, |x| x.clone()), None);
+  }
+
+  #[test]
+  #[should_panic(expected = "dimensions do not match!")]
+  fn test_measurement_noise_dimensions() {
+    let kf = tracker();
+    KalmanFilter::new(kf.x, kf.p, kf.f, kf.h, kf.q, Matrix::identity(2));
+  }
+
+  #[test]
+  #[should_panic(expected = "dimensions do not match!")]
+  fn test_process_noise_dimensions() {
+    ExtendedKalmanFilter::new(
+      Matrix::<f64>::zeros(2, 1),
+      Matrix::identity(2),
+      Matrix::zeros(2, 1),
+      Matrix::identity(1),
+    );
+  }
+}
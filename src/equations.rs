@@ -0,0 +1,334 @@
+//! Solvers for the linear and quadratic matrix equations of control theory: the
+//! Sylvester and Lyapunov equations and the continuous and discrete algebraic
+//! Riccati equations. The solvers return `None` if the equation has no unique
+//! solution or an iteration does not converge.
+use crate::matrix::num::Real;
+use crate::Matrix;
+
+const MAX_ITERATIONS: usize = 100;
+
+fn symmetrize<T: Real>(a: &Matrix<T>) -> Matrix<T> {
+  &(a + &a.trans()) * T::from_f64(0.5)
+}
+
+fn sub_matrix<T: Real>(a: &Matrix<T>, rows: (usize, usize), cols: (usize, usize)) -> Matrix<T> {
+  let mut res = Matrix::zeros(rows.1, cols.1);
+  for i in 0..rows.1 {
+    for j in 0..cols.1 {
+      res.set(i, j, a.get(rows.0 + i, cols.0 + j));
+    }
+  }
+  res
+}
+
+// Solves `A X = B` for a matrix `A` with at least as many rows as columns in the
+// least squares sense. `A` is reduced to upper triangular form by Householder
+// reflections, which avoids squaring the condition number as the normal
+// equations do. Returns `None` if `A` does not have full column rank.
+fn least_squares<T: Real>(a: &Matrix<T>, b: &Matrix<T>) -> Option<Matrix<T>> {
+  let (m, n) = (a.n_rows(), a.n_cols());
+  let mut r = a.clone();
+  let mut y = b.clone();
+  for k in 0..n {
+    let norm = (k..m).fold(T::zero(), |acc, i| acc + r.get(i, k) * r.get(i, k)).sqrt();
+    if norm.is_zero() {
+      return None;
+    }
+    let alpha = if r.get(k, k) > T::zero() { -norm } else { norm };
+    let mut v = (k..m).map(|i| r.get(i, k)).collect::<Vec<T>>();
+    v[0] = v[0] - alpha;
+    let vv = v.iter().fold(T::zero(), |acc, &x| acc + x * x);
+    for target in [&mut r, &mut y] {
+      for j in 0..target.n_cols() {
+        let dot = (0..v.len()).fold(T::zero(), |acc, i| acc + v[i] * target.get(k + i, j));
+        let f = (dot + dot) / vv;
+        for (i, &x) in v.iter().enumerate() {
+          target.set(k + i, j, target.get(k + i, j) - f * x);
+        }
+      }
+    }
+  }
+  sub_matrix(&r, (0, n), (0, n)).lu_solve(&sub_matrix(&y, (0, n), (0, b.n_cols())))
+}
+
+// Assembles the 2 x 2 block matrix [[a, b], [c, d]].
+fn block_matrix<T: Real>(a: &Matrix<T>, b: &Matrix<T>, c: &Matrix<T>, d: &Matrix<T>) -> Matrix<T> {
+  let (n, m) = (a.n_rows() + c.n_rows(), a.n_cols() + b.n_cols());
+  let mut res = Matrix::zeros(n, m);
+  for (block, r, s) in [(a, 0, 0), (b, 0, a.n_cols()), (c, a.n_rows(), 0)] {
+    for (e, i, j) in block.iter() {
+      res.set(r + i, s + j, *e);
+    }
+  }
+  for (e, i, j) in d.iter() {
+    res.set(a.n_rows() + i, a.n_cols() + j, *e);
+  }
+  res
+}
+
+// Splits the diagonal of a quasi-triangular matrix into its 1 x 1 and 2 x 2
+// blocks, given as pairs of the first index and the size.
+fn diagonal_blocks<T: Real>(s: &Matrix<T>) -> Vec<(usize, usize)> {
+  let n = s.n_rows();
+  let mut blocks = vec![];
+  let mut i = 0;
+  while i < n {
+    let size = if i + 1 < n && !s.get(i + 1, i).is_zero() { 2 } else { 1 };
+    blocks.push((i, size));
+    i += size;
+  }
+  blocks
+}
+
+/// Solves the Sylvester equation `A X + X B = C` by the Bartels–Stewart
+/// algorithm: `A` and `B` are reduced to real Schur form, and the transformed
+/// equation is solved block by block. A unique solution exists if `A` and `-B`
+/// have no eigenvalue in common.
+pub fn solve_sylvester<T: Real>(a: &Matrix<T>, b: &Matrix<T>, c: &Matrix<T>) -> Option<Matrix<T>> {
+  let (m, n) = (a.check_square(), b.check_square());
+  if c.n_rows() != m || c.n_cols() != n {
+    panic!("dimensions do not match!");
+  }
+  let (u, s) = a.real_schur()?;
+  let (v, t) = b.real_schur()?;
+  let f = &(&u.trans() * c) * &v;
+  let mut y = Matrix::zeros(m, n);
+  let row_blocks = diagonal_blocks(&s);
+  for &(c0, q) in &diagonal_blocks(&t) {
+    for &(r0, p) in row_blocks.iter().rev() {
+      // S_ii Y_ij + Y_ij T_jj = F_ij - sum_{k > i} S_ik Y_kj - sum_{l < j} Y_il T_lj,
+      // written as a linear system of size p * q for the row-major entries of Y_ij.
+      let mut rhs = Matrix::zeros(p * q, 1);
+      for di in 0..p {
+        for dj in 0..q {
+          let (i, j) = (r0 + di, c0 + dj);
+          let mut r = f.get(i, j);
+          for k in r0 + p..m {
+            r = r - s.get(i, k) * y.get(k, j);
+          }
+          for l in 0..c0 {
+            r = r - y.get(i, l) * t.get(l, j);
+          }
+          rhs.set(di * q + dj, 0, r);
+        }
+      }
+      let mut system = Matrix::zeros(p * q, p * q);
+      for di in 0..p {
+        for dj in 0..q {
+          let row = di * q + dj;
+          for k in 0..p {
+            let e = system.get(row, k * q + dj) + s.get(r0 + di, r0 + k);
+            system.set(row, k * q + dj, e);
+          }
+          for l in 0..q {
+            let e = system.get(row, di * q + l) + t.get(c0 + l, c0 + dj);
+            system.set(row, di * q + l, e);
+          }
+        }
+      }
+      let block = system.lu_solve(&rhs)?;
+      for di in 0..p {
+        for dj in 0..q {
+          y.set(r0 + di, c0 + dj, block.get(di * q + dj, 0));
+        }
+      }
+    }
+  }
+  Some(&(&u * &y) * &v.trans())
+}
+
+/// Solves the continuous Lyapunov equation `A X + X A^T + Q = 0`. For a stable
+/// `A` and positive semidefinite `Q`, e.g. `Q = B B^T`, the solution is the
+/// controllability Gramian.
+pub fn solve_lyapunov<T: Real>(a: &Matrix<T>, q: &Matrix<T>) -> Option<Matrix<T>> {
+  solve_sylvester(a, &a.trans(), &-q).map(|x| symmetrize(&x))
+}
+
+/// Solves the discrete Lyapunov (Stein) equation `A X A^T - X + Q = 0` by the
+/// doubling iteration summing `X = sum_k A^k Q (A^T)^k`. This requires the
+/// spectral radius of `A` to be less than one; otherwise `None` is returned.
+pub fn solve_discrete_lyapunov<T: Real>(a: &Matrix<T>, q: &Matrix<T>) -> Option<Matrix<T>> {
+  let n = a.check_square();
+  if q.n_rows() != n || q.n_cols() != n {
+    panic!("dimensions do not match!");
+  }
+  let mut x = q.clone();
+  let mut a_k = a.clone();
+  for _ in 0..MAX_ITERATIONS {
+    let increment = &(&a_k * &x) * &a_k.trans();
+    x = &x + &increment;
+    a_k = &a_k * &a_k;
+    if !x.norm_1().to_f64().is_finite() {
+      return None;
+    }
+    if increment.norm_1() <= T::epsilon() * x.norm_1() {
+      return Some(symmetrize(&x));
+    }
+  }
+  None
+}
+
+/// Solves the continuous algebraic Riccati equation
+/// `A^T X + X A - X B R^-1 B^T X + Q = 0` for the stabilizing solution, which
+/// yields the LQR gain `K = R^-1 B^T X`. The stable invariant subspace of the
+/// Hamiltonian matrix is obtained from its matrix sign function, computed by the
+/// scaled Newton iteration.
+pub fn solve_care<T: Real>(
+  a: &Matrix<T>,
+  b: &Matrix<T>,
+  q: &Matrix<T>,
+  r: &Matrix<T>,
+) -> Option<Matrix<T>> {
+  let n = a.check_square();
+  if b.n_rows() != n || q.n_rows() != n || q.n_cols() != n || r.n_rows() != b.n_cols() {
+    panic!("dimensions do not match!");
+  }
+  let g = b * &r.lu_solve(&b.trans())?;
+  let h = block_matrix(a, &-&g, &-q, &-&a.trans());
+  let mut z = h;
+  let mut converged = false;
+  for _ in 0..MAX_ITERATIONS {
+    let z_inv = z.lu_inverse()?;
+    // Scaling by |det Z|^(-1/2n) speeds up the initial phase of the iteration.
+    let (_, u, _) = z.lu();
+    let log_det = (0..2 * n).fold(0.0, |acc, i| acc + u.get(i, i).to_f64().abs().ln());
+    let c = T::from_f64((-log_det / (2 * n) as f64).exp());
+    let next = &(&(&z * c) + &(&z_inv * (T::one() / c))) * T::from_f64(0.5);
+    let change = (&next - &z).norm_1();
+    z = next;
+    if change <= T::from_f64(1e3) * T::epsilon() * z.norm_1() {
+      converged = true;
+      break;
+    }
+  }
+  if !converged {
+    return None;
+  }
+  // The stable invariant subspace is the null space of sign(H) + I, so X solves
+  // [W12; W22 + I] X = -[W11 + I; W21] in the least squares sense.
+  let w = &z + &Matrix::identity(2 * n);
+  let m = sub_matrix(&w, (0, 2 * n), (n, n));
+  let rhs = -&sub_matrix(&w, (0, 2 * n), (0, n));
+  let x = least_squares(&m, &rhs)?;
+  Some(symmetrize(&x))
+}
+
+/// Solves the discrete algebraic Riccati equation
+/// `A^T X A - X - A^T X B (R + B^T X B)^-1 B^T X A + Q = 0` for the stabilizing
+/// solution by the structure-preserving doubling algorithm.
+pub fn solve_dare<T: Real>(
+  a: &Matrix<T>,
+  b: &Matrix<T>,
+  q: &Matrix<T>,
+  r: &Matrix<T>,
+) -> Option<Matrix<T>> {
+  let n = a.check_square();
+  if b.n_rows() != n || q.n_rows() != n || q.n_cols() != n || r.n_rows() != b.n_cols() {
+    panic!("dimensions do not match!");
+  }
+  let id = Matrix::identity(n);
+  let mut a_k = a.clone();
+  let mut g = b * &r.lu_solve(&b.trans())?;
+  let mut h = q.clone();
+  for _ in 0..MAX_ITERATIONS {
+    let w = &id + &(&g * &h);
+    let w_inv_a = w.lu_solve(&a_k)?;
+    let w_inv_g = w.lu_solve(&g)?;
+    let h_next = &h + &(&(&a_k.trans() * &h) * &w_inv_a);
+    g = &g + &(&(&a_k * &w_inv_g) * &a_k.trans());
+    a_k = &a_k * &w_inv_a;
+    let change = (&h_next - &h).norm_1();
+    h = h_next;
+    if change <= T::epsilon() * h.norm_1() {
+      return Some(symmetrize(&h));
+    }
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_small(m: &Matrix<f64>, tol: f64) {
+    assert!(m.norm_1() < tol, "{:?}", m);
+  }
+
+  fn stable() -> Matrix<f64> {
+    Matrix::new(vec![vec![-1.0, 2.0, 0.0], vec![-2.0, -1.0, 1.0], vec![0.0, 0.5, -3.0]])
+  }
+
+  #[test]
+  fn test_sylvester() {
+    let a = Matrix::new(vec![
+      vec![1.0, 2.0, 0.0, 1.0],
+      vec![-3.0, 1.0, 1.0, 0.0],
+      vec![0.0, 0.0, 4.0, 2.0],
+      vec![1.0, 0.0, -1.0, 5.0],
+    ]);
+    let b = Matrix::new(vec![vec![2.0, 1.0, 0.0], vec![-1.0, 2.0, 0.5], vec![0.0, 0.0, 3.0]]);
+    let c = Matrix::create_from_data((0..12).map(|v| (v % 5) as f64 - 2.0).collect(), 4, 3);
+    let x = solve_sylvester(&a, &b, &c).unwrap();
+    assert_small(&(&(&(&a * &x) + &(&x * &b)) - &c), 1e-12);
+    // A and -B share the eigenvalue 1.
+    let a = Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 2.0]]);
+    let b = Matrix::new(vec![vec![-1.0]]);
+    assert_eq!(solve_sylvester(&a, &b, &Matrix::zeros(2, 1)), None);
+  }
+
+  #[test]
+  fn test_lyapunov() {
+    let a = stable();
+    let q = Matrix::new(vec![vec![1.0, 0.0, 0.0], vec![0.0, 2.0, 0.5], vec![0.0, 0.5, 1.0]]);
+    let x = solve_lyapunov(&a, &q).unwrap();
+    assert_small(&(&(&(&a * &x) + &(&x * &a.trans())) + &q), 1e-12);
+    assert!(x.symmetric_eigen().0.iter().all(|&v| v > 0.0));
+  }
+
+  #[test]
+  fn test_discrete_lyapunov() {
+    let a = &stable() * 0.2;
+    let q = Matrix::new(vec![vec![1.0, 0.0, 0.0], vec![0.0, 2.0, 0.5], vec![0.0, 0.5, 1.0]]);
+    let x = solve_discrete_lyapunov(&a, &q).unwrap();
+    assert_small(&(&(&(&(&a * &x) * &a.trans()) - &x) + &q), 1e-12);
+    let unstable = Matrix::new(vec![vec![1.5, 0.0], vec![0.0, 0.5]]);
+    assert_eq!(solve_discrete_lyapunov(&unstable, &Matrix::identity(2)), None);
+  }
+
+  #[test]
+  fn test_care() {
+    // The double integrator with unit weights has X = [[sqrt(3), 1], [1, sqrt(3)]].
+    let a = Matrix::new(vec![vec![0.0, 1.0], vec![0.0, 0.0]]);
+    let b = Matrix::new(vec![vec![0.0], vec![1.0]]);
+    let x = solve_care(&a, &b, &Matrix::identity(2), &Matrix::identity(1)).unwrap();
+    let s = 3f64.sqrt();
+    assert_small(&(&x - &Matrix::new(vec![vec![s, 1.0], vec![1.0, s]])), 1e-10);
+    let a = Matrix::new(vec![vec![1.0, 2.0, 0.0], vec![0.0, -1.0, 3.0], vec![1.0, 0.0, 2.0]]);
+    let b = Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 0.0], vec![0.0, 1.0]]);
+    let q = Matrix::identity(3);
+    let r = Matrix::new(vec![vec![2.0, 0.0], vec![0.0, 1.0]]);
+    let x = solve_care(&a, &b, &q, &r).unwrap();
+    let g = &b * &r.lu_solve(&b.trans()).unwrap();
+    let residual = &(&(&(&a.trans() * &x) + &(&x * &a)) - &(&(&x * &g) * &x)) + &q;
+    assert_small(&residual, 1e-9);
+    // The closed loop A - G X is stable.
+    let closed = &a - &(&g * &x);
+    assert!(closed.eigenvalues().unwrap().iter().all(|v| v.0 < 0.0));
+  }
+
+  #[test]
+  fn test_dare() {
+    let a = Matrix::new(vec![vec![1.0, 0.1], vec![0.0, 1.0]]);
+    let b = Matrix::new(vec![vec![0.005], vec![0.1]]);
+    let q = Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 0.5]]);
+    let r = Matrix::new(vec![vec![0.1]]);
+    let x = solve_dare(&a, &b, &q, &r).unwrap();
+    let btxb = &r + &(&(&b.trans() * &x) * &b);
+    let k = btxb.lu_solve(&(&(&b.trans() * &x) * &a)).unwrap();
+    let residual = &(&(&(&a.trans() * &x) * &a) - &x) - &(&(&(&a.trans() * &x) * &b) * &k);
+    assert_small(&(&residual + &q), 1e-9);
+    let closed = &a - &(&b * &k);
+    let radius = closed.eigenvalues().unwrap().iter().map(|v| v.0.hypot(v.1)).fold(0.0, f64::max);
+    assert!(radius < 1.0);
+  }
+}
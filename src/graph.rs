@@ -0,0 +1,329 @@
+//! Graph algorithms treating a square `Matrix` as the adjacency or weight matrix
+//! of a graph. The entry at row `i` and column `j` describes the edge from vertex
+//! `i` to vertex `j`, a zero entry means there is no such edge. The shortest path
+//! functions are the exception: they take weight matrices where a missing edge is
+//! `T::infinity()` and a zero entry is an edge of length zero. Undirected graphs
+//! are represented by symmetric matrices.
+use crate::matrix::num::{RealField, Ring, Semiring, Zero};
+use crate::matrix::semiring::Infinity;
+use crate::Matrix;
+
+fn check_square<T: Clone + PartialEq>(a: &Matrix<T>) -> usize {
+  if a.n_rows() != a.n_cols() {
+    panic!("matrix must be square!");
+  }
+  a.n_rows()
+}
+
+fn from_usize<T: Semiring>(n: usize) -> T {
+  (0..n).fold(T::zero(), |acc, _| acc + T::one())
+}
+
+/// Computes the diagonal matrix of the (weighted) out-degrees, i.e. the row sums.
+pub fn degree_matrix<T: Semiring>(a: &Matrix<T>) -> Matrix<T> {
+  let n = check_square(a);
+  let mut d = Matrix::zeros(n, n);
+  for i in 0..n {
+    let degree = (0..n).fold(T::zero(), |acc, j| acc + a.get(i, j));
+    d.set(i, i, degree);
+  }
+  d
+}
+
+/// Computes the Laplacian `D - A`.
+pub fn laplacian<T: Ring>(a: &Matrix<T>) -> Matrix<T> {
+  &degree_matrix(a) - a
+}
+
+/// Computes the symmetric normalized Laplacian `I - D^(-1/2) A D^(-1/2)`.
+/// Rows and columns of isolated vertices are zero.
+pub fn normalized_laplacian<T: RealField>(a: &Matrix<T>) -> Matrix<T> {
+  let n = check_square(a);
+  let scale = (0..n)
+    .map(|i| {
+      let degree = (0..n).fold(T::zero(), |acc, j| acc + a.get(i, j));
+      if degree.is_zero() {
+        T::zero()
+      } else {
+        T::one() / degree.sqrt()
+      }
+    })
+    .collect::<Vec<T>>();
+  let mut l = Matrix::zeros(n, n);
+  for (e, i, j) in l.iter_mut() {
+    let diag = if i == j && !scale[i].is_zero() { T::one() } else { T::zero() };
+    *e = diag - scale[i] * a.get(i, j) * scale[j];
+  }
+  l
+}
+
+/// Computes the lengths of all shortest paths by the Floyd–Warshall algorithm.
+/// Missing edges are represented by `T::infinity()`, as are unreachable vertices
+/// in the result. Returns `None` if the graph contains a negative cycle.
+pub fn floyd_warshall<T: Semiring + Infinity>(w: &Matrix<T>) -> Option<Matrix<T>> {
+  let n = check_square(w);
+  let mut d = w.clone();
+  for (e, i, j) in d.iter_mut() {
+    if i == j && T::zero() < *e {
+      *e = T::zero();
+    }
+  }
+  let mut dist = d.iter().map(|(e, _, _)| *e).collect::<Vec<T>>();
+  for k in 0..n {
+    for i in 0..n {
+      let dik = dist[i * n + k];
+      if dik == T::infinity() {
+        continue;
+      }
+      for j in 0..n {
+        let dkj = dist[k * n + j];
+        if dkj != T::infinity() && dik + dkj < dist[i * n + j] {
+          dist[i * n + j] = dik + dkj;
+        }
+      }
+    }
+  }
+  if (0..n).any(|i| dist[i * n + i] < T::zero()) {
+    return None;
+  }
+  Some(Matrix::create_from_data(dist, n, n))
+}
+
+/// Labels the vertices by their connected component, ignoring edge directions.
+/// The components are numbered from zero in order of their smallest vertex.
+pub fn connected_components<T: Zero + Clone + PartialEq>(a: &Matrix<T>) -> Vec<usize> {
+  let n = check_square(a);
+  let mut labels = vec![usize::MAX; n];
+  let mut count = 0;
+  for start in 0..n {
+    if labels[start] != usize::MAX {
+      continue;
+    }
+    labels[start] = count;
+    let mut stack = vec![start];
+    while let Some(i) = stack.pop() {
+      for (j, label) in labels.iter_mut().enumerate() {
+        let adjacent = !a.get(i, j).is_zero() || !a.get(j, i).is_zero();
+        if adjacent && *label == usize::MAX {
+          *label = count;
+          stack.push(j);
+        }
+      }
+    }
+    count += 1;
+  }
+  labels
+}
+
+/// Computes the PageRank of every vertex by power iteration, returned as a column.
+/// The edge weights of a vertex are normalized to transition probabilities and
+/// vertices without outgoing edges link to every vertex. The iteration stops once
+/// the 1-norm of the change drops below `tol`, or after `max_iter` steps.
+pub fn pagerank<T: RealField>(a: &Matrix<T>, damping: T, tol: T, max_iter: usize) -> Matrix<T> {
+  let n = check_square(a);
+  if n == 0 {
+    return Matrix::zeros(0, 1);
+  }
+  let size = from_usize::<T>(n);
+  let out = (0..n)
+    .map(|i| (0..n).fold(T::zero(), |acc, j| acc + a.get(i, j)))
+    .collect::<Vec<T>>();
+  let mut rank = vec![T::one() / size; n];
+  for _ in 0..max_iter {
+    let dangling = (0..n)
+      .filter(|&i| out[i].is_zero())
+      .fold(T::zero(), |acc, i| acc + rank[i]);
+    let base = ((T::one() - damping) + damping * dangling) / size;
+    let mut next = vec![base; n];
+    for i in 0..n {
+      if out[i].is_zero() {
+        continue;
+      }
+      let share = damping * rank[i] / out[i];
+      for (j, e) in next.iter_mut().enumerate() {
+        *e = *e + share * a.get(i, j);
+      }
+    }
+    let change = (0..n).fold(T::zero(), |acc, i| acc + (next[i] - rank[i]).abs());
+    rank = next;
+    if change < tol {
+      break;
+    }
+  }
+  Matrix::create_from_data(rank, n, 1)
+}
+
+/// Computes the Fiedler vector of an undirected graph, i.e. the eigenvector belonging
+/// to the second smallest eigenvalue of the Laplacian, returned as a column.
+pub fn fiedler_vector<T: RealField>(a: &Matrix<T>) -> Matrix<T> {
+  let n = check_square(a);
+  if n < 2 {
+    panic!("graph must have at least two vertices!");
+  }
+  let (_, vectors) = laplacian(a).symmetric_eigen();
+  vectors.get_col(1)
+}
+
+/// Splits an undirected graph into two clusters by the signs of its Fiedler vector.
+/// Returns the cluster label, `0` or `1`, of every vertex.
+pub fn spectral_bisection<T: RealField>(a: &Matrix<T>) -> Vec<usize> {
+  let f = fiedler_vector(a);
+  let flip = f.get(0, 0) < T::zero();
+  f.iter()
+    .map(|(x, _, _)| if (*x < T::zero()) != flip { 1 } else { 0 })
+    .collect()
+}
+
+/// Converts the adjacency matrix of a directed graph into its oriented incidence
+/// matrix. Every non-zero entry off the diagonal becomes a column, in row-major
+/// order, holding `-1` at the source and `1` at the target vertex. Edge weights
+/// and loops are dropped.
+pub fn incidence_matrix<T: Ring>(a: &Matrix<T>) -> Matrix<T> {
+  let n = check_square(a);
+  let edges = a
+    .iter()
+    .filter(|(e, i, j)| i != j && !e.is_zero())
+    .map(|(_, i, j)| (i, j))
+    .collect::<Vec<(usize, usize)>>();
+  let mut b = Matrix::zeros(n, edges.len());
+  for (e, i, k) in b.iter_mut() {
+    let (source, target) = edges[k];
+    if i == source {
+      *e = -T::one();
+    } else if i == target {
+      *e = T::one();
+    }
+  }
+  b
+}
+
+/// Converts an oriented incidence matrix, as created by `incidence_matrix`, back
+/// into the adjacency matrix having a `1` for every edge.
+pub fn adjacency_from_incidence<T: Ring>(b: &Matrix<T>) -> Matrix<T> {
+  let n = b.n_rows();
+  let mut a = Matrix::zeros(n, n);
+  for k in 0..b.n_cols() {
+    let source = (0..n).find(|&i| b.get(i, k) == -T::one());
+    let target = (0..n).find(|&i| b.get(i, k) == T::one());
+    match (source, target) {
+      (Some(i), Some(j)) => {
+        a.set(i, j, T::one());
+      }
+      _ => panic!("not an incidence matrix!"),
+    }
+  }
+  a
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const INF: f64 = f64::INFINITY;
+
+  fn path_graph() -> Matrix<f64> {
+    Matrix::new(vec![vec![0.0, 1.0, 0.0], vec![1.0, 0.0, 1.0], vec![0.0, 1.0, 0.0]])
+  }
+
+  #[test]
+  fn test_laplacian() {
+    let a = Matrix::new(vec![vec![0, 1, 0], vec![1, 0, 1], vec![0, 1, 0]]);
+    assert_eq!(
+      degree_matrix(&a),
+      Matrix::new(vec![vec![1, 0, 0], vec![0, 2, 0], vec![0, 0, 1]])
+    );
+    assert_eq!(
+      laplacian(&a),
+      Matrix::new(vec![vec![1, -1, 0], vec![-1, 2, -1], vec![0, -1, 1]])
+    );
+  }
+
+  #[test]
+  fn test_normalized_laplacian() {
+    let l = normalized_laplacian(&path_graph());
+    let h = -1.0 / 2f64.sqrt();
+    let expected = Matrix::new(vec![vec![1.0, h, 0.0], vec![h, 1.0, h], vec![0.0, h, 1.0]]);
+    for ((x, _, _), (y, _, _)) in l.iter().zip(expected.iter()) {
+      assert!((x - y).abs() < 1e-12);
+    }
+  }
+
+  #[test]
+  fn test_floyd_warshall() {
+    let w = Matrix::new(vec![
+      vec![0, 3, i64::MAX, 7],
+      vec![8, 0, 2, i64::MAX],
+      vec![5, i64::MAX, 0, 1],
+      vec![2, i64::MAX, i64::MAX, 0],
+    ]);
+    assert_eq!(
+      floyd_warshall(&w).unwrap(),
+      Matrix::new(vec![vec![0, 3, 5, 6], vec![5, 0, 2, 3], vec![3, 6, 0, 1], vec![2, 5, 7, 0]])
+    );
+    let negative = Matrix::new(vec![vec![0.0, 1.0], vec![-2.0, 0.0]]);
+    assert_eq!(floyd_warshall(&negative), None);
+    let disconnected = Matrix::new(vec![vec![INF, 1.0], vec![INF, INF]]);
+    assert_eq!(
+      floyd_warshall(&disconnected).unwrap(),
+      Matrix::new(vec![vec![0.0, 1.0], vec![INF, 0.0]])
+    );
+  }
+
+  #[test]
+  fn test_connected_components() {
+    let a = Matrix::new(vec![
+      vec![0, 0, 1, 0],
+      vec![0, 0, 0, 0],
+      vec![0, 0, 0, 0],
+      vec![0, 1, 0, 0],
+    ]);
+    assert_eq!(connected_components(&a), vec![0, 1, 0, 1]);
+  }
+
+  #[test]
+  fn test_pagerank() {
+    let a = Matrix::new(vec![
+      vec![0.0, 1.0, 1.0, 0.0],
+      vec![0.0, 0.0, 1.0, 0.0],
+      vec![1.0, 0.0, 0.0, 0.0],
+      vec![0.0, 0.0, 0.0, 0.0],
+    ]);
+    let r = pagerank(&a, 0.85, 1e-12, 1000);
+    let total = r.iter().fold(0.0, |acc, (x, _, _)| acc + x);
+    assert!((total - 1.0).abs() < 1e-10);
+    assert!(r.get(2, 0) > r.get(0, 0) && r.get(0, 0) > r.get(1, 0) && r.get(1, 0) > r.get(3, 0));
+    let cycle = Matrix::new(vec![vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0], vec![1.0, 0.0, 0.0]]);
+    let r = pagerank(&cycle, 0.85, 1e-12, 1000);
+    assert!(r.iter().all(|(x, _, _)| (x - 1.0 / 3.0).abs() < 1e-10));
+  }
+
+  #[test]
+  fn test_spectral_bisection() {
+    // Two triangles joined by a single edge.
+    let mut a = Matrix::zeros(6, 6);
+    for &(i, j) in &[(0, 1), (0, 2), (1, 2), (3, 4), (3, 5), (4, 5), (2, 3)] {
+      a.set(i, j, 1.0);
+      a.set(j, i, 1.0);
+    }
+    let f = fiedler_vector(&a);
+    let l = laplacian(&a);
+    let lf = &l * &f;
+    let lambda = (&f.trans() * &lf).get(0, 0);
+    assert!((&lf - &(&f * lambda)).iter().all(|(x, _, _)| x.abs() < 1e-10));
+    assert_eq!(spectral_bisection(&a), vec![0, 0, 0, 1, 1, 1]);
+  }
+
+  #[test]
+  fn test_incidence_matrix() {
+    let a = Matrix::new(vec![vec![0, 1, 1], vec![0, 0, 1], vec![0, 0, 0]]);
+    let b = incidence_matrix(&a);
+    assert_eq!(
+      b,
+      Matrix::new(vec![vec![-1, -1, 0], vec![1, 0, -1], vec![0, 1, 1]])
+    );
+    assert_eq!(adjacency_from_incidence(&b), a);
+    let undirected = &a + &a.trans();
+    let b = incidence_matrix(&undirected);
+    assert_eq!(&b * &b.trans(), &laplacian(&undirected) * 2);
+  }
+}
@@ -0,0 +1,19 @@
+//! Reading and writing matrices in common file formats. All readers and writers
+//! work on any `std::io::Read` or `std::io::Write` and report failures as
+//! `IoError`.
+mod binary;
+mod crc32;
+mod csv;
+mod error;
+mod inflate;
+mod mat;
+mod matrix_market;
+mod npy;
+mod npz;
+pub use binary::{BinaryHeader, BinaryReader, BinaryView, BinaryWriter, MatrixView};
+pub use csv::CsvOptions;
+pub use error::IoError;
+pub use mat::{MatClass, MatFile, MatNumber, MatValue, MatWriter};
+pub use matrix_market::{MatrixMarketValue, MtxField, MtxFormat, MtxHeader, MtxSymmetry};
+pub use npy::NpyValue;
+pub use npz::{NpzArchive, NpzWriter};
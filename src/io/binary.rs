@@ -0,0 +1,552 @@
+use super::crc32::{crc32, Crc32};
+use super::npy::NpyValue;
+use super::IoError;
+use crate::Matrix;
+use std::convert::TryInto;
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::ops::Range;
+
+// The file starts with a signature which detects text mode transfers, like PNG.
+const MAGIC: &[u8; 8] = b"\x89MXB\r\n\x1a\n";
+const VERSION: u16 = 1;
+const FILE_HEADER_LEN: usize = 16;
+// The name length which marks the end of the file.
+const END: u32 = u32::MAX;
+// Payloads are written in chunks, so that streaming needs little memory.
+const CHUNK: usize = 1 << 16;
+
+fn format_error<T>(message: &str) -> Result<T, IoError> {
+  Err(IoError::Format(format!("binary: {}", message)))
+}
+
+fn padding(len: usize) -> usize {
+  (8 - len % 8) % 8
+}
+
+fn file_header() -> [u8; FILE_HEADER_LEN] {
+  let mut header = [0; FILE_HEADER_LEN];
+  header[..8].copy_from_slice(MAGIC);
+  header[8] = b'<';
+  header[10..12].copy_from_slice(&VERSION.to_le_bytes());
+  header
+}
+
+// Checks the file header and returns whether the file is big endian.
+fn parse_file_header(header: &[u8]) -> Result<bool, IoError> {
+  if header.len() < FILE_HEADER_LEN || &header[..8] != MAGIC {
+    return format_error("not a matrix file");
+  }
+  let big_endian = match header[8] {
+    b'<' => false,
+    b'>' => true,
+    _ => return format_error("invalid byte order"),
+  };
+  let version = [header[10], header[11]];
+  let version = match big_endian {
+    true => u16::from_be_bytes(version),
+    false => u16::from_le_bytes(version),
+  };
+  if version != VERSION {
+    return format_error(&format!("unsupported version {}", version));
+  }
+  Ok(big_endian)
+}
+
+fn read_u32(bytes: &[u8], big_endian: bool) -> u32 {
+  let bytes = bytes[..4].try_into().unwrap();
+  if big_endian {
+    u32::from_be_bytes(bytes)
+  } else {
+    u32::from_le_bytes(bytes)
+  }
+}
+
+fn read_u64(bytes: &[u8], big_endian: bool) -> u64 {
+  let bytes = bytes[..8].try_into().unwrap();
+  if big_endian {
+    u64::from_be_bytes(bytes)
+  } else {
+    u64::from_le_bytes(bytes)
+  }
+}
+
+/// The description of a matrix stored in the native binary format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryHeader {
+  pub name: String,
+  /// The kind and size of the scalar type, as given by `NpyValue`.
+  pub kind: char,
+  pub size: usize,
+  pub n_rows: usize,
+  pub n_cols: usize,
+}
+
+impl BinaryHeader {
+  fn payload_len(&self) -> Result<usize, IoError> {
+    match self.n_rows.checked_mul(self.n_cols).and_then(|n| n.checked_mul(self.size)) {
+      Some(len) => Ok(len),
+      None => format_error(&format!("matrix {:?} is too large", self.name)),
+    }
+  }
+
+  fn check<T: NpyValue>(&self) -> Result<(), IoError> {
+    if self.kind != T::KIND || self.size != T::SIZE {
+      let (name, kind, size) = (&self.name, self.kind, self.size);
+      let target = format!("{}{}", T::KIND, T::SIZE);
+      let message = format!("cannot read {:?} of dtype {}{} as {}", name, kind, size, target);
+      return format_error(&message);
+    }
+    Ok(())
+  }
+
+  // Parses the fixed part of a record following its name.
+  fn parse(name: String, fields: &[u8], big_endian: bool) -> Result<BinaryHeader, IoError> {
+    let header = BinaryHeader {
+      name,
+      kind: fields[0] as char,
+      size: fields[1] as usize,
+      n_rows: read_u64(&fields[8..], big_endian) as usize,
+      n_cols: read_u64(&fields[16..], big_endian) as usize,
+    };
+    header.payload_len()?;
+    Ok(header)
+  }
+}
+
+/// Writes matrices in the native binary format, one after another.
+///
+/// A file starts with a signature, the byte order and the format version. Each
+/// matrix is stored as its name, the dtype, its shape and its entries in
+/// row-major order, followed by the CRC-32 checksum of the entries. All fields
+/// and entries are aligned to eight bytes, and the writer uses little endian
+/// byte order.
+pub struct BinaryWriter<W: Write> {
+  writer: W,
+  names: Vec<String>,
+}
+
+impl<W: Write> BinaryWriter<W> {
+  /// Writes the file header.
+  pub fn new(mut writer: W) -> Result<BinaryWriter<W>, IoError> {
+    writer.write_all(&file_header())?;
+    Ok(BinaryWriter {
+      writer,
+      names: vec![],
+    })
+  }
+
+  /// Writes a matrix under the given name, which must be unique within the file.
+  pub fn write<T: NpyValue>(&mut self, name: &str, m: &Matrix<T>) -> Result<(), IoError> {
+    if self.names.iter().any(|n| n == name) {
+      return format_error(&format!("duplicate name {:?}", name));
+    }
+    if name.len() >= END as usize {
+      return format_error("name too long");
+    }
+    let mut header = (name.len() as u32).to_le_bytes().to_vec();
+    header.extend_from_slice(name.as_bytes());
+    header.resize(header.len() + padding(header.len()), 0);
+    header.extend_from_slice(&[T::KIND as u8, T::SIZE as u8, 0, 0, 0, 0, 0, 0]);
+    header.extend_from_slice(&(m.n_rows() as u64).to_le_bytes());
+    header.extend_from_slice(&(m.n_cols() as u64).to_le_bytes());
+    self.writer.write_all(&header)?;
+    let mut crc = Crc32::new();
+    let mut chunk = Vec::with_capacity(CHUNK + T::SIZE);
+    for (e, _, _) in m.iter() {
+      e.write_npy_bytes(&mut chunk);
+      if chunk.len() >= CHUNK {
+        crc.update(&chunk);
+        self.writer.write_all(&chunk)?;
+        chunk.clear();
+      }
+    }
+    crc.update(&chunk);
+    chunk.resize(chunk.len() + padding(m.n_rows() * m.n_cols() * T::SIZE), 0);
+    chunk.extend_from_slice(&crc.finish().to_le_bytes());
+    chunk.extend_from_slice(&[0; 4]);
+    self.writer.write_all(&chunk)?;
+    self.names.push(name.to_string());
+    Ok(())
+  }
+
+  /// Writes the end of the file and returns the underlying writer.
+  pub fn finish(mut self) -> Result<W, IoError> {
+    self.writer.write_all(&END.to_le_bytes())?;
+    self.writer.write_all(&[0; 4])?;
+    self.writer.flush()?;
+    Ok(self.writer)
+  }
+}
+
+/// Reads matrices in the native binary format one after another, without
+/// holding more than one of them in memory.
+pub struct BinaryReader<R: Read> {
+  reader: R,
+  big_endian: bool,
+  // The header of the matrix whose entries are to be read next.
+  pending: Option<BinaryHeader>,
+  finished: bool,
+}
+
+impl<R: Read> BinaryReader<R> {
+  /// Reads the file header.
+  pub fn new(mut reader: R) -> Result<BinaryReader<R>, IoError> {
+    let mut header = [0; FILE_HEADER_LEN];
+    reader.read_exact(&mut header)?;
+    Ok(BinaryReader {
+      big_endian: parse_file_header(&header)?,
+      reader,
+      pending: None,
+      finished: false,
+    })
+  }
+
+  /// Reads the header of the next matrix, skipping the entries of the current
+  /// one if they have not been read. Returns `None` at the end of the file.
+  pub fn next_header(&mut self) -> Result<Option<BinaryHeader>, IoError> {
+    if let Some(header) = self.pending.take() {
+      let len = header.payload_len()?;
+      self.payload(len, |_| {})?;
+    }
+    if self.finished {
+      return Ok(None);
+    }
+    let mut len = [0; 4];
+    self.reader.read_exact(&mut len)?;
+    let len = read_u32(&len, self.big_endian);
+    if len == END {
+      self.reader.read_exact(&mut [0; 4])?;
+      self.finished = true;
+      return Ok(None);
+    }
+    let mut name = vec![0; len as usize + padding(4 + len as usize)];
+    self.reader.read_exact(&mut name)?;
+    name.truncate(len as usize);
+    let name = match String::from_utf8(name) {
+      Ok(name) => name,
+      Err(_) => return format_error("name is not valid utf-8"),
+    };
+    let mut fields = [0; 24];
+    self.reader.read_exact(&mut fields)?;
+    let header = BinaryHeader::parse(name, &fields, self.big_endian)?;
+    self.pending = Some(header.clone());
+    Ok(Some(header))
+  }
+
+  /// Reads the entries of the matrix whose header was read last, and verifies
+  /// their checksum.
+  pub fn read_matrix<T: NpyValue>(&mut self) -> Result<Matrix<T>, IoError> {
+    // On a dtype mismatch the entries stay pending, so that they can be skipped.
+    match &self.pending {
+      Some(header) => header.check::<T>()?,
+      None => return format_error("no matrix header has been read"),
+    }
+    let header = self.pending.take().unwrap();
+    let len = header.payload_len()?;
+    // The shape is not trusted before the checksum is, so the entries are
+    // collected as they arrive rather than allocated up front.
+    let mut data = Vec::with_capacity(len.min(CHUNK) / T::SIZE);
+    let big_endian = self.big_endian;
+    self.payload(len, |chunk| {
+      data.extend(chunk.chunks_exact(T::SIZE).map(|b| T::from_npy_bytes(b, !big_endian)))
+    })?;
+    Ok(Matrix::create_from_data(data, header.n_rows, header.n_cols))
+  }
+
+  /// Reads the next matrix together with its name, or returns `None` at the end
+  /// of the file.
+  pub fn read_next<T: NpyValue>(&mut self) -> Result<Option<(String, Matrix<T>)>, IoError> {
+    match self.next_header()? {
+      Some(header) => Ok(Some((header.name, self.read_matrix()?))),
+      None => Ok(None),
+    }
+  }
+
+  // Passes the payload of `len` bytes in chunks of whole entries, and checks
+  // the trailing checksum.
+  fn payload<F: FnMut(&[u8])>(&mut self, len: usize, mut f: F) -> Result<(), IoError> {
+    let mut crc = Crc32::new();
+    let mut chunk = vec![0; CHUNK.min(len)];
+    let mut left = len;
+    while left > 0 {
+      let n = left.min(CHUNK);
+      self.reader.read_exact(&mut chunk[..n])?;
+      crc.update(&chunk[..n]);
+      f(&chunk[..n]);
+      left -= n;
+    }
+    let mut trailer = vec![0; padding(len) + 8];
+    self.reader.read_exact(&mut trailer)?;
+    if read_u32(&trailer[padding(len)..], self.big_endian) != crc.finish() {
+      return format_error("checksum mismatch");
+    }
+    Ok(())
+  }
+}
+
+struct Entry {
+  header: BinaryHeader,
+  payload: Range<usize>,
+  crc: u32,
+}
+
+/// The matrices of a file in the native binary format held in memory, for
+/// instance a memory-mapped file. The matrices are accessed in place, without
+/// copying their entries.
+pub struct BinaryView<'a> {
+  bytes: &'a [u8],
+  big_endian: bool,
+  entries: Vec<Entry>,
+}
+
+impl<'a> BinaryView<'a> {
+  /// Reads the directory of the file. The checksums are verified when a matrix
+  /// is accessed.
+  pub fn new(bytes: &'a [u8]) -> Result<BinaryView<'a>, IoError> {
+    let big_endian = parse_file_header(bytes)?;
+    let truncated = || format_error("unexpected end of file");
+    let mut entries = vec![];
+    let mut at = FILE_HEADER_LEN;
+    loop {
+      let len = match bytes.get(at..at + 4) {
+        Some(len) => read_u32(len, big_endian) as usize,
+        None => return truncated(),
+      };
+      if len == END as usize {
+        break;
+      }
+      let fields = at + 4 + len + padding(4 + len);
+      let (name, fields) = match (bytes.get(at + 4..at + 4 + len), bytes.get(fields..fields + 24)) {
+        (Some(name), Some(fields)) => (name, fields),
+        _ => return truncated(),
+      };
+      let name = match std::str::from_utf8(name) {
+        Ok(name) => name.to_string(),
+        Err(_) => return format_error("name is not valid utf-8"),
+      };
+      let header = BinaryHeader::parse(name, fields, big_endian)?;
+      let start = at + 4 + len + padding(4 + len) + 24;
+      let end = match start.checked_add(header.payload_len()?) {
+        Some(end) if end <= bytes.len() => end,
+        Some(_) => return truncated(),
+        None => return format_error(&format!("matrix {:?} is too large", header.name)),
+      };
+      let payload = start..end;
+      let crc_at = payload.end + padding(payload.end - start);
+      let crc = match bytes.get(crc_at..crc_at + 8) {
+        Some(trailer) => read_u32(trailer, big_endian),
+        None => return truncated(),
+      };
+      entries.push(Entry {
+        header,
+        payload,
+        crc,
+      });
+      at = crc_at + 8;
+    }
+    Ok(BinaryView {
+      bytes,
+      big_endian,
+      entries,
+    })
+  }
+
+  /// Returns the headers of the matrices in the order they are stored.
+  pub fn headers(&self) -> Vec<&BinaryHeader> {
+    self.entries.iter().map(|e| &e.header).collect()
+  }
+
+  /// Returns a view of the named matrix after verifying its checksum. Fails if
+  /// there is no such matrix or its dtype is not that of `T`.
+  pub fn get<T: NpyValue>(&self, name: &str) -> Result<MatrixView<'a, T>, IoError> {
+    let entry = match self.entries.iter().find(|e| e.header.name == name) {
+      Some(entry) => entry,
+      None => return format_error(&format!("no matrix named {:?}", name)),
+    };
+    entry.header.check::<T>()?;
+    let data = &self.bytes[entry.payload.clone()];
+    if crc32(data) != entry.crc {
+      return format_error(&format!("checksum mismatch in {:?}", name));
+    }
+    Ok(MatrixView {
+      data,
+      n_rows: entry.header.n_rows,
+      n_cols: entry.header.n_cols,
+      little_endian: !self.big_endian,
+      scalar: PhantomData,
+    })
+  }
+}
+
+/// A matrix stored in a borrowed byte slice, whose entries are decoded on
+/// access.
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixView<'a, T> {
+  data: &'a [u8],
+  n_rows: usize,
+  n_cols: usize,
+  little_endian: bool,
+  scalar: PhantomData<T>,
+}
+
+impl<'a, T: NpyValue> MatrixView<'a, T> {
+  pub fn n_rows(&self) -> usize {
+    self.n_rows
+  }
+
+  pub fn n_cols(&self) -> usize {
+    self.n_cols
+  }
+
+  /// Returns the entry at row `i` and column `j`.
+  pub fn get(&self, i: usize, j: usize) -> T {
+    if i >= self.n_rows || j >= self.n_cols {
+      panic!("index out of bounds!");
+    }
+    let at = (i * self.n_cols + j) * T::SIZE;
+    T::from_npy_bytes(&self.data[at..at + T::SIZE], self.little_endian)
+  }
+
+  /// Returns the raw bytes of the entries in row-major order.
+  pub fn as_bytes(&self) -> &'a [u8] {
+    self.data
+  }
+
+  /// Copies the entries into a matrix.
+  pub fn to_matrix(&self) -> Matrix<T> {
+    let data = self
+      .data
+      .chunks_exact(T::SIZE)
+      .map(|b| T::from_npy_bytes(b, self.little_endian))
+      .collect();
+    Matrix::create_from_data(data, self.n_rows, self.n_cols)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Complex;
+
+  fn sample() -> Vec<u8> {
+    let mut writer = BinaryWriter::new(vec![]).unwrap();
+    let weights = Matrix::new(vec![vec![1.5, -2.0, 3.0], vec![0.0, 1e-9, 7.0]]);
+    writer.write("weights", &weights).unwrap();
+    writer.write("ids", &Matrix::new(vec![vec![4i32], vec![-5]])).unwrap();
+    writer.write("z", &Matrix::new(vec![vec![Complex::new(1.0f32, 2.0)]])).unwrap();
+    writer.finish().unwrap()
+  }
+
+  #[test]
+  fn test_layout() {
+    let bytes = sample();
+    assert_eq!(&bytes[..16], b"\x89MXB\r\n\x1a\n<\0\x01\0\0\0\0\0");
+    // The name "weights" padded to 8 bytes, then the dtype and the shape.
+    assert_eq!(&bytes[16..32], b"\x07\0\0\0weights\0\0\0\0\0");
+    assert_eq!(&bytes[32..34], b"f\x08");
+    assert_eq!(&bytes[56..64], &1.5f64.to_le_bytes());
+    assert_eq!(bytes.len() % 8, 0);
+    assert_eq!(&bytes[bytes.len() - 8..], b"\xff\xff\xff\xff\0\0\0\0");
+  }
+
+  #[test]
+  fn test_streaming() {
+    let bytes = sample();
+    let mut reader = BinaryReader::new(&bytes[..]).unwrap();
+    let (name, weights) = reader.read_next::<f64>().unwrap().unwrap();
+    assert_eq!(name, "weights");
+    assert_eq!(weights.get(1, 1), 1e-9);
+    // The entries of a matrix are skipped unless they are read.
+    let header = reader.next_header().unwrap().unwrap();
+    assert_eq!((header.kind, header.size, header.n_rows, header.n_cols), ('i', 4, 2, 1));
+    assert!(reader.read_matrix::<i64>().is_err());
+    let header = reader.next_header().unwrap().unwrap();
+    assert_eq!(header.name, "z");
+    assert_eq!(reader.read_matrix::<Complex<f32>>().unwrap().get(0, 0), Complex::new(1.0, 2.0));
+    assert!(reader.next_header().unwrap().is_none());
+    assert!(reader.next_header().unwrap().is_none());
+  }
+
+  #[test]
+  fn test_large_matrix() {
+    let m = Matrix::create_from_data((0..20000).map(|k| k as f64 * 0.5).collect(), 100, 200);
+    let mut writer = BinaryWriter::new(vec![]).unwrap();
+    writer.write("m", &m).unwrap();
+    let bytes = writer.finish().unwrap();
+    let mut reader = BinaryReader::new(&bytes[..]).unwrap();
+    assert_eq!(reader.read_next::<f64>().unwrap().unwrap().1, m);
+    let view = BinaryView::new(&bytes).unwrap();
+    assert_eq!(view.get::<f64>("m").unwrap().to_matrix(), m);
+  }
+
+  #[test]
+  fn test_view() {
+    let bytes = sample();
+    let view = BinaryView::new(&bytes).unwrap();
+    let names = view.headers().iter().map(|h| h.name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names, vec!["weights", "ids", "z"]);
+    let weights = view.get::<f64>("weights").unwrap();
+    assert_eq!((weights.n_rows(), weights.n_cols()), (2, 3));
+    assert_eq!(weights.get(0, 1), -2.0);
+    assert_eq!(weights.as_bytes().as_ptr(), bytes[56..].as_ptr());
+    assert_eq!(view.get::<i32>("ids").unwrap().to_matrix(), Matrix::new(vec![vec![4], vec![-5]]));
+    assert!(view.get::<f32>("weights").is_err());
+    assert!(view.get::<f64>("missing").is_err());
+  }
+
+  #[test]
+  fn test_big_endian() {
+    let mut bytes = file_header().to_vec();
+    bytes[8] = b'>';
+    bytes[10..12].copy_from_slice(&VERSION.to_be_bytes());
+    bytes.extend_from_slice(&1u32.to_be_bytes());
+    bytes.extend_from_slice(b"a\0\0\0");
+    bytes.extend_from_slice(b"u\x02\0\0\0\0\0\0");
+    bytes.extend_from_slice(&1u64.to_be_bytes());
+    bytes.extend_from_slice(&2u64.to_be_bytes());
+    let payload = [0x01, 0x02, 0xff, 0xfe];
+    bytes.extend_from_slice(&payload);
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.extend_from_slice(&crc32(&payload).to_be_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    bytes.extend_from_slice(&END.to_be_bytes());
+    bytes.extend_from_slice(&[0; 4]);
+    let expected = Matrix::new(vec![vec![0x0102u16, 0xfffe]]);
+    assert_eq!(BinaryView::new(&bytes).unwrap().get::<u16>("a").unwrap().to_matrix(), expected);
+    let mut reader = BinaryReader::new(&bytes[..]).unwrap();
+    assert_eq!(reader.read_next::<u16>().unwrap().unwrap().1, expected);
+  }
+
+  #[test]
+  fn test_errors() {
+    let mut bytes = sample();
+    bytes[60] ^= 0x10;
+    let e = BinaryView::new(&bytes).unwrap().get::<f64>("weights").unwrap_err();
+    assert_eq!(e.to_string(), "binary: checksum mismatch in \"weights\"");
+    let mut reader = BinaryReader::new(&bytes[..]).unwrap();
+    assert!(reader.read_next::<f64>().is_err());
+    let bytes = sample();
+    assert!(BinaryView::new(&bytes[..bytes.len() - 8]).is_err());
+    assert!(matches!(BinaryReader::new(&bytes[..10]), Err(IoError::Io(_))));
+    assert!(BinaryView::new(b"\x89MXB\r\n\x1a\n<\0\x02\0\0\0\0\0").is_err());
+    let mut writer = BinaryWriter::new(vec![]).unwrap();
+    writer.write("a", &Matrix::new(vec![vec![1u8]])).unwrap();
+    assert!(writer.write("a", &Matrix::new(vec![vec![1u8]])).is_err());
+  }
+
+  #[test]
+  fn test_corrupt_shape() {
+    // A huge shape fails on the missing entries instead of allocating them.
+    let mut bytes = sample();
+    bytes[40..48].copy_from_slice(&(1u64 << 40).to_le_bytes());
+    let mut reader = BinaryReader::new(&bytes[..]).unwrap();
+    assert!(matches!(reader.read_next::<f64>(), Err(IoError::Io(_))));
+    assert!(BinaryView::new(&bytes).is_err());
+    // A payload reaching past the end of the address space.
+    bytes[40..48].copy_from_slice(&(u64::MAX / 8).to_le_bytes());
+    bytes[48..56].copy_from_slice(&1u64.to_le_bytes());
+    let e = BinaryView::new(&bytes).err().unwrap();
+    assert_eq!(e.to_string(), "binary: matrix \"weights\" is too large");
+  }
+}
@@ -0,0 +1,57 @@
+// The CRC-32 checksum of zip, gzip and PNG (reflected polynomial 0xEDB88320).
+const TABLE: [u32; 256] = {
+  let mut table = [0; 256];
+  let mut n = 0;
+  while n < 256 {
+    let mut c = n as u32;
+    let mut k = 0;
+    while k < 8 {
+      c = if c & 1 == 1 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+      k += 1;
+    }
+    table[n] = c;
+    n += 1;
+  }
+  table
+};
+
+/// Computes the checksum of data arriving in pieces.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Crc32(u32);
+
+impl Crc32 {
+  pub(crate) fn new() -> Crc32 {
+    Crc32(0xFFFF_FFFF)
+  }
+
+  pub(crate) fn update(&mut self, bytes: &[u8]) {
+    for &b in bytes {
+      self.0 = TABLE[((self.0 ^ b as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+    }
+  }
+
+  pub(crate) fn finish(&self) -> u32 {
+    !self.0
+  }
+}
+
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+  let mut crc = Crc32::new();
+  crc.update(bytes);
+  crc.finish()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_crc32() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    let mut crc = Crc32::new();
+    crc.update(b"1234");
+    crc.update(b"56789");
+    assert_eq!(crc.finish(), 0xCBF4_3926);
+  }
+}
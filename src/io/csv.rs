@@ -0,0 +1,307 @@
+use super::IoError;
+use crate::Matrix;
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::str::FromStr;
+
+/// The dialect of a CSV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+  pub delimiter: char,
+  /// Whether the first record holds column names rather than values.
+  pub has_header: bool,
+  /// Lines starting with this character are skipped, and outside of quotes the
+  /// character ends a record.
+  pub comment: Option<char>,
+  /// Fields enclosed in this character may contain delimiters, line breaks and
+  /// the quote character itself written twice.
+  pub quote: Option<char>,
+  /// Unquoted fields denoting a missing value. They are read as `NaN`, so reading
+  /// them into a type without `NaN` fails.
+  pub na_values: Vec<String>,
+}
+
+impl Default for CsvOptions {
+  /// Comma separated values without header, quoted by `"`, with empty fields and
+  /// `NA` as missing values.
+  fn default() -> CsvOptions {
+    CsvOptions {
+      delimiter: ',',
+      has_header: false,
+      comment: None,
+      quote: Some('"'),
+      na_values: vec![String::new(), String::from("NA")],
+    }
+  }
+}
+
+struct Field {
+  text: String,
+  quoted: bool,
+  line: usize,
+  column: usize,
+}
+
+// Reads the records of a CSV file as lists of fields. A record continues on the
+// next line while a quoted field is open.
+fn read_records<R: Read>(reader: R, options: &CsvOptions) -> Result<Vec<Vec<Field>>, IoError> {
+  let mut lines = BufReader::new(reader).lines();
+  let mut records = vec![];
+  let mut line_number = 0;
+  while let Some(line) = lines.next() {
+    line_number += 1;
+    let mut line = line?;
+    if options.comment.is_some_and(|c| line.starts_with(c)) || line.trim().is_empty() {
+      continue;
+    }
+    let (mut record, mut field) = (vec![], None::<Field>);
+    let mut in_quotes = false;
+    let mut column = 0;
+    loop {
+      let mut chars = line.chars().peekable();
+      while let Some(c) = chars.next() {
+        column += 1;
+        let current = field.get_or_insert_with(|| Field {
+          text: String::new(),
+          quoted: false,
+          line: line_number,
+          column,
+        });
+        if in_quotes {
+          if Some(c) == options.quote {
+            if chars.peek() == Some(&c) {
+              chars.next();
+              column += 1;
+              current.text.push(c);
+            } else {
+              in_quotes = false;
+            }
+          } else {
+            current.text.push(c);
+          }
+        } else if c == options.delimiter {
+          record.push(field.take().unwrap());
+        } else if Some(c) == options.comment {
+          break;
+        } else if Some(c) == options.quote && current.text.trim().is_empty() {
+          current.text.clear();
+          current.quoted = true;
+          in_quotes = true;
+        } else if current.quoted && !c.is_whitespace() {
+          return Err(IoError::parse(line_number, column, "unexpected character after quote"));
+        } else {
+          current.text.push(c);
+        }
+      }
+      if !in_quotes {
+        break;
+      }
+      match lines.next() {
+        Some(next) => {
+          line = next?;
+          line_number += 1;
+          column = 0;
+          field.as_mut().unwrap().text.push('\n');
+        }
+        None => return Err(IoError::parse(line_number, column, "unterminated quote")),
+      }
+    }
+    record.push(field.unwrap_or(Field {
+      text: String::new(),
+      quoted: false,
+      line: line_number,
+      column: column + 1,
+    }));
+    if record.len() > 1 || !record[0].text.trim().is_empty() || record[0].quoted {
+      records.push(record);
+    }
+  }
+  Ok(records)
+}
+
+impl<T: FromStr + Clone + PartialEq> Matrix<T> {
+  /// Reads a matrix from CSV, one row per record. If the options declare a
+  /// header, the first record is skipped. Errors report the line and column of
+  /// the offending field.
+  pub fn read_csv<R: Read>(reader: R, options: &CsvOptions) -> Result<Matrix<T>, IoError> {
+    Matrix::read_csv_records(read_records(reader, options)?, options)
+  }
+
+  /// Reads a matrix from CSV whose first record holds the column names, and
+  /// returns the names together with the matrix.
+  pub fn read_csv_with_header<R: Read>(
+    reader: R,
+    options: &CsvOptions,
+  ) -> Result<(Vec<String>, Matrix<T>), IoError> {
+    let options = CsvOptions {
+      has_header: true,
+      ..options.clone()
+    };
+    let records = read_records(reader, &options)?;
+    let header = match records.first() {
+      Some(record) => record.iter().map(|f| f.text.trim().to_string()).collect(),
+      None => return Err(IoError::parse(1, 1, "missing header")),
+    };
+    Ok((header, Matrix::read_csv_records(records, &options)?))
+  }
+
+  fn read_csv_records(
+    records: Vec<Vec<Field>>,
+    options: &CsvOptions,
+  ) -> Result<Matrix<T>, IoError> {
+    let skip = if options.has_header { 1 } else { 0 };
+    let n_cols = records.first().map_or(0, |r| r.len());
+    let mut data = vec![];
+    let mut n_rows = 0;
+    for record in records.into_iter().skip(skip) {
+      if record.len() != n_cols {
+        let message = format!("expected {} fields, found {}", n_cols, record.len());
+        return Err(IoError::parse(record[0].line, 1, message));
+      }
+      for field in record {
+        let text = if field.quoted { &field.text[..] } else { field.text.trim() };
+        let text = if !field.quoted && options.na_values.iter().any(|na| na == text) {
+          "NaN"
+        } else {
+          text
+        };
+        match text.parse() {
+          Ok(value) => data.push(value),
+          Err(_) => {
+            let message = format!("cannot parse {:?}", field.text.trim());
+            return Err(IoError::parse(field.line, field.column, message));
+          }
+        }
+      }
+      n_rows += 1;
+    }
+    Ok(Matrix::create_from_data(data, n_rows, if n_rows == 0 { 0 } else { n_cols }))
+  }
+}
+
+impl<T: Display + Clone + PartialEq> Matrix<T> {
+  /// Writes the matrix as CSV, one record per row.
+  pub fn write_csv<W: Write>(&self, writer: W, options: &CsvOptions) -> Result<(), IoError> {
+    self.write_csv_records(writer, None, options)
+  }
+
+  /// Writes the matrix as CSV preceded by a header record holding the column names.
+  pub fn write_csv_with_header<W: Write>(
+    &self,
+    writer: W,
+    header: &[&str],
+    options: &CsvOptions,
+  ) -> Result<(), IoError> {
+    if header.len() != self.n_cols() {
+      panic!("dimensions do not match!");
+    }
+    self.write_csv_records(writer, Some(header), options)
+  }
+
+  fn write_csv_records<W: Write>(
+    &self,
+    mut writer: W,
+    header: Option<&[&str]>,
+    options: &CsvOptions,
+  ) -> Result<(), IoError> {
+    let escape = |text: &str| match options.quote {
+      Some(q) if text.contains([options.delimiter, q, '\n', '\r']) => {
+        let doubled = text.replace(q, &format!("{}{}", q, q));
+        format!("{}{}{}", q, doubled, q)
+      }
+      _ => text.to_string(),
+    };
+    let delimiter = options.delimiter.to_string();
+    if let Some(header) = header {
+      let names = header.iter().map(|h| escape(h)).collect::<Vec<String>>();
+      writeln!(writer, "{}", names.join(&delimiter))?;
+    }
+    for i in 0..self.n_rows() {
+      let fields = (0..self.n_cols())
+        .map(|j| escape(&self.get(i, j).to_string()))
+        .collect::<Vec<String>>();
+      writeln!(writer, "{}", fields.join(&delimiter))?;
+    }
+    writer.flush()?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn read<T: FromStr + Clone + PartialEq>(
+    text: &str,
+    options: &CsvOptions,
+  ) -> Result<Matrix<T>, IoError> {
+    Matrix::read_csv(text.as_bytes(), options)
+  }
+
+  #[test]
+  fn test_read_csv() {
+    let m: Matrix<i64> = read("1,2,3\n4, 5 ,6\n\n", &CsvOptions::default()).unwrap();
+    assert_eq!(m, Matrix::new(vec![vec![1, 2, 3], vec![4, 5, 6]]));
+    let options = CsvOptions {
+      delimiter: ';',
+      has_header: true,
+      comment: Some('#'),
+      ..CsvOptions::default()
+    };
+    let text = "# exported data\nx;y\n1.5;\"2\"\n-3;4e2 # last row\n";
+    let m: Matrix<f64> = read(text, &options).unwrap();
+    assert_eq!(m, Matrix::new(vec![vec![1.5, 2.0], vec![-3.0, 400.0]]));
+  }
+
+  #[test]
+  fn test_header_and_quotes() {
+    let text = "\"name, first\",\"say \"\"hi\"\"\",\"multi\nline\"\n1,2,3\n";
+    let options = CsvOptions::default();
+    let (header, m) = Matrix::<u8>::read_csv_with_header(text.as_bytes(), &options).unwrap();
+    assert_eq!(header, vec!["name, first", "say \"hi\"", "multi\nline"]);
+    assert_eq!(m, Matrix::new(vec![vec![1, 2, 3]]));
+    let mut out = vec![];
+    let names = header.iter().map(|h| h.as_str()).collect::<Vec<&str>>();
+    m.write_csv_with_header(&mut out, &names, &options).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), text);
+  }
+
+  #[test]
+  fn test_missing_values() {
+    let m: Matrix<f64> = read("1,,3\nNA,5,NaN\n", &CsvOptions::default()).unwrap();
+    assert!(m.get(0, 1).is_nan() && m.get(1, 0).is_nan() && m.get(1, 2).is_nan());
+    assert_eq!(m.get(1, 1), 5.0);
+    match read::<i32>("1,,3\n", &CsvOptions::default()) {
+      Err(IoError::Parse { line, column, .. }) => assert_eq!((line, column), (1, 3)),
+      other => panic!("unexpected result {:?}", other),
+    }
+  }
+
+  #[test]
+  fn test_errors() {
+    match read::<i32>("1,2\n3,x4\n", &CsvOptions::default()) {
+      Err(IoError::Parse { line, column, message }) => {
+        assert_eq!((line, column), (2, 3));
+        assert_eq!(message, "cannot parse \"x4\"");
+      }
+      other => panic!("unexpected result {:?}", other),
+    }
+    let e = read::<i32>("1,2\n3\n", &CsvOptions::default()).unwrap_err();
+    assert_eq!(e.to_string(), "line 2, column 1: expected 2 fields, found 1");
+    let e = read::<i32>("\"1,2\n", &CsvOptions::default()).unwrap_err();
+    assert!(matches!(e, IoError::Parse { line: 1, .. }));
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let m = Matrix::new(vec![vec![0.1, -2.5e-10], vec![f64::INFINITY, 3.0]]);
+    let options = CsvOptions {
+      delimiter: '\t',
+      ..CsvOptions::default()
+    };
+    let mut out = vec![];
+    m.write_csv(&mut out, &options).unwrap();
+    assert_eq!(String::from_utf8(out.clone()).unwrap(), "0.1\t-0.00000000025\ninf\t3\n");
+    assert_eq!(Matrix::read_csv(&out[..], &options).unwrap(), m);
+  }
+}
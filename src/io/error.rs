@@ -0,0 +1,55 @@
+use std::fmt;
+
+/// The error returned when reading or writing a matrix fails.
+#[derive(Debug)]
+pub enum IoError {
+  /// The underlying reader or writer failed.
+  Io(std::io::Error),
+  /// A text format could not be parsed. Lines and columns count from one.
+  Parse {
+    line: usize,
+    column: usize,
+    message: String,
+  },
+  /// A binary format is malformed, or a format cannot represent the matrix.
+  Format(String),
+}
+
+impl IoError {
+  pub(crate) fn parse<S: Into<String>>(line: usize, column: usize, message: S) -> IoError {
+    IoError::Parse {
+      line,
+      column,
+      message: message.into(),
+    }
+  }
+}
+
+impl fmt::Display for IoError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      IoError::Io(e) => write!(f, "{}", e),
+      IoError::Parse {
+        line,
+        column,
+        message,
+      } => write!(f, "line {}, column {}: {}", line, column, message),
+      IoError::Format(message) => write!(f, "{}", message),
+    }
+  }
+}
+
+impl std::error::Error for IoError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      IoError::Io(e) => Some(e),
+      _ => None,
+    }
+  }
+}
+
+impl From<std::io::Error> for IoError {
+  fn from(e: std::io::Error) -> IoError {
+    IoError::Io(e)
+  }
+}
@@ -0,0 +1,298 @@
+// Decompression of deflate streams (RFC 1951) and their zlib wrapper (RFC 1950),
+// following the canonical Huffman decoding of zlib's reference decoder `puff`.
+use super::IoError;
+
+const MAX_BITS: usize = 15;
+
+// The base lengths and extra bits of the length symbols 257 to 285.
+const LENGTH_BASE: [u16; 29] = [
+  3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+  163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+  0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+// The base distances and extra bits of the distance symbols 0 to 29.
+const DIST_BASE: [u16; 30] = [
+  1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+  2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+  0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+// The order in which the code length code lengths of a dynamic block are stored.
+const CODE_LENGTH_ORDER: [usize; 19] =
+  [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+fn error<T>(message: &str) -> Result<T, IoError> {
+  Err(IoError::Format(format!("inflate: {}", message)))
+}
+
+struct BitReader<'a> {
+  bytes: &'a [u8],
+  pos: usize,
+  buffer: u32,
+  count: u32,
+}
+
+impl<'a> BitReader<'a> {
+  // Reads `n` <= 16 bits, least significant first.
+  fn bits(&mut self, n: u32) -> Result<u32, IoError> {
+    while self.count < n {
+      match self.bytes.get(self.pos) {
+        Some(&b) => self.buffer |= (b as u32) << self.count,
+        None => return error("unexpected end of stream"),
+      }
+      self.pos += 1;
+      self.count += 8;
+    }
+    let value = self.buffer & ((1 << n) - 1);
+    self.buffer >>= n;
+    self.count -= n;
+    Ok(value)
+  }
+
+  // Discards the bits up to the next byte boundary.
+  fn align(&mut self) {
+    self.buffer = 0;
+    self.count = 0;
+  }
+}
+
+// A canonical Huffman code, given by the number of codes of each length and the
+// symbols ordered by code.
+struct Huffman {
+  counts: [u16; MAX_BITS + 1],
+  symbols: Vec<u16>,
+}
+
+impl Huffman {
+  fn new(lengths: &[u8]) -> Result<Huffman, IoError> {
+    let mut counts = [0; MAX_BITS + 1];
+    for &l in lengths {
+      counts[l as usize] += 1;
+    }
+    // Reject over-subscribed codes, whose codes would not be prefix free.
+    let mut left = 1i32;
+    for &count in &counts[1..] {
+      left = 2 * left - count as i32;
+      if left < 0 {
+        return error("invalid code lengths");
+      }
+    }
+    let mut offsets = [0; MAX_BITS + 1];
+    for len in 1..MAX_BITS {
+      offsets[len + 1] = offsets[len] + counts[len];
+    }
+    let mut symbols = vec![0; lengths.len()];
+    for (symbol, &l) in lengths.iter().enumerate() {
+      if l != 0 {
+        symbols[offsets[l as usize] as usize] = symbol as u16;
+        offsets[l as usize] += 1;
+      }
+    }
+    Ok(Huffman { counts, symbols })
+  }
+
+  fn decode(&self, reader: &mut BitReader) -> Result<u16, IoError> {
+    let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+    for len in 1..=MAX_BITS {
+      code |= reader.bits(1)? as i32;
+      let count = self.counts[len] as i32;
+      if code - first < count {
+        return Ok(self.symbols[(index + code - first) as usize]);
+      }
+      index += count;
+      first = (first + count) << 1;
+      code <<= 1;
+    }
+    error("invalid code")
+  }
+}
+
+fn fixed_codes() -> Result<(Huffman, Huffman), IoError> {
+  let mut lengths = [8; 288];
+  lengths[144..256].iter_mut().for_each(|l| *l = 9);
+  lengths[256..280].iter_mut().for_each(|l| *l = 7);
+  Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), IoError> {
+  let n_lengths = reader.bits(5)? as usize + 257;
+  let n_dists = reader.bits(5)? as usize + 1;
+  let n_codes = reader.bits(4)? as usize + 4;
+  if n_lengths > 286 || n_dists > 30 {
+    return error("too many codes");
+  }
+  let mut lengths = [0; 19];
+  for &k in &CODE_LENGTH_ORDER[..n_codes] {
+    lengths[k] = reader.bits(3)? as u8;
+  }
+  let code_lengths = Huffman::new(&lengths)?;
+  let mut lengths = vec![];
+  while lengths.len() < n_lengths + n_dists {
+    let symbol = code_lengths.decode(reader)?;
+    let (value, repeat) = match symbol {
+      0..=15 => (symbol as u8, 1),
+      16 => match lengths.last() {
+        Some(&last) => (last, 3 + reader.bits(2)?),
+        None => return error("repeated length without a previous one"),
+      },
+      17 => (0, 3 + reader.bits(3)?),
+      _ => (0, 11 + reader.bits(7)?),
+    };
+    if lengths.len() + repeat as usize > n_lengths + n_dists {
+      return error("too many code lengths");
+    }
+    lengths.extend(std::iter::repeat_n(value, repeat as usize));
+  }
+  if lengths[256] == 0 {
+    return error("missing end of block code");
+  }
+  let (literals, dists) = lengths.split_at(n_lengths);
+  Ok((Huffman::new(literals)?, Huffman::new(dists)?))
+}
+
+fn inflate_block(
+  reader: &mut BitReader,
+  out: &mut Vec<u8>,
+  literals: &Huffman,
+  dists: &Huffman,
+) -> Result<(), IoError> {
+  loop {
+    let symbol = literals.decode(reader)? as usize;
+    if symbol < 256 {
+      out.push(symbol as u8);
+    } else if symbol == 256 {
+      return Ok(());
+    } else {
+      let symbol = symbol - 257;
+      if symbol >= 29 {
+        return error("invalid length symbol");
+      }
+      let len = LENGTH_BASE[symbol] as usize + reader.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+      let symbol = dists.decode(reader)? as usize;
+      if symbol >= 30 {
+        return error("invalid distance symbol");
+      }
+      let dist = DIST_BASE[symbol] as usize + reader.bits(DIST_EXTRA[symbol] as u32)? as usize;
+      if dist > out.len() {
+        return error("distance too far back");
+      }
+      // The copy may overlap its own output, which repeats the last `dist` bytes.
+      let start = out.len() - dist;
+      for k in 0..len {
+        out.push(out[start + k]);
+      }
+    }
+  }
+}
+
+// Decompresses a raw deflate stream and returns the data and the number of bytes
+// consumed.
+pub(crate) fn inflate(bytes: &[u8]) -> Result<(Vec<u8>, usize), IoError> {
+  let mut reader = BitReader {
+    bytes,
+    pos: 0,
+    buffer: 0,
+    count: 0,
+  };
+  let mut out = vec![];
+  loop {
+    let last = reader.bits(1)? == 1;
+    match reader.bits(2)? {
+      0 => {
+        reader.align();
+        let header = match bytes.get(reader.pos..reader.pos + 4) {
+          Some(header) => header,
+          None => return error("unexpected end of stream"),
+        };
+        let len = u16::from_le_bytes([header[0], header[1]]);
+        if len != !u16::from_le_bytes([header[2], header[3]]) {
+          return error("invalid stored block length");
+        }
+        let start = reader.pos + 4;
+        match bytes.get(start..start + len as usize) {
+          Some(data) => out.extend_from_slice(data),
+          None => return error("unexpected end of stream"),
+        }
+        reader.pos = start + len as usize;
+      }
+      1 => {
+        let (literals, dists) = fixed_codes()?;
+        inflate_block(&mut reader, &mut out, &literals, &dists)?;
+      }
+      2 => {
+        let (literals, dists) = dynamic_codes(&mut reader)?;
+        inflate_block(&mut reader, &mut out, &literals, &dists)?;
+      }
+      _ => return error("invalid block type"),
+    }
+    if last {
+      return Ok((out, reader.pos));
+    }
+  }
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+  let (mut a, mut b) = (1u32, 0u32);
+  for chunk in bytes.chunks(5552) {
+    for &x in chunk {
+      a += x as u32;
+      b += a;
+    }
+    a %= 65521;
+    b %= 65521;
+  }
+  (b << 16) | a
+}
+
+// Decompresses a zlib stream and verifies its checksum.
+pub(crate) fn zlib_decompress(bytes: &[u8]) -> Result<Vec<u8>, IoError> {
+  let valid = bytes.len() >= 2 && bytes[0] & 0x0F == 8;
+  if !valid || !u16::from_be_bytes([bytes[0], bytes[1]]).is_multiple_of(31) {
+    return error("invalid zlib header");
+  }
+  if bytes[1] & 0x20 != 0 {
+    return error("preset dictionaries are not supported");
+  }
+  let (out, len) = inflate(&bytes[2..])?;
+  match bytes.get(2 + len..6 + len) {
+    Some(c) if u32::from_be_bytes([c[0], c[1], c[2], c[3]]) == adler32(&out) => Ok(out),
+    Some(_) => error("checksum mismatch"),
+    None => error("unexpected end of stream"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn hex(s: &str) -> Vec<u8> {
+    (0..s.len()).step_by(2).map(|k| u8::from_str_radix(&s[k..k + 2], 16).unwrap()).collect()
+  }
+
+  #[test]
+  fn test_zlib_decompress() {
+    // Streams written by zlib with a stored, a fixed and a dynamic Huffman block.
+    let stored = zlib_decompress(&hex("7801010600f9ff73746f726564093c0292")).unwrap();
+    assert_eq!(stored, b"stored");
+    let fixed = zlib_decompress(&hex("78dacb48cdc9c957c84090003a2e067d")).unwrap();
+    assert_eq!(fixed, b"hello hello hello");
+    let dynamic = "78da45cab70100300803b05b0d98ceff6bc66816a4143d99d4323a82227b15c81a4ebb9bdd72f1\
+                   db03fd021402";
+    let expected = (0..50u32).map(|i| ((i * i * i + i / 3) % 13 + 97) as u8).collect::<Vec<u8>>();
+    assert_eq!(zlib_decompress(&hex(dynamic)).unwrap(), expected);
+  }
+
+  #[test]
+  fn test_errors() {
+    let mut bytes = hex("78dacb48cdc9c957c84090003a2e067d");
+    assert!(zlib_decompress(&bytes[..bytes.len() - 1]).is_err());
+    assert!(zlib_decompress(&bytes[..8]).is_err());
+    bytes[15] ^= 1;
+    let e = zlib_decompress(&bytes).unwrap_err();
+    assert_eq!(e.to_string(), "inflate: checksum mismatch");
+    assert!(zlib_decompress(b"\x78\x00").is_err());
+  }
+}
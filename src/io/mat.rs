@@ -0,0 +1,766 @@
+use super::inflate::zlib_decompress;
+use super::IoError;
+use crate::matrix::complex::Complex;
+use crate::Matrix;
+use std::convert::{TryFrom, TryInto};
+use std::io::{Read, Write};
+
+// The data types of MAT-file elements.
+const MI_INT8: u32 = 1;
+const MI_UINT8: u32 = 2;
+const MI_INT16: u32 = 3;
+const MI_UINT16: u32 = 4;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_SINGLE: u32 = 7;
+const MI_DOUBLE: u32 = 9;
+const MI_INT64: u32 = 12;
+const MI_UINT64: u32 = 13;
+const MI_MATRIX: u32 = 14;
+const MI_COMPRESSED: u32 = 15;
+
+// The array classes and flags of `miMATRIX` elements.
+const MX_SPARSE: u32 = 5;
+const FLAG_COMPLEX: u32 = 0x0800;
+const FLAG_LOGICAL: u32 = 0x0200;
+
+/// The MATLAB class of a numeric array.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatClass {
+  Double,
+  Single,
+  Int8,
+  UInt8,
+  Int16,
+  UInt16,
+  Int32,
+  UInt32,
+  Int64,
+  UInt64,
+  Logical,
+}
+
+impl MatClass {
+  // The class number of `miMATRIX` elements, and the data type values of the
+  // class are written as.
+  fn code(self) -> (u32, u32) {
+    match self {
+      MatClass::Double => (6, MI_DOUBLE),
+      MatClass::Single => (7, MI_SINGLE),
+      MatClass::Int8 => (8, MI_INT8),
+      MatClass::UInt8 => (9, MI_UINT8),
+      MatClass::Int16 => (10, MI_INT16),
+      MatClass::UInt16 => (11, MI_UINT16),
+      MatClass::Int32 => (12, MI_INT32),
+      MatClass::UInt32 => (13, MI_UINT32),
+      MatClass::Int64 => (14, MI_INT64),
+      MatClass::UInt64 => (15, MI_UINT64),
+      MatClass::Logical => (9, MI_UINT8),
+    }
+  }
+
+  fn from_code(class: u32, logical: bool) -> Option<MatClass> {
+    let classes = [
+      MatClass::Double,
+      MatClass::Single,
+      MatClass::Int8,
+      MatClass::UInt8,
+      MatClass::Int16,
+      MatClass::UInt16,
+      MatClass::Int32,
+      MatClass::UInt32,
+      MatClass::Int64,
+      MatClass::UInt64,
+    ];
+    match class {
+      _ if logical => Some(MatClass::Logical),
+      6..=15 => Some(classes[class as usize - 6]),
+      _ => None,
+    }
+  }
+}
+
+/// A value as stored in a MAT-file. Integer and logical arrays hold integers,
+/// floating point arrays hold floats, and MATLAB may store either with a
+/// smaller data type than the class of the array.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MatNumber {
+  Int(i128),
+  Float(f64),
+}
+
+/// A scalar type that can be stored in MAT-files.
+pub trait MatValue: Clone + PartialEq {
+  /// The class of arrays of this type.
+  const CLASS: MatClass;
+
+  /// Whether arrays of this type are written as complex arrays.
+  const COMPLEX: bool;
+
+  /// Converts a stored value given by its real and imaginary part.
+  fn from_mat(re: MatNumber, im: MatNumber) -> Self;
+
+  /// Returns the real and imaginary part of the value.
+  fn to_mat(&self) -> (MatNumber, MatNumber);
+}
+
+macro_rules! mat_value_impl {
+  ($($T:ty, $class:expr, $variant:ident);*) => {
+    $(
+      impl MatValue for $T {
+        const CLASS: MatClass = $class;
+        const COMPLEX: bool = false;
+        fn from_mat(re: MatNumber, _: MatNumber) -> $T {
+          match re {
+            MatNumber::Int(i) => i as $T,
+            MatNumber::Float(f) => f as $T,
+          }
+        }
+        fn to_mat(&self) -> (MatNumber, MatNumber) {
+          (MatNumber::$variant(*self as _), MatNumber::Int(0))
+        }
+      }
+    )*
+  };
+}
+mat_value_impl!(f64, MatClass::Double, Float; f32, MatClass::Single, Float);
+mat_value_impl!(i8, MatClass::Int8, Int; u8, MatClass::UInt8, Int; i16, MatClass::Int16, Int);
+mat_value_impl!(u16, MatClass::UInt16, Int; i32, MatClass::Int32, Int);
+mat_value_impl!(u32, MatClass::UInt32, Int; i64, MatClass::Int64, Int);
+mat_value_impl!(u64, MatClass::UInt64, Int);
+
+impl MatValue for bool {
+  const CLASS: MatClass = MatClass::Logical;
+  const COMPLEX: bool = false;
+  fn from_mat(re: MatNumber, _: MatNumber) -> bool {
+    re != MatNumber::Int(0) && re != MatNumber::Float(0.0)
+  }
+  fn to_mat(&self) -> (MatNumber, MatNumber) {
+    (MatNumber::Int(*self as i128), MatNumber::Int(0))
+  }
+}
+
+macro_rules! mat_complex_impl {
+  ($($T:ty),*) => {
+    $(
+      impl MatValue for Complex<$T> {
+        const CLASS: MatClass = <$T>::CLASS;
+        const COMPLEX: bool = true;
+        fn from_mat(re: MatNumber, im: MatNumber) -> Complex<$T> {
+          Complex::new(<$T>::from_mat(re, im), <$T>::from_mat(im, re))
+        }
+        fn to_mat(&self) -> (MatNumber, MatNumber) {
+          (self.re.to_mat().0, self.im.to_mat().0)
+        }
+      }
+    )*
+  };
+}
+mat_complex_impl!(f32, f64);
+
+fn format_error<T>(message: &str) -> Result<T, IoError> {
+  Err(IoError::Format(format!("mat: {}", message)))
+}
+
+// Reads MAT-file data in the byte order given by the file.
+struct Source<'a> {
+  bytes: &'a [u8],
+  big_endian: bool,
+}
+
+struct Element<'a> {
+  data_type: u32,
+  data: &'a [u8],
+}
+
+impl<'a> Source<'a> {
+  fn u32_at(&self, at: usize) -> Result<u32, IoError> {
+    match self.bytes.get(at..at + 4) {
+      Some(b) if self.big_endian => Ok(u32::from_be_bytes(b.try_into().unwrap())),
+      Some(b) => Ok(u32::from_le_bytes(b.try_into().unwrap())),
+      None => format_error("unexpected end of file"),
+    }
+  }
+
+  // Reads the element at `at` and returns it with the offset of the next one.
+  // Small elements pack the type and size into the first four bytes of the tag.
+  fn element(&self, at: usize) -> Result<(Element<'a>, usize), IoError> {
+    let first = self.u32_at(at)?;
+    let (data_type, size, start) = if first >> 16 != 0 {
+      (first & 0xFFFF, (first >> 16) as usize, at + 4)
+    } else {
+      (first, self.u32_at(at + 4)? as usize, at + 8)
+    };
+    let data = match self.bytes.get(start..start + size) {
+      Some(data) => data,
+      None => return format_error("unexpected end of file"),
+    };
+    let next = match data_type {
+      MI_COMPRESSED => start + size,
+      _ if start == at + 4 => at + 8,
+      _ => start + size.div_ceil(8) * 8,
+    };
+    Ok((Element { data_type, data }, next))
+  }
+
+  fn numbers(&self, element: &Element) -> Result<Vec<MatNumber>, IoError> {
+    let big = self.big_endian;
+    macro_rules! decode {
+      ($T:ty, $variant:ident) => {
+        element
+          .data
+          .chunks_exact(std::mem::size_of::<$T>())
+          .map(|b| {
+            let b = b.try_into().unwrap();
+            let v = if big { <$T>::from_be_bytes(b) } else { <$T>::from_le_bytes(b) };
+            MatNumber::$variant(v.into())
+          })
+          .collect()
+      };
+    }
+    Ok(match element.data_type {
+      MI_INT8 => decode!(i8, Int),
+      MI_UINT8 => decode!(u8, Int),
+      MI_INT16 => decode!(i16, Int),
+      MI_UINT16 => decode!(u16, Int),
+      MI_INT32 => decode!(i32, Int),
+      MI_UINT32 => decode!(u32, Int),
+      MI_INT64 => decode!(i64, Int),
+      MI_UINT64 => decode!(u64, Int),
+      MI_SINGLE => decode!(f32, Float),
+      MI_DOUBLE => decode!(f64, Float),
+      t => return format_error(&format!("unsupported data type {}", t)),
+    })
+  }
+
+  fn indices(&self, element: &Element) -> Result<Vec<usize>, IoError> {
+    self
+      .numbers(element)?
+      .into_iter()
+      .map(|n| match n {
+        MatNumber::Int(i) => usize::try_from(i).or_else(|_| format_error("invalid index")),
+        _ => format_error("invalid index"),
+      })
+      .collect()
+  }
+}
+
+struct Variable {
+  name: String,
+  class: MatClass,
+  n_rows: usize,
+  n_cols: usize,
+  // The stored values in column-major order.
+  re: Vec<MatNumber>,
+  im: Option<Vec<MatNumber>>,
+  // The column-major positions of the stored values of a sparse array, whose
+  // other entries are zero.
+  positions: Option<Vec<usize>>,
+}
+
+// Parses the sub-elements of an `miMATRIX` element. Returns `None` for arrays
+// which are not numeric matrices.
+fn parse_matrix(source: &Source) -> Result<Option<Variable>, IoError> {
+  let mut elements = vec![];
+  let mut at = 0;
+  while at < source.bytes.len() {
+    let (element, next) = source.element(at)?;
+    elements.push(element);
+    at = next;
+  }
+  if elements.len() < 3 {
+    return format_error("incomplete array");
+  }
+  let flags = match source.indices(&elements[0])?.first() {
+    Some(&flags) => flags as u32,
+    None => return format_error("missing array flags"),
+  };
+  let dims = source.indices(&elements[1])?;
+  let name = String::from_utf8_lossy(elements[2].data).into_owned();
+  let (class_code, complex) = (flags & 0xFF, flags & FLAG_COMPLEX != 0);
+  let class = match MatClass::from_code(class_code, flags & FLAG_LOGICAL != 0) {
+    Some(class) => class,
+    None if class_code == MX_SPARSE => MatClass::Double,
+    None => return Ok(None),
+  };
+  if dims.len() != 2 {
+    return Ok(None);
+  }
+  let (n_rows, n_cols) = (dims[0], dims[1]);
+  let len = match n_rows.checked_mul(n_cols) {
+    Some(len) => len,
+    None => return format_error(&format!("{:?} is too large", name)),
+  };
+  let sparse = class_code == MX_SPARSE;
+  let first = if sparse { 5 } else { 3 };
+  if elements.len() < first + if complex { 2 } else { 1 } {
+    return format_error(&format!("missing data of {:?}", name));
+  }
+  let parts = &elements[first..];
+  let re = source.numbers(&parts[0])?;
+  let im = match complex {
+    true => Some(source.numbers(&parts[1])?),
+    false => None,
+  };
+  let (re, im, positions) = if sparse {
+    // Compressed sparse columns: the rows of the nonzero entries, and for each
+    // column the index of its first entry. Only the nonzero entries are kept, as
+    // the declared shape may be far larger than the file.
+    let rows = source.indices(&elements[3])?;
+    let starts = source.indices(&elements[4])?;
+    if n_cols.checked_add(1) != Some(starts.len()) {
+      return format_error(&format!("invalid column starts of {:?}", name));
+    }
+    let zero = MatNumber::Float(0.0);
+    let (mut sparse_re, mut positions) = (vec![], vec![]);
+    let mut sparse_im = im.as_ref().map(|_| vec![]);
+    for j in 0..n_cols {
+      for k in starts[j]..starts[j + 1] {
+        match (rows.get(k), re.get(k)) {
+          (Some(&i), Some(&v)) if i < n_rows => {
+            sparse_re.push(v);
+            positions.push(j * n_rows + i);
+          }
+          _ => return format_error(&format!("invalid sparse data of {:?}", name)),
+        }
+        if let (Some(sparse_im), Some(im)) = (sparse_im.as_mut(), im.as_ref()) {
+          sparse_im.push(im.get(k).copied().unwrap_or(zero));
+        }
+      }
+    }
+    (sparse_re, sparse_im, Some(positions))
+  } else {
+    if re.len() != len || im.as_ref().is_some_and(|im| im.len() != re.len()) {
+      return format_error(&format!("wrong number of values in {:?}", name));
+    }
+    (re, im, None)
+  };
+  Ok(Some(Variable {
+    name,
+    class,
+    n_rows,
+    n_cols,
+    re,
+    im,
+    positions,
+  }))
+}
+
+/// A MATLAB Level 5 MAT-file, as written by `save -v7` and `save -v6`. The file
+/// is read into memory, and its variables are converted on request.
+///
+/// Only numeric and logical two-dimensional arrays are read, and other
+/// variables such as strings, cells and structs are skipped. Sparse arrays are
+/// read into dense matrices.
+pub struct MatFile {
+  variables: Vec<Variable>,
+}
+
+impl MatFile {
+  /// Reads the variables of a MAT-file in either byte order, decompressing
+  /// compressed variables.
+  pub fn new<R: Read>(mut reader: R) -> Result<MatFile, IoError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    let big_endian = match bytes.get(126..128) {
+      Some(b"IM") => false,
+      Some(b"MI") => true,
+      _ => return format_error("not a level 5 MAT-file"),
+    };
+    let version = [bytes[124], bytes[125]];
+    let version = match big_endian {
+      true => u16::from_be_bytes(version),
+      false => u16::from_le_bytes(version),
+    };
+    if version != 0x0100 {
+      return format_error(&format!("unsupported version {:#06x}", version));
+    }
+    let mut variables = vec![];
+    let source = Source {
+      bytes: &bytes[128..],
+      big_endian,
+    };
+    let mut at = 0;
+    while at < source.bytes.len() {
+      let (element, next) = source.element(at)?;
+      let inflated;
+      let element = if element.data_type == MI_COMPRESSED {
+        inflated = zlib_decompress(element.data)?;
+        let inner = Source {
+          bytes: &inflated,
+          big_endian,
+        };
+        inner.element(0)?.0
+      } else {
+        element
+      };
+      if element.data_type == MI_MATRIX {
+        let contents = Source {
+          bytes: element.data,
+          big_endian,
+        };
+        if let Some(variable) = parse_matrix(&contents)? {
+          variables.push(variable);
+        }
+      }
+      at = next;
+    }
+    Ok(MatFile { variables })
+  }
+
+  /// Returns the names of the variables.
+  pub fn names(&self) -> Vec<&str> {
+    self.variables.iter().map(|v| v.name.as_str()).collect()
+  }
+
+  /// Returns the class of the named variable.
+  pub fn class(&self, name: &str) -> Option<MatClass> {
+    self.variables.iter().find(|v| v.name == name).map(|v| v.class)
+  }
+
+  /// Returns whether the named variable is stored as a sparse array.
+  pub fn is_sparse(&self, name: &str) -> Option<bool> {
+    self.variables.iter().find(|v| v.name == name).map(|v| v.positions.is_some())
+  }
+
+  /// Reads the named variable. Its class must be that of `T`, and only complex
+  /// types can hold complex arrays.
+  pub fn get<T: MatValue>(&self, name: &str) -> Result<Matrix<T>, IoError> {
+    let variable = match self.variables.iter().find(|v| v.name == name) {
+      Some(variable) => variable,
+      None => return format_error(&format!("no variable named {:?}", name)),
+    };
+    if variable.class != T::CLASS || (variable.im.is_some() && !T::COMPLEX) {
+      let complex = if variable.im.is_some() { "complex " } else { "" };
+      let (class, target) = (variable.class, T::CLASS);
+      let message = format!("cannot read {}{:?} array {:?} as {:?}", complex, class, name, target);
+      return format_error(&message);
+    }
+    let (n_rows, n_cols) = (variable.n_rows, variable.n_cols);
+    let value = |index: usize| {
+      let im = variable.im.as_ref().map_or(MatNumber::Int(0), |im| im[index]);
+      T::from_mat(variable.re[index], im)
+    };
+    // The shape of a sparse array is not backed by the file, so a failing
+    // allocation is reported instead of aborting.
+    let mut data = vec![];
+    if data.try_reserve_exact(n_rows * n_cols).is_err() {
+      return format_error(&format!("{:?} is too large", name));
+    }
+    match &variable.positions {
+      Some(positions) => {
+        let zero = MatNumber::Float(0.0);
+        data.resize(n_rows * n_cols, T::from_mat(zero, zero));
+        for (index, &k) in positions.iter().enumerate() {
+          data[(k % n_rows) * n_cols + k / n_rows] = value(index);
+        }
+      }
+      None => data.extend((0..n_rows * n_cols).map(|k| value((k % n_cols) * n_rows + k / n_cols))),
+    }
+    Ok(Matrix::create_from_data(data, n_rows, n_cols))
+  }
+}
+
+// Appends an element padded to a multiple of eight bytes. Fails if the element
+// is too large for its 32-bit size field.
+fn push_element(out: &mut Vec<u8>, data_type: u32, data: &[u8]) -> Result<(), IoError> {
+  let size = match u32::try_from(data.len()) {
+    Ok(size) => size,
+    Err(_) => return format_error("array is too large for a MAT-file"),
+  };
+  out.extend_from_slice(&data_type.to_le_bytes());
+  out.extend_from_slice(&size.to_le_bytes());
+  out.extend_from_slice(data);
+  out.resize(out.len() + (8 - data.len() % 8) % 8, 0);
+  Ok(())
+}
+
+fn encode(data_type: u32, values: &[MatNumber]) -> Result<Vec<u8>, IoError> {
+  let mut out = vec![];
+  for v in values {
+    let (i, f) = match *v {
+      MatNumber::Int(i) => (i, i as f64),
+      MatNumber::Float(f) => (f as i128, f),
+    };
+    macro_rules! push_int {
+      ($T:ty) => {
+        match <$T>::try_from(i) {
+          Ok(i) => out.extend_from_slice(&i.to_le_bytes()),
+          Err(_) => return format_error(&format!("value {} does not fit the array class", i)),
+        }
+      };
+    }
+    match data_type {
+      MI_INT8 => push_int!(i8),
+      MI_UINT8 => push_int!(u8),
+      MI_INT16 => push_int!(i16),
+      MI_UINT16 => push_int!(u16),
+      MI_INT32 => push_int!(i32),
+      MI_UINT32 => push_int!(u32),
+      MI_INT64 => push_int!(i64),
+      MI_UINT64 => push_int!(u64),
+      MI_SINGLE => out.extend_from_slice(&(f as f32).to_le_bytes()),
+      _ => out.extend_from_slice(&f.to_le_bytes()),
+    }
+  }
+  Ok(out)
+}
+
+// Encodes dimensions and sparse indices, which MAT-files store as 32-bit integers.
+fn encode_indices(indices: &[usize]) -> Result<Vec<u8>, IoError> {
+  let mut out = vec![];
+  for &i in indices {
+    match i32::try_from(i) {
+      Ok(i) => out.extend_from_slice(&i.to_le_bytes()),
+      Err(_) => return format_error("array is too large for a MAT-file"),
+    }
+  }
+  Ok(out)
+}
+
+/// Writes a MATLAB Level 5 MAT-file without compression, which MATLAB reads
+/// like files written by `save -v6`.
+pub struct MatWriter<W: Write> {
+  writer: W,
+  names: Vec<String>,
+}
+
+impl<W: Write> MatWriter<W> {
+  /// Writes the header of the file.
+  pub fn new(mut writer: W) -> Result<MatWriter<W>, IoError> {
+    let text = "MATLAB 5.0 MAT-file, Platform: matrix_algebra";
+    let mut header = format!("{:<116}", text).into_bytes();
+    header.extend_from_slice(&[0; 8]);
+    header.extend_from_slice(&0x0100u16.to_le_bytes());
+    header.extend_from_slice(b"IM");
+    writer.write_all(&header)?;
+    Ok(MatWriter {
+      writer,
+      names: vec![],
+    })
+  }
+
+  /// Adds a matrix as a full array under the given name, which must be a valid
+  /// MATLAB variable name not yet used in the file.
+  pub fn add<T: MatValue>(&mut self, name: &str, m: &Matrix<T>) -> Result<(), IoError> {
+    self.add_array(name, m, false)
+  }
+
+  /// Adds a matrix as a sparse array, storing only its nonzero entries. MATLAB
+  /// supports sparse arrays of the classes double and logical only.
+  pub fn add_sparse<T: MatValue>(&mut self, name: &str, m: &Matrix<T>) -> Result<(), IoError> {
+    if T::CLASS != MatClass::Double && T::CLASS != MatClass::Logical {
+      return format_error(&format!("sparse arrays of class {:?} are not supported", T::CLASS));
+    }
+    self.add_array(name, m, true)
+  }
+
+  fn add_array<T: MatValue>(
+    &mut self,
+    name: &str,
+    m: &Matrix<T>,
+    sparse: bool,
+  ) -> Result<(), IoError> {
+    let valid = name.len() <= 63
+      && name.starts_with(|c: char| c.is_ascii_alphabetic())
+      && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+      return format_error(&format!("invalid variable name {:?}", name));
+    }
+    if self.names.iter().any(|n| n == name) {
+      return format_error(&format!("duplicate variable name {:?}", name));
+    }
+    let (n_rows, n_cols) = (m.n_rows(), m.n_cols());
+    let (class, data_type) = T::CLASS.code();
+    let mut flags = if sparse { MX_SPARSE } else { class };
+    if T::COMPLEX {
+      flags |= FLAG_COMPLEX;
+    }
+    if T::CLASS == MatClass::Logical {
+      flags |= FLAG_LOGICAL;
+    }
+    // Values are stored in column-major order, and sparse arrays hold only the
+    // nonzero ones.
+    let zero = T::from_mat(MatNumber::Int(0), MatNumber::Int(0));
+    let mut entries = vec![];
+    let mut starts = vec![0];
+    for j in 0..n_cols {
+      for i in 0..n_rows {
+        if !sparse || m.get(i, j) != zero {
+          entries.push((i, m.get(i, j).to_mat()));
+        }
+      }
+      starts.push(entries.len());
+    }
+    let mut body = vec![];
+    let nzmax = match u32::try_from(if sparse { entries.len() } else { 0 }) {
+      Ok(nzmax) => nzmax,
+      Err(_) => return format_error("array is too large for a MAT-file"),
+    };
+    let flags = [flags.to_le_bytes(), nzmax.to_le_bytes()].concat();
+    push_element(&mut body, MI_UINT32, &flags)?;
+    push_element(&mut body, MI_INT32, &encode_indices(&[n_rows, n_cols])?)?;
+    push_element(&mut body, MI_INT8, name.as_bytes())?;
+    if sparse {
+      let rows = entries.iter().map(|e| e.0).collect::<Vec<usize>>();
+      push_element(&mut body, MI_INT32, &encode_indices(&rows)?)?;
+      push_element(&mut body, MI_INT32, &encode_indices(&starts)?)?;
+    }
+    let re = entries.iter().map(|e| (e.1).0).collect::<Vec<MatNumber>>();
+    push_element(&mut body, data_type, &encode(data_type, &re)?)?;
+    if T::COMPLEX {
+      let im = entries.iter().map(|e| (e.1).1).collect::<Vec<MatNumber>>();
+      push_element(&mut body, data_type, &encode(data_type, &im)?)?;
+    }
+    let mut element = vec![];
+    push_element(&mut element, MI_MATRIX, &body)?;
+    self.writer.write_all(&element)?;
+    self.names.push(name.to_string());
+    Ok(())
+  }
+
+  /// Flushes the file and returns the underlying writer.
+  pub fn finish(mut self) -> Result<W, IoError> {
+    self.writer.flush()?;
+    Ok(self.writer)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn write(f: impl FnOnce(&mut MatWriter<Vec<u8>>)) -> MatFile {
+    let mut writer = MatWriter::new(vec![]).unwrap();
+    f(&mut writer);
+    let bytes = writer.finish().unwrap();
+    assert_eq!(bytes.len() % 8, 0);
+    MatFile::new(&bytes[..]).unwrap()
+  }
+
+  fn header(version: [u8; 2], endian: &[u8]) -> Vec<u8> {
+    let mut header = format!("{:<124}", "MATLAB 5.0 MAT-file").into_bytes();
+    header.extend_from_slice(&version);
+    header.extend_from_slice(endian);
+    header
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let a = Matrix::new(vec![vec![1.0, 2.5, -1e300], vec![-3.0, 4.0, f64::INFINITY]]);
+    let n = Matrix::new(vec![vec![i16::MIN, 7]]);
+    let z = Matrix::new(vec![vec![Complex::new(1.0f32, -2.0)], vec![Complex::new(0.0, 0.5)]]);
+    let b = Matrix::new(vec![vec![true, false], vec![false, true]]);
+    let mat = write(|w| {
+      w.add("a", &a).unwrap();
+      w.add("n", &n).unwrap();
+      w.add("z", &z).unwrap();
+      w.add("flags", &b).unwrap();
+      w.add("u", &Matrix::new(vec![vec![u64::MAX]])).unwrap();
+    });
+    assert_eq!(mat.names(), vec!["a", "n", "z", "flags", "u"]);
+    assert_eq!(mat.class("z"), Some(MatClass::Single));
+    assert_eq!(mat.class("flags"), Some(MatClass::Logical));
+    assert_eq!(mat.get::<f64>("a").unwrap(), a);
+    assert_eq!(mat.get::<i16>("n").unwrap(), n);
+    assert_eq!(mat.get::<Complex<f32>>("z").unwrap(), z);
+    assert_eq!(mat.get::<bool>("flags").unwrap(), b);
+    assert_eq!(mat.get::<u64>("u").unwrap().get(0, 0), u64::MAX);
+  }
+
+  #[test]
+  fn test_sparse() {
+    let mut s = Matrix::zeros(3, 4);
+    s.set(2, 0, 1.5);
+    s.set(0, 3, -2.0);
+    let mut c: Matrix<Complex<f64>> = Matrix::zeros(2, 2);
+    c.set(1, 1, Complex::new(0.0, 3.0));
+    let mat = write(|w| {
+      w.add_sparse("s", &s).unwrap();
+      w.add_sparse("c", &c).unwrap();
+      w.add_sparse("b", &Matrix::new(vec![vec![false, true]])).unwrap();
+      assert!(w.add_sparse("i", &Matrix::new(vec![vec![1i32]])).is_err());
+    });
+    assert_eq!(mat.is_sparse("s"), Some(true));
+    assert_eq!(mat.get::<f64>("s").unwrap(), s);
+    assert_eq!(mat.get::<Complex<f64>>("c").unwrap(), c);
+    assert_eq!(mat.get::<bool>("b").unwrap(), Matrix::new(vec![vec![false, true]]));
+  }
+
+  #[test]
+  fn test_compressed() {
+    // The variable x = [1 2.5; -3 4] compressed by zlib, as written by `save -v7`.
+    let hex = "78dae36360608800623620e680d220c00ae533413123145740e53981588101063ed843688e0310\
+               9ac501420b3800007bc303ba";
+    let data = (0..hex.len())
+      .step_by(2)
+      .map(|k| u8::from_str_radix(&hex[k..k + 2], 16).unwrap())
+      .collect::<Vec<u8>>();
+    let mut bytes = header([0, 1], b"IM");
+    bytes.extend_from_slice(&MI_COMPRESSED.to_le_bytes());
+    bytes.extend_from_slice(&u32::try_from(data.len()).unwrap().to_le_bytes());
+    bytes.extend_from_slice(&data);
+    let mat = MatFile::new(&bytes[..]).unwrap();
+    assert_eq!(mat.is_sparse("x"), Some(false));
+    let x = mat.get::<f64>("x").unwrap();
+    assert_eq!(x, Matrix::new(vec![vec![1.0, 2.5], vec![-3.0, 4.0]]));
+  }
+
+  #[test]
+  fn test_big_endian() {
+    let element = |data_type: u32, data: &[u8]| {
+      let size = u32::try_from(data.len()).unwrap();
+      let mut out = [data_type.to_be_bytes(), size.to_be_bytes()].concat();
+      out.extend_from_slice(data);
+      out.resize(out.len().div_ceil(8) * 8, 0);
+      out
+    };
+    // A double array stored as bytes, with its name in a small element.
+    let mut body = element(MI_UINT32, &[[0, 0, 0, 6], [0; 4]].concat());
+    body.extend(element(MI_INT32, &[2i32.to_be_bytes(), 3i32.to_be_bytes()].concat()));
+    body.extend_from_slice(&((2u32 << 16) | MI_INT8).to_be_bytes());
+    body.extend_from_slice(b"be\0\0");
+    body.extend(element(MI_UINT8, &[1, 2, 3, 4, 5, 6]));
+    let mut bytes = header([1, 0], b"MI");
+    bytes.extend(element(MI_MATRIX, &body));
+    let mat = MatFile::new(&bytes[..]).unwrap();
+    let m = mat.get::<f64>("be").unwrap();
+    assert_eq!(m, Matrix::new(vec![vec![1.0, 3.0, 5.0], vec![2.0, 4.0, 6.0]]));
+  }
+
+  #[test]
+  fn test_errors() {
+    let mat = write(|w| {
+      w.add("z", &Matrix::new(vec![vec![Complex::new(1.0, 1.0)]])).unwrap();
+      assert!(w.add("z", &Matrix::new(vec![vec![1.0]])).is_err());
+      assert!(w.add("1x", &Matrix::new(vec![vec![1.0]])).is_err());
+      assert!(w.add("a b", &Matrix::new(vec![vec![1.0]])).is_err());
+    });
+    let e = mat.get::<f64>("z").unwrap_err();
+    assert_eq!(e.to_string(), "mat: cannot read complex Double array \"z\" as Double");
+    assert!(mat.get::<Complex<f32>>("z").is_err());
+    assert!(mat.get::<f64>("y").is_err());
+    assert!(MatFile::new(&header([0, 2], b"IM")[..]).is_err());
+    assert!(MatFile::new(&b"not a mat-file"[..]).is_err());
+  }
+
+  #[test]
+  fn test_corrupt_shape() {
+    let mut s = Matrix::zeros(2, 1);
+    s.set(1, 0, 1.5);
+    let mut writer = MatWriter::new(vec![]).unwrap();
+    writer.add_sparse("s", &s).unwrap();
+    writer.add("d", &s).unwrap();
+    let mut bytes = writer.finish().unwrap();
+    // The number of rows of the first array follows the header, the tag of the
+    // array and its flags. Reading the file must not allocate the dense matrix.
+    bytes[160..164].copy_from_slice(&i32::MAX.to_le_bytes());
+    let mat = MatFile::new(&bytes[..]).unwrap();
+    assert_eq!(mat.is_sparse("s"), Some(true));
+    // The dense array ends with its name and its two values.
+    let d = bytes.len() - 48;
+    bytes[d..d + 8].copy_from_slice(&[3i32.to_le_bytes(), 1i32.to_le_bytes()].concat());
+    let e = MatFile::new(&bytes[..]).err().unwrap();
+    assert_eq!(e.to_string(), "mat: wrong number of values in \"d\"");
+  }
+}
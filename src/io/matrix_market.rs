@@ -0,0 +1,469 @@
+use super::IoError;
+use crate::matrix::complex::Complex;
+use crate::matrix::num::Ring;
+use crate::Matrix;
+use std::io::{BufRead, BufReader, Read, Write};
+
+/// Whether a Matrix Market file lists all entries (`array`) or only the nonzero
+/// ones with their indices (`coordinate`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MtxFormat {
+  Array,
+  Coordinate,
+}
+
+/// The type of the entries of a Matrix Market file. `Pattern` files list only
+/// the positions of the nonzero entries, which are read as one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MtxField {
+  Real,
+  Integer,
+  Complex,
+  Pattern,
+}
+
+/// The symmetry of a Matrix Market file, of which only the lower triangle is
+/// stored unless it is `General`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MtxSymmetry {
+  General,
+  Symmetric,
+  SkewSymmetric,
+  Hermitian,
+}
+
+/// The banner line of a Matrix Market file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MtxHeader {
+  pub format: MtxFormat,
+  pub field: MtxField,
+  pub symmetry: MtxSymmetry,
+}
+
+/// A scalar type that can be read from and written to Matrix Market files.
+pub trait MatrixMarketValue: Ring {
+  /// The field this type is written as.
+  const FIELD: MtxField;
+
+  /// Parses an entry of a file with the given field from its tokens, two for
+  /// complex entries and one otherwise. Returns `None` if the field cannot be
+  /// represented by this type or the tokens are invalid.
+  fn from_tokens(field: MtxField, tokens: &[&str]) -> Option<Self>;
+
+  /// Formats the entry as the tokens of the field `FIELD`.
+  fn to_tokens(&self) -> String;
+
+  /// The complex conjugate, which is the identity for real types.
+  fn conj(&self) -> Self {
+    self.clone()
+  }
+}
+
+macro_rules! real_value_impl {
+  ($field:expr, $($T:ty),*) => {
+    $(
+      impl MatrixMarketValue for $T {
+        const FIELD: MtxField = $field;
+        fn from_tokens(field: MtxField, tokens: &[&str]) -> Option<$T> {
+          match field {
+            MtxField::Pattern => Some(1 as $T),
+            MtxField::Complex => None,
+            MtxField::Real if $field == MtxField::Integer => None,
+            _ => tokens[0].parse().ok(),
+          }
+        }
+        fn to_tokens(&self) -> String {
+          self.to_string()
+        }
+      }
+    )*
+  };
+}
+real_value_impl!(MtxField::Real, f32, f64);
+real_value_impl!(MtxField::Integer, i8, i16, i32, i64, i128, isize);
+
+macro_rules! complex_value_impl {
+  ($($T:ty),*) => {
+    $(
+      impl MatrixMarketValue for Complex<$T> {
+        const FIELD: MtxField = MtxField::Complex;
+        fn from_tokens(field: MtxField, tokens: &[&str]) -> Option<Complex<$T>> {
+          match field {
+            MtxField::Complex => {
+              Some(Complex::new(tokens[0].parse().ok()?, tokens[1].parse().ok()?))
+            }
+            _ => <$T>::from_tokens(field, tokens).map(Complex::from),
+          }
+        }
+        fn to_tokens(&self) -> String {
+          format!("{} {}", self.re, self.im)
+        }
+        fn conj(&self) -> Complex<$T> {
+          Complex::conj(self)
+        }
+      }
+    )*
+  };
+}
+complex_value_impl!(f32, f64);
+
+// Splits a line into its whitespace separated tokens with their columns.
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+  let mut res = vec![];
+  let mut start = None;
+  for (column, (offset, c)) in line.char_indices().enumerate() {
+    match (start, c.is_whitespace()) {
+      (None, false) => start = Some((column + 1, offset)),
+      (Some((col, begin)), true) => {
+        res.push((col, &line[begin..offset]));
+        start = None;
+      }
+      _ => {}
+    }
+  }
+  if let Some((col, begin)) = start {
+    res.push((col, &line[begin..]));
+  }
+  res
+}
+
+fn parse_header(line: &str) -> Result<MtxHeader, IoError> {
+  let words = tokens(line);
+  let error = |column: usize, message: &str| Err(IoError::parse(1, column, message));
+  if words.len() != 5 || words[0].1 != "%%MatrixMarket" {
+    return error(1, "expected the banner %%MatrixMarket matrix <format> <field> <symmetry>");
+  }
+  if !words[1].1.eq_ignore_ascii_case("matrix") {
+    return error(words[1].0, "only matrices are supported");
+  }
+  let format = match words[2].1.to_ascii_lowercase().as_str() {
+    "array" => MtxFormat::Array,
+    "coordinate" => MtxFormat::Coordinate,
+    _ => return error(words[2].0, "unknown format"),
+  };
+  let field = match words[3].1.to_ascii_lowercase().as_str() {
+    "real" | "double" => MtxField::Real,
+    "integer" => MtxField::Integer,
+    "complex" => MtxField::Complex,
+    "pattern" => MtxField::Pattern,
+    _ => return error(words[3].0, "unknown field"),
+  };
+  let symmetry = match words[4].1.to_ascii_lowercase().as_str() {
+    "general" => MtxSymmetry::General,
+    "symmetric" => MtxSymmetry::Symmetric,
+    "skew-symmetric" => MtxSymmetry::SkewSymmetric,
+    "hermitian" => MtxSymmetry::Hermitian,
+    _ => return error(words[4].0, "unknown symmetry"),
+  };
+  if format == MtxFormat::Array && field == MtxField::Pattern {
+    return error(words[3].0, "pattern matrices must be in coordinate format");
+  }
+  if symmetry == MtxSymmetry::Hermitian && field != MtxField::Complex {
+    return error(words[4].0, "hermitian matrices must be complex");
+  }
+  Ok(MtxHeader {
+    format,
+    field,
+    symmetry,
+  })
+}
+
+// Whether the entry at (i, j) is listed in a file with the given symmetry.
+fn is_stored(symmetry: MtxSymmetry, i: usize, j: usize) -> bool {
+  match symmetry {
+    MtxSymmetry::General => true,
+    MtxSymmetry::SkewSymmetric => i > j,
+    _ => i >= j,
+  }
+}
+
+// The positions listed in a file with the given symmetry, column by column.
+fn stored_positions(
+  n_rows: usize,
+  n_cols: usize,
+  symmetry: MtxSymmetry,
+) -> impl Iterator<Item = (usize, usize)> {
+  (0..n_cols)
+    .flat_map(move |j| (0..n_rows).map(move |i| (i, j)))
+    .filter(move |&(i, j)| is_stored(symmetry, i, j))
+}
+
+// Stores the entry at (i, j) and, for symmetric files, its mirror image.
+fn store<T: MatrixMarketValue>(
+  m: &mut Matrix<T>,
+  symmetry: MtxSymmetry,
+  i: usize,
+  j: usize,
+  v: T,
+) {
+  if i != j {
+    match symmetry {
+      MtxSymmetry::General => {}
+      MtxSymmetry::Symmetric => m.set(j, i, v.clone()),
+      MtxSymmetry::SkewSymmetric => m.set(j, i, -v.clone()),
+      MtxSymmetry::Hermitian => m.set(j, i, v.conj()),
+    }
+  }
+  m.set(i, j, v);
+}
+
+impl<T: MatrixMarketValue> Matrix<T> {
+  /// Reads a matrix from the Matrix Market exchange format. Coordinate files are
+  /// read into a dense matrix, and symmetric files are expanded.
+  pub fn read_matrix_market<R: Read>(reader: R) -> Result<Matrix<T>, IoError> {
+    Ok(Matrix::read_matrix_market_with_header(reader)?.1)
+  }
+
+  /// Reads a matrix from the Matrix Market exchange format and returns it
+  /// together with the header of the file, which allows writing it back in the
+  /// same form.
+  pub fn read_matrix_market_with_header<R: Read>(
+    reader: R,
+  ) -> Result<(MtxHeader, Matrix<T>), IoError> {
+    let mut lines = BufReader::new(reader)
+      .lines()
+      .enumerate()
+      .map(|(k, l)| (k + 1, l));
+    let header = match lines.next() {
+      Some((_, line)) => parse_header(&line?)?,
+      None => return Err(IoError::parse(1, 1, "missing header")),
+    };
+    let mut data_lines = lines.filter(|(_, l)| match l {
+      Ok(l) => !l.starts_with('%') && !l.trim().is_empty(),
+      Err(_) => true,
+    });
+    let (size_line, size) = match data_lines.next() {
+      Some((k, line)) => (k, line?),
+      None => return Err(IoError::parse(1, 1, "missing size line")),
+    };
+    let size = tokens(&size)
+      .iter()
+      .map(|(c, t)| {
+        t.parse::<usize>()
+          .map_err(|_| IoError::parse(size_line, *c, "invalid size"))
+      })
+      .collect::<Result<Vec<usize>, IoError>>()?;
+    let expected = if header.format == MtxFormat::Array { 2 } else { 3 };
+    if size.len() != expected {
+      return Err(IoError::parse(size_line, 1, format!("expected {} sizes", expected)));
+    }
+    let (n_rows, n_cols) = (size[0], size[1]);
+    if header.symmetry != MtxSymmetry::General && n_rows != n_cols {
+      return Err(IoError::parse(size_line, 1, "symmetric matrices must be square"));
+    }
+    let len = match n_rows.checked_mul(n_cols) {
+      Some(len) => len,
+      None => return Err(IoError::parse(size_line, 1, "matrix is too large")),
+    };
+    if header.format == MtxFormat::Coordinate && size[2] > len {
+      let message = format!("{} entries do not fit in a {} x {} matrix", size[2], n_rows, n_cols);
+      return Err(IoError::parse(size_line, 1, message));
+    }
+    let mut m = Matrix::zeros(n_rows, n_cols);
+    // Array files list the entries column by column, symmetric ones only those on
+    // or, if skew-symmetric, below the diagonal.
+    let mut positions = stored_positions(n_rows, n_cols, header.symmetry);
+    let n_values = if header.field == MtxField::Complex { 2 } else { 1 };
+    let n_tokens = match header.format {
+      MtxFormat::Array => n_values,
+      MtxFormat::Coordinate if header.field == MtxField::Pattern => 2,
+      MtxFormat::Coordinate => 2 + n_values,
+    };
+    let mut count = 0;
+    for (k, line) in data_lines {
+      let line = line?;
+      let words = tokens(&line);
+      if words.len() != n_tokens {
+        let message = format!("expected {} values, found {}", n_tokens, words.len());
+        return Err(IoError::parse(k, 1, message));
+      }
+      let (i, j, first) = match header.format {
+        MtxFormat::Array => match positions.next() {
+          Some((i, j)) => (i, j, 0),
+          None => return Err(IoError::parse(k, 1, "too many entries")),
+        },
+        MtxFormat::Coordinate => {
+          let index = |(column, word): (usize, &str), n: usize| match word.parse::<usize>() {
+            Ok(i) if i >= 1 && i <= n => Ok(i - 1),
+            _ => Err(IoError::parse(k, column, format!("invalid index {:?}", word))),
+          };
+          let (i, j) = (index(words[0], n_rows)?, index(words[1], n_cols)?);
+          if !is_stored(header.symmetry, i, j) {
+            let message = "entry above the diagonal of a symmetric matrix";
+            return Err(IoError::parse(k, 1, message));
+          }
+          (i, j, 2)
+        }
+      };
+      let values = words[first..].iter().map(|w| w.1).collect::<Vec<&str>>();
+      match T::from_tokens(header.field, &values) {
+        Some(v) => store(&mut m, header.symmetry, i, j, v),
+        None => {
+          let text = values.join(" ");
+          let message = format!("cannot read {:?} as a {:?} entry", text, header.field);
+          return Err(IoError::parse(k, words.get(first).map_or(1, |w| w.0), message));
+        }
+      }
+      count += 1;
+    }
+    let expected = match header.format {
+      MtxFormat::Array => count + positions.count(),
+      MtxFormat::Coordinate => size[2],
+    };
+    if count != expected {
+      let message = format!("expected {} entries, found {}", expected, count);
+      return Err(IoError::parse(size_line, 1, message));
+    }
+    Ok((header, m))
+  }
+
+  /// Writes the matrix in the Matrix Market exchange format described by
+  /// `header`. The field must be `FIELD` of the scalar type, `Pattern`, or `Real`
+  /// for an integer type. Fails if the matrix lacks the declared symmetry.
+  pub fn write_matrix_market<W: Write>(
+    &self,
+    mut writer: W,
+    header: &MtxHeader,
+  ) -> Result<(), IoError> {
+    let compatible = header.field == T::FIELD
+      || (header.field == MtxField::Real && T::FIELD == MtxField::Integer)
+      || (header.field == MtxField::Pattern && header.format == MtxFormat::Coordinate);
+    let hermitian = header.symmetry == MtxSymmetry::Hermitian;
+    if !compatible || (hermitian && T::FIELD != MtxField::Complex) {
+      return Err(IoError::Format(format!("cannot write this matrix as {:?}", header)));
+    }
+    let (n_rows, n_cols) = (self.n_rows(), self.n_cols());
+    let symmetric = (0..n_rows).all(|i| {
+      (0..n_cols).all(|j| {
+        let (a, b) = (self.get(i, j), self.get(j.min(n_rows - 1), i.min(n_cols - 1)));
+        match header.symmetry {
+          MtxSymmetry::General => true,
+          _ if n_rows != n_cols => false,
+          MtxSymmetry::Symmetric => a == b,
+          MtxSymmetry::SkewSymmetric => a == -b,
+          MtxSymmetry::Hermitian => a == b.conj(),
+        }
+      })
+    });
+    if !symmetric {
+      return Err(IoError::Format(format!("matrix is not {:?}", header.symmetry)));
+    }
+    let format = format!("{:?}", header.format).to_lowercase();
+    let field = format!("{:?}", header.field).to_lowercase();
+    let symmetry = match header.symmetry {
+      MtxSymmetry::General => "general",
+      MtxSymmetry::Symmetric => "symmetric",
+      MtxSymmetry::SkewSymmetric => "skew-symmetric",
+      MtxSymmetry::Hermitian => "hermitian",
+    };
+    writeln!(writer, "%%MatrixMarket matrix {} {} {}", format, field, symmetry)?;
+    let entries = stored_positions(n_rows, n_cols, header.symmetry);
+    match header.format {
+      MtxFormat::Array => {
+        writeln!(writer, "{} {}", n_rows, n_cols)?;
+        for (i, j) in entries {
+          writeln!(writer, "{}", self.get(i, j).to_tokens())?;
+        }
+      }
+      MtxFormat::Coordinate => {
+        let nonzero = entries.filter(|&(i, j)| !self.get(i, j).is_zero()).collect::<Vec<_>>();
+        writeln!(writer, "{} {} {}", n_rows, n_cols, nonzero.len())?;
+        for (i, j) in nonzero {
+          if header.field == MtxField::Pattern {
+            writeln!(writer, "{} {}", i + 1, j + 1)?;
+          } else {
+            writeln!(writer, "{} {} {}", i + 1, j + 1, self.get(i, j).to_tokens())?;
+          }
+        }
+      }
+    }
+    writer.flush()?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const GENERAL: &str = "%%MatrixMarket matrix coordinate real general\n";
+
+  fn read<T: MatrixMarketValue>(text: &str) -> Result<(MtxHeader, Matrix<T>), IoError> {
+    Matrix::read_matrix_market_with_header(text.as_bytes())
+  }
+
+  fn write<T: MatrixMarketValue>(m: &Matrix<T>, header: &MtxHeader) -> String {
+    let mut out = vec![];
+    m.write_matrix_market(&mut out, header).unwrap();
+    String::from_utf8(out).unwrap()
+  }
+
+  #[test]
+  fn test_coordinate() {
+    let text = "%%MatrixMarket matrix coordinate real general\n% a comment\n\n\
+                3 4 3\n1 1 1.5\n3 2 -2e3\n2 4 7\n";
+    let (header, m) = read::<f64>(text).unwrap();
+    assert_eq!(header.format, MtxFormat::Coordinate);
+    let mut expected = Matrix::zeros(3, 4);
+    expected.set(0, 0, 1.5);
+    expected.set(2, 1, -2000.0);
+    expected.set(1, 3, 7.0);
+    assert_eq!(m, expected);
+    let written = write(&m, &header);
+    assert_eq!(written, format!("{}3 4 3\n1 1 1.5\n3 2 -2000\n2 4 7\n", GENERAL));
+    assert_eq!(read::<f64>(&written).unwrap(), (header, m));
+  }
+
+  #[test]
+  fn test_array_symmetric() {
+    let text = "%%MatrixMarket matrix array integer symmetric\n3 3\n1\n2\n3\n4\n5\n6\n";
+    let (header, m) = read::<i64>(text).unwrap();
+    assert_eq!(m, Matrix::new(vec![vec![1, 2, 3], vec![2, 4, 5], vec![3, 5, 6]]));
+    assert_eq!(write(&m, &header), text);
+    let skew = "%%MatrixMarket matrix array real skew-symmetric\n2 2\n3\n";
+    let (header, m) = read::<f64>(skew).unwrap();
+    assert_eq!(m, Matrix::new(vec![vec![0.0, -3.0], vec![3.0, 0.0]]));
+    assert_eq!(write(&m, &header), skew);
+  }
+
+  #[test]
+  fn test_complex_and_pattern() {
+    let text = "%%MatrixMarket matrix coordinate complex hermitian\n2 2 2\n1 1 2 0\n2 1 1 -1\n";
+    let (header, m) = read::<Complex<f64>>(text).unwrap();
+    assert_eq!(m.get(0, 1), Complex::new(1.0, 1.0));
+    assert_eq!(m.get(1, 0), Complex::new(1.0, -1.0));
+    assert_eq!(write(&m, &header), text);
+    let pattern = "%%MatrixMarket matrix coordinate pattern general\n2 3 2\n1 3\n2 1\n";
+    let (header, m) = read::<i32>(pattern).unwrap();
+    assert_eq!(m, Matrix::new(vec![vec![0, 0, 1], vec![1, 0, 0]]));
+    let expected = "%%MatrixMarket matrix coordinate pattern general\n2 3 2\n2 1\n1 3\n";
+    assert_eq!(write(&m, &header), expected);
+    // Real matrices read into complex ones.
+    let real = "%%MatrixMarket matrix array real general\n1 1\n2.5\n";
+    let (_, c) = read::<Complex<f64>>(real).unwrap();
+    assert_eq!(c.get(0, 0), Complex::from(2.5));
+  }
+
+  #[test]
+  fn test_errors() {
+    let e = read::<i32>("%%MatrixMarket matrix array real general\n1 1\n2.5\n").unwrap_err();
+    assert_eq!(e.to_string(), "line 3, column 1: cannot read \"2.5\" as a Real entry");
+    let e = read::<f64>(&format!("{}2 2 1\n1 3 1\n", GENERAL)).unwrap_err();
+    assert_eq!(e.to_string(), "line 3, column 3: invalid index \"3\"");
+    let e = read::<f64>(&format!("{}2 2 2\n1 1 1\n", GENERAL)).unwrap_err();
+    assert!(matches!(e, IoError::Parse { line: 2, .. }));
+    let e = read::<f64>(&format!("{}4294967296 4294967296 1\n1 1 1\n", GENERAL)).unwrap_err();
+    assert_eq!(e.to_string(), "line 2, column 1: matrix is too large");
+    let e = read::<f64>(&format!("{}2 1 3\n1 1 1\n", GENERAL)).unwrap_err();
+    assert_eq!(e.to_string(), "line 2, column 1: 3 entries do not fit in a 2 x 1 matrix");
+    let e = read::<f64>("%%MatrixMarket matrix array real hermitian\n2 2\n").unwrap_err();
+    assert!(matches!(e, IoError::Parse { line: 1, column: 34, .. }));
+    let header = MtxHeader {
+      format: MtxFormat::Array,
+      field: MtxField::Real,
+      symmetry: MtxSymmetry::Symmetric,
+    };
+    let m = Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+    assert!(matches!(m.write_matrix_market(vec![], &header), Err(IoError::Format(_))));
+  }
+}
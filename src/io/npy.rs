@@ -0,0 +1,363 @@
+use super::IoError;
+use crate::matrix::complex::Complex;
+use crate::Matrix;
+use std::convert::TryInto;
+use std::io::{Read, Write};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// A scalar type with a NumPy dtype, which can be stored in `.npy` files.
+pub trait NpyValue: Clone + PartialEq {
+  /// The kind of the dtype: `b` for booleans, `i` and `u` for signed and unsigned
+  /// integers, `f` for floats and `c` for complex numbers.
+  const KIND: char;
+
+  /// The size of a value in bytes.
+  const SIZE: usize;
+
+  /// Decodes a value from its `SIZE` bytes.
+  fn from_npy_bytes(bytes: &[u8], little_endian: bool) -> Self;
+
+  /// Appends the little endian encoding of the value.
+  fn write_npy_bytes(&self, out: &mut Vec<u8>);
+}
+
+macro_rules! npy_value_impl {
+  ($($T:ty, $kind:expr);*) => {
+    $(
+      impl NpyValue for $T {
+        const KIND: char = $kind;
+        const SIZE: usize = std::mem::size_of::<$T>();
+        fn from_npy_bytes(bytes: &[u8], little_endian: bool) -> $T {
+          let bytes = bytes.try_into().unwrap();
+          if little_endian {
+            <$T>::from_le_bytes(bytes)
+          } else {
+            <$T>::from_be_bytes(bytes)
+          }
+        }
+        fn write_npy_bytes(&self, out: &mut Vec<u8>) {
+          out.extend_from_slice(&self.to_le_bytes());
+        }
+      }
+    )*
+  };
+}
+npy_value_impl!(f32, 'f'; f64, 'f'; i8, 'i'; i16, 'i'; i32, 'i'; i64, 'i');
+npy_value_impl!(u8, 'u'; u16, 'u'; u32, 'u'; u64, 'u');
+
+impl NpyValue for bool {
+  const KIND: char = 'b';
+  const SIZE: usize = 1;
+  fn from_npy_bytes(bytes: &[u8], _: bool) -> bool {
+    bytes[0] != 0
+  }
+  fn write_npy_bytes(&self, out: &mut Vec<u8>) {
+    out.push(*self as u8);
+  }
+}
+
+macro_rules! npy_complex_impl {
+  ($($T:ty),*) => {
+    $(
+      impl NpyValue for Complex<$T> {
+        const KIND: char = 'c';
+        const SIZE: usize = 2 * <$T>::SIZE;
+        fn from_npy_bytes(bytes: &[u8], little_endian: bool) -> Complex<$T> {
+          let (re, im) = bytes.split_at(<$T>::SIZE);
+          Complex::new(
+            <$T>::from_npy_bytes(re, little_endian),
+            <$T>::from_npy_bytes(im, little_endian),
+          )
+        }
+        fn write_npy_bytes(&self, out: &mut Vec<u8>) {
+          self.re.write_npy_bytes(out);
+          self.im.write_npy_bytes(out);
+        }
+      }
+    )*
+  };
+}
+npy_complex_impl!(f32, f64);
+
+// The dtype string NumPy uses for `T`, such as `<f8`.
+fn descr<T: NpyValue>() -> String {
+  let order = if T::SIZE == 1 { '|' } else { '<' };
+  format!("{}{}{}", order, T::KIND, T::SIZE)
+}
+
+fn format_error<T>(message: String) -> Result<T, IoError> {
+  Err(IoError::Format(message))
+}
+
+// Returns the text following `'key':` in the header dictionary.
+fn dict_value<'a>(header: &'a str, key: &str) -> Result<&'a str, IoError> {
+  for quote in ['\'', '"'] {
+    if let Some(start) = header.find(&format!("{}{}{}", quote, key, quote)) {
+      let rest = header[start + key.len() + 2..].trim_start();
+      if let Some(rest) = rest.strip_prefix(':') {
+        return Ok(rest.trim_start());
+      }
+    }
+  }
+  format_error(format!("missing key {:?} in npy header", key))
+}
+
+struct NpyHeader {
+  little_endian: bool,
+  kind: char,
+  size: usize,
+  fortran_order: bool,
+  shape: Vec<usize>,
+}
+
+fn parse_header(header: &str) -> Result<NpyHeader, IoError> {
+  let invalid = |key: &str| format_error(format!("invalid {:?} in npy header", key));
+  let value = dict_value(header, "descr")?;
+  let descr = match value.chars().next() {
+    Some(q) if q == '\'' || q == '"' => match value[1..].find(q) {
+      Some(end) => &value[1..end + 1],
+      None => return invalid("descr"),
+    },
+    _ => return invalid("descr"),
+  };
+  let mut chars = descr.chars();
+  let little_endian = match chars.next() {
+    Some('<') => true,
+    Some('>') => false,
+    Some('|') | Some('=') => cfg!(target_endian = "little"),
+    _ => return invalid("descr"),
+  };
+  let kind = match chars.next() {
+    Some(kind) => kind,
+    None => return invalid("descr"),
+  };
+  let size = match chars.as_str().parse() {
+    Ok(size) => size,
+    Err(_) => return invalid("descr"),
+  };
+  let value = dict_value(header, "fortran_order")?;
+  let fortran_order = if value.starts_with("True") {
+    true
+  } else if value.starts_with("False") {
+    false
+  } else {
+    return invalid("fortran_order");
+  };
+  let value = dict_value(header, "shape")?;
+  let shape = match (value.strip_prefix('('), value.find(')')) {
+    (Some(_), Some(end)) => value[1..end]
+      .split(',')
+      .map(|s| s.trim())
+      .filter(|s| !s.is_empty())
+      .map(|s| s.parse())
+      .collect::<Result<Vec<usize>, _>>(),
+    _ => return invalid("shape"),
+  };
+  match shape {
+    Ok(shape) => Ok(NpyHeader {
+      little_endian,
+      kind,
+      size,
+      fortran_order,
+      shape,
+    }),
+    Err(_) => invalid("shape"),
+  }
+}
+
+impl<T: NpyValue> Matrix<T> {
+  /// Reads a matrix from the NumPy `.npy` format. Arrays of dimension zero and
+  /// one are read as a 1 x 1 matrix and a column vector. The dtype must be that
+  /// of `T`, in either byte order.
+  pub fn read_npy<R: Read>(mut reader: R) -> Result<Matrix<T>, IoError> {
+    let mut preamble = [0; 8];
+    reader.read_exact(&mut preamble)?;
+    if &preamble[..6] != MAGIC {
+      return format_error(String::from("not an npy file"));
+    }
+    let header_len = match preamble[6] {
+      1 => {
+        let mut len = [0; 2];
+        reader.read_exact(&mut len)?;
+        u16::from_le_bytes(len) as usize
+      }
+      2 | 3 => {
+        let mut len = [0; 4];
+        reader.read_exact(&mut len)?;
+        u32::from_le_bytes(len) as usize
+      }
+      version => return format_error(format!("unsupported npy version {}", version)),
+    };
+    let mut header = vec![0; header_len];
+    reader.read_exact(&mut header)?;
+    // Versions 1 and 2 encode the header in latin-1, which for the characters of
+    // a valid header coincides with utf-8.
+    let header = match String::from_utf8(header) {
+      Ok(header) => parse_header(&header)?,
+      Err(_) => return format_error(String::from("npy header is not valid text")),
+    };
+    if header.kind != T::KIND || header.size != T::SIZE {
+      let message = format!("cannot read dtype {}{} as {}", header.kind, header.size, descr::<T>());
+      return format_error(message);
+    }
+    let (n_rows, n_cols) = match header.shape[..] {
+      [] => (1, 1),
+      [n] => (n, 1),
+      [n_rows, n_cols] => (n_rows, n_cols),
+      _ => return format_error(format!("cannot read shape {:?} as a matrix", header.shape)),
+    };
+    let len = match n_rows.checked_mul(n_cols).and_then(|n| n.checked_mul(T::SIZE)) {
+      Some(len) => len,
+      None => return format_error(format!("shape {:?} is too large", header.shape)),
+    };
+    // The shape is untrusted, so the entries are read as they arrive rather than
+    // allocated up front.
+    let mut bytes = vec![];
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < len {
+      return Err(IoError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+    }
+    let values = bytes
+      .chunks_exact(T::SIZE)
+      .map(|b| T::from_npy_bytes(b, header.little_endian))
+      .collect::<Vec<T>>();
+    if header.fortran_order {
+      let data = (0..n_rows * n_cols)
+        .map(|k| values[(k % n_cols) * n_rows + k / n_cols].clone())
+        .collect();
+      Ok(Matrix::create_from_data(data, n_rows, n_cols))
+    } else {
+      Ok(Matrix::create_from_data(values, n_rows, n_cols))
+    }
+  }
+
+  /// Writes the matrix in the NumPy `.npy` format as a two-dimensional array in
+  /// C order and little endian byte order.
+  pub fn write_npy<W: Write>(&self, mut writer: W) -> Result<(), IoError> {
+    let mut header = format!(
+      "{{'descr': '{}', 'fortran_order': False, 'shape': ({}, {}), }}",
+      descr::<T>(),
+      self.n_rows(),
+      self.n_cols()
+    );
+    // The data starts at a multiple of 64 bytes, and the header ends with a newline.
+    let mut preamble = MAGIC.to_vec();
+    let padding = |prefix: usize, header: &str| (64 - (prefix + header.len() + 1) % 64) % 64;
+    if header.len() + padding(10, &header) < 1 << 16 {
+      header.push_str(&" ".repeat(padding(10, &header)));
+      header.push('\n');
+      preamble.extend_from_slice(&[1, 0]);
+      preamble.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    } else {
+      header.push_str(&" ".repeat(padding(12, &header)));
+      header.push('\n');
+      preamble.extend_from_slice(&[2, 0]);
+      preamble.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    }
+    writer.write_all(&preamble)?;
+    writer.write_all(header.as_bytes())?;
+    let mut bytes = Vec::with_capacity(self.n_rows() * self.n_cols() * T::SIZE);
+    for (e, _, _) in self.iter() {
+      e.write_npy_bytes(&mut bytes);
+    }
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn npy_file(version: u8, header: &str, data: &[u8]) -> Vec<u8> {
+    let mut file = MAGIC.to_vec();
+    file.extend_from_slice(&[version, 0]);
+    if version == 1 {
+      file.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    } else {
+      file.extend_from_slice(&(header.len() as u32).to_le_bytes());
+    }
+    file.extend_from_slice(header.as_bytes());
+    file.extend_from_slice(data);
+    file
+  }
+
+  fn round_trip<T: NpyValue + std::fmt::Debug>(m: Matrix<T>) {
+    let mut out = vec![];
+    m.write_npy(&mut out).unwrap();
+    assert_eq!(out.len() % 64, (m.n_rows() * m.n_cols() * T::SIZE) % 64);
+    assert_eq!(Matrix::<T>::read_npy(&out[..]).unwrap(), m);
+  }
+
+  #[test]
+  fn test_write_npy() {
+    let m = Matrix::new(vec![vec![1i32, 2, 3], vec![4, 5, 6]]);
+    let mut out = vec![];
+    m.write_npy(&mut out).unwrap();
+    let header = "{'descr': '<i4', 'fortran_order': False, 'shape': (2, 3), }";
+    let padded = format!("{}{}\n", header, " ".repeat(117 - header.len()));
+    assert_eq!(&out[..8], b"\x93NUMPY\x01\x00");
+    assert_eq!(u16::from_le_bytes([out[8], out[9]]), 118);
+    assert_eq!(&out[10..128], padded.as_bytes());
+    assert_eq!(&out[128..132], &1i32.to_le_bytes());
+    assert_eq!(out.len(), 128 + 24);
+  }
+
+  #[test]
+  fn test_round_trip() {
+    round_trip(Matrix::new(vec![vec![1.5f64, -2.0], vec![f64::INFINITY, 1e-300]]));
+    round_trip(Matrix::new(vec![vec![1.5f32, -2.0, 0.25]]));
+    round_trip(Matrix::new(vec![vec![i64::MIN], vec![7]]));
+    round_trip(Matrix::new(vec![vec![0u8, 255], vec![17, 3]]));
+    round_trip(Matrix::new(vec![vec![true, false], vec![false, true]]));
+    round_trip(Matrix::new(vec![vec![Complex::new(1.0f64, -1.0), Complex::new(0.5, 2.0)]]));
+    round_trip(Matrix::new(vec![vec![Complex::new(3.0f32, 4.0)]]));
+  }
+
+  #[test]
+  fn test_read_npy() {
+    // Big endian values in Fortran order with a version 2 header.
+    let header = "{\"descr\": \">f8\", \"fortran_order\": True, \"shape\": (2, 3)}\n";
+    let data = [1.0f64, 4.0, 2.0, 5.0, 3.0, 6.0]
+      .iter()
+      .flat_map(|x| x.to_be_bytes())
+      .collect::<Vec<u8>>();
+    let m = Matrix::<f64>::read_npy(&npy_file(2, header, &data)[..]).unwrap();
+    assert_eq!(m, Matrix::new(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]));
+    // A one-dimensional array with a version 3 header.
+    let header = "{'descr': '|b1', 'fortran_order': False, 'shape': (3,), }\n";
+    let m = Matrix::<bool>::read_npy(&npy_file(3, header, &[1, 0, 1])[..]).unwrap();
+    assert_eq!(m, Matrix::new(vec![vec![true], vec![false], vec![true]]));
+    let header = "{'descr': '<c8', 'fortran_order': False, 'shape': (), }\n";
+    let data = [2.0f32, -1.0].iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<u8>>();
+    let m = Matrix::<Complex<f32>>::read_npy(&npy_file(1, header, &data)[..]).unwrap();
+    assert_eq!(m.get(0, 0), Complex::new(2.0, -1.0));
+  }
+
+  #[test]
+  fn test_errors() {
+    let header = "{'descr': '<i8', 'fortran_order': False, 'shape': (1,), }\n";
+    let file = npy_file(1, header, &[0; 8]);
+    let e = Matrix::<f64>::read_npy(&file[..]).unwrap_err();
+    assert_eq!(e.to_string(), "cannot read dtype i8 as <f8");
+    assert!(matches!(Matrix::<i64>::read_npy(&file[..7]), Err(IoError::Io(_))));
+    assert!(matches!(Matrix::<i64>::read_npy(&file[..20]), Err(IoError::Io(_))));
+    let header = "{'descr': '<i8', 'fortran_order': False, 'shape': (1, 1, 1), }\n";
+    let e = Matrix::<i64>::read_npy(&npy_file(1, header, &[0; 8])[..]).unwrap_err();
+    assert_eq!(e.to_string(), "cannot read shape [1, 1, 1] as a matrix");
+    let e = Matrix::<i64>::read_npy(&b"PK\x03\x04 not npy"[..]).unwrap_err();
+    assert!(matches!(e, IoError::Format(_)));
+  }
+
+  #[test]
+  fn test_corrupt_shape() {
+    let header = "{'descr': '<i8', 'fortran_order': False, 'shape': (4294967296, 4294967296), }\n";
+    let e = Matrix::<i64>::read_npy(&npy_file(1, header, &[0; 8])[..]).unwrap_err();
+    assert_eq!(e.to_string(), "shape [4294967296, 4294967296] is too large");
+    let header = "{'descr': '<i8', 'fortran_order': False, 'shape': (1099511627776, 1), }\n";
+    let e = Matrix::<i64>::read_npy(&npy_file(1, header, &[0; 8])[..]).unwrap_err();
+    assert!(matches!(e, IoError::Io(_)));
+  }
+}
@@ -0,0 +1,352 @@
+use super::crc32::crc32;
+use super::npy::NpyValue;
+use super::IoError;
+use crate::Matrix;
+use std::convert::{TryFrom, TryInto};
+use std::io::{Read, Write};
+use std::ops::Range;
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_DIRECTORY: u32 = 0x0605_4b50;
+const ZIP64_END_OF_DIRECTORY: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR: u32 = 0x0706_4b50;
+// The DOS date of 1980-01-01, the earliest one a zip file can hold.
+const DATE: u16 = 0x21;
+const VERSION: u16 = 20;
+const STORED: u16 = 0;
+
+fn format_error<T>(message: &str) -> Result<T, IoError> {
+  Err(IoError::Format(format!("npz: {}", message)))
+}
+
+// Reads little endian integers at an offset, failing on truncated archives.
+fn read_u16(bytes: &[u8], at: usize) -> Result<u16, IoError> {
+  match at.checked_add(2).and_then(|end| bytes.get(at..end)) {
+    Some(b) => Ok(u16::from_le_bytes(b.try_into().unwrap())),
+    None => format_error("unexpected end of archive"),
+  }
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Result<u32, IoError> {
+  match at.checked_add(4).and_then(|end| bytes.get(at..end)) {
+    Some(b) => Ok(u32::from_le_bytes(b.try_into().unwrap())),
+    None => format_error("unexpected end of archive"),
+  }
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> Result<u64, IoError> {
+  match at.checked_add(8).and_then(|end| bytes.get(at..end)) {
+    Some(b) => Ok(u64::from_le_bytes(b.try_into().unwrap())),
+    None => format_error("unexpected end of archive"),
+  }
+}
+
+// Converts an offset or size read from the archive, which may not fit in usize.
+fn to_usize(v: u64) -> Result<usize, IoError> {
+  match usize::try_from(v) {
+    Ok(v) => Ok(v),
+    Err(_) => format_error("unexpected end of archive"),
+  }
+}
+
+struct Entry {
+  name: String,
+  method: u16,
+  crc: u32,
+  data: Range<usize>,
+}
+
+/// A NumPy `.npz` archive, a zip file holding one `.npy` file per named
+/// matrix. The archive is read into memory, and the matrices are decoded on
+/// request, so that they may have different scalar types.
+pub struct NpzArchive {
+  bytes: Vec<u8>,
+  entries: Vec<Entry>,
+}
+
+impl NpzArchive {
+  /// Reads the directory of an archive. Fails if it is not a valid zip file.
+  pub fn new<R: Read>(mut reader: R) -> Result<NpzArchive, IoError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    // The end of directory record is followed by a comment of at most 65535 bytes.
+    let end = (0..bytes.len().saturating_sub(21))
+      .rev()
+      .take(1 << 16)
+      .find(|&at| read_u32(&bytes, at).ok() == Some(END_OF_DIRECTORY));
+    let end = match end {
+      Some(end) => end,
+      None => return format_error("not a zip file"),
+    };
+    let mut count = read_u16(&bytes, end + 10)? as u64;
+    let mut offset = read_u32(&bytes, end + 16)? as u64;
+    if (count == 0xFFFF || offset == 0xFFFF_FFFF)
+      && end >= 20
+      && read_u32(&bytes, end - 20)? == ZIP64_LOCATOR
+    {
+      let zip64_end = to_usize(read_u64(&bytes, end - 12)?)?;
+      if read_u32(&bytes, zip64_end)? != ZIP64_END_OF_DIRECTORY {
+        return format_error("invalid zip64 end of directory");
+      }
+      count = read_u64(&bytes, zip64_end + 32)?;
+      offset = read_u64(&bytes, zip64_end + 48)?;
+    }
+    let mut entries = vec![];
+    let mut at = to_usize(offset)?;
+    for _ in 0..count {
+      if read_u32(&bytes, at)? != CENTRAL_HEADER {
+        return format_error("invalid central directory");
+      }
+      let method = read_u16(&bytes, at + 10)?;
+      let crc = read_u32(&bytes, at + 16)?;
+      let mut size = read_u32(&bytes, at + 20)? as u64;
+      let uncompressed_size = read_u32(&bytes, at + 24)?;
+      let name_len = read_u16(&bytes, at + 28)? as usize;
+      let extra_len = read_u16(&bytes, at + 30)? as usize;
+      let comment_len = read_u16(&bytes, at + 32)? as usize;
+      let mut local = read_u32(&bytes, at + 42)? as u64;
+      let name = match bytes.get(at + 46..at + 46 + name_len) {
+        Some(name) => String::from_utf8_lossy(name).into_owned(),
+        None => return format_error("unexpected end of archive"),
+      };
+      // The zip64 extra field holds those sizes and offsets which do not fit in
+      // 32 bits, in this order.
+      let mut extra = at + 46 + name_len;
+      while extra + 4 <= at + 46 + name_len + extra_len {
+        let (id, len) = (read_u16(&bytes, extra)?, read_u16(&bytes, extra + 2)? as usize);
+        if id == 1 {
+          let mut field = extra + 4;
+          if uncompressed_size == 0xFFFF_FFFF {
+            field += 8;
+          }
+          if size == 0xFFFF_FFFF {
+            size = read_u64(&bytes, field)?;
+            field += 8;
+          }
+          if local == 0xFFFF_FFFF {
+            local = read_u64(&bytes, field)?;
+          }
+        }
+        extra += 4 + len;
+      }
+      let local = to_usize(local)?;
+      if read_u32(&bytes, local)? != LOCAL_HEADER {
+        return format_error("invalid local file header");
+      }
+      let start = local + 30 + read_u16(&bytes, local + 26)? as usize;
+      let start = start + read_u16(&bytes, local + 28)? as usize;
+      let data = match start.checked_add(to_usize(size)?) {
+        Some(end) if end <= bytes.len() => start..end,
+        _ => return format_error("unexpected end of archive"),
+      };
+      let name = name.strip_suffix(".npy").unwrap_or(&name).to_string();
+      entries.push(Entry {
+        name,
+        method,
+        crc,
+        data,
+      });
+      at += 46 + name_len + extra_len + comment_len;
+    }
+    Ok(NpzArchive { bytes, entries })
+  }
+
+  /// Returns the names of the matrices in the archive, without the `.npy`
+  /// extension.
+  pub fn names(&self) -> Vec<&str> {
+    self.entries.iter().map(|e| e.name.as_str()).collect()
+  }
+
+  /// Reads the matrix of the given name. Fails if there is no such matrix, its
+  /// checksum does not match, or its dtype is not that of `T`.
+  pub fn get<T: NpyValue>(&self, name: &str) -> Result<Matrix<T>, IoError> {
+    let entry = match self.entries.iter().find(|e| e.name == name) {
+      Some(entry) => entry,
+      None => return format_error(&format!("no matrix named {:?}", name)),
+    };
+    if entry.method != STORED {
+      return format_error(&format!("{:?} uses an unsupported compression method", name));
+    }
+    let data = &self.bytes[entry.data.clone()];
+    if crc32(data) != entry.crc {
+      return format_error(&format!("checksum mismatch in {:?}", name));
+    }
+    Matrix::read_npy(data)
+  }
+}
+
+struct WrittenEntry {
+  name: String,
+  crc: u32,
+  size: u32,
+  offset: u32,
+}
+
+/// Writes a NumPy `.npz` archive as an uncompressed zip file, like `numpy.savez`.
+pub struct NpzWriter<W: Write> {
+  writer: W,
+  offset: u64,
+  entries: Vec<WrittenEntry>,
+}
+
+impl<W: Write> NpzWriter<W> {
+  pub fn new(writer: W) -> NpzWriter<W> {
+    NpzWriter {
+      writer,
+      offset: 0,
+      entries: vec![],
+    }
+  }
+
+  /// Adds a matrix under the given name, which must be unique within the archive.
+  pub fn add<T: NpyValue>(&mut self, name: &str, m: &Matrix<T>) -> Result<(), IoError> {
+    if self.entries.iter().any(|e| e.name == name) {
+      return format_error(&format!("duplicate name {:?}", name));
+    }
+    let mut data = vec![];
+    m.write_npy(&mut data)?;
+    let file_name = format!("{}.npy", name);
+    let end = self.offset + 30 + file_name.len() as u64 + data.len() as u64;
+    if end > u32::MAX as u64 || file_name.len() > u16::MAX as usize {
+      return format_error("archives larger than 4 GiB are not supported");
+    }
+    let entry = WrittenEntry {
+      name: name.to_string(),
+      crc: crc32(&data),
+      size: data.len() as u32,
+      offset: self.offset as u32,
+    };
+    let mut header = vec![];
+    header.extend_from_slice(&LOCAL_HEADER.to_le_bytes());
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    header.extend_from_slice(&STORED.to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    header.extend_from_slice(&DATE.to_le_bytes());
+    header.extend_from_slice(&entry.crc.to_le_bytes());
+    header.extend_from_slice(&entry.size.to_le_bytes());
+    header.extend_from_slice(&entry.size.to_le_bytes());
+    header.extend_from_slice(&(file_name.len() as u16).to_le_bytes());
+    header.extend_from_slice(&0u16.to_le_bytes());
+    header.extend_from_slice(file_name.as_bytes());
+    self.writer.write_all(&header)?;
+    self.writer.write_all(&data)?;
+    self.offset = end;
+    self.entries.push(entry);
+    Ok(())
+  }
+
+  /// Writes the central directory, which completes the archive, and returns the
+  /// underlying writer.
+  pub fn finish(mut self) -> Result<W, IoError> {
+    let mut directory = vec![];
+    for entry in &self.entries {
+      let file_name = format!("{}.npy", entry.name);
+      directory.extend_from_slice(&CENTRAL_HEADER.to_le_bytes());
+      directory.extend_from_slice(&VERSION.to_le_bytes());
+      directory.extend_from_slice(&VERSION.to_le_bytes());
+      directory.extend_from_slice(&0u16.to_le_bytes());
+      directory.extend_from_slice(&STORED.to_le_bytes());
+      directory.extend_from_slice(&0u16.to_le_bytes());
+      directory.extend_from_slice(&DATE.to_le_bytes());
+      directory.extend_from_slice(&entry.crc.to_le_bytes());
+      directory.extend_from_slice(&entry.size.to_le_bytes());
+      directory.extend_from_slice(&entry.size.to_le_bytes());
+      directory.extend_from_slice(&(file_name.len() as u16).to_le_bytes());
+      // The lengths of the extra field and comment, disk number and attributes.
+      directory.extend_from_slice(&[0; 12]);
+      directory.extend_from_slice(&entry.offset.to_le_bytes());
+      directory.extend_from_slice(file_name.as_bytes());
+    }
+    if self.entries.len() > u16::MAX as usize
+      || self.offset + directory.len() as u64 > u32::MAX as u64
+    {
+      return format_error("archives larger than 4 GiB are not supported");
+    }
+    let count = (self.entries.len() as u16).to_le_bytes();
+    let size = (directory.len() as u32).to_le_bytes();
+    directory.extend_from_slice(&END_OF_DIRECTORY.to_le_bytes());
+    directory.extend_from_slice(&[0; 4]);
+    directory.extend_from_slice(&count);
+    directory.extend_from_slice(&count);
+    directory.extend_from_slice(&size);
+    directory.extend_from_slice(&(self.offset as u32).to_le_bytes());
+    directory.extend_from_slice(&0u16.to_le_bytes());
+    self.writer.write_all(&directory)?;
+    self.writer.flush()?;
+    Ok(self.writer)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Complex;
+
+  fn archive() -> Vec<u8> {
+    let mut writer = NpzWriter::new(vec![]);
+    writer.add("a", &Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]])).unwrap();
+    writer.add("labels", &Matrix::new(vec![vec![7i64], vec![-1]])).unwrap();
+    writer.add("z", &Matrix::new(vec![vec![Complex::new(0.0f64, 1.0)]])).unwrap();
+    writer.finish().unwrap()
+  }
+
+  #[test]
+  fn test_npz_round_trip() {
+    let npz = NpzArchive::new(&archive()[..]).unwrap();
+    assert_eq!(npz.names(), vec!["a", "labels", "z"]);
+    assert_eq!(npz.get::<f64>("a").unwrap(), Matrix::new(vec![vec![1.0, 2.0], vec![3.0, 4.0]]));
+    assert_eq!(npz.get::<i64>("labels").unwrap(), Matrix::new(vec![vec![7], vec![-1]]));
+    assert_eq!(npz.get::<Complex<f64>>("z").unwrap().get(0, 0), Complex::new(0.0, 1.0));
+    assert!(npz.get::<f64>("labels").is_err());
+    assert!(npz.get::<f64>("b").is_err());
+  }
+
+  #[test]
+  fn test_npz_layout() {
+    let bytes = archive();
+    assert_eq!(&bytes[..4], b"PK\x03\x04");
+    assert_eq!(&bytes[30..35], b"a.npy");
+    assert_eq!(&bytes[35..41], b"\x93NUMPY");
+    // The end of directory record closes the archive and counts three entries.
+    let end = bytes.len() - 22;
+    assert_eq!(read_u32(&bytes, end).unwrap(), END_OF_DIRECTORY);
+    assert_eq!(read_u16(&bytes, end + 10).unwrap(), 3);
+    let directory = read_u32(&bytes, end + 16).unwrap() as usize;
+    assert_eq!(read_u32(&bytes, end + 12).unwrap() as usize, end - directory);
+  }
+
+  #[test]
+  fn test_npz_errors() {
+    let mut bytes = archive();
+    bytes[40] ^= 1;
+    let npz = NpzArchive::new(&bytes[..]).unwrap();
+    let e = npz.get::<f64>("a").unwrap_err();
+    assert_eq!(e.to_string(), "npz: checksum mismatch in \"a\"");
+    assert!(npz.get::<i64>("labels").is_ok());
+    assert!(NpzArchive::new(&bytes[..bytes.len() - 1]).is_err());
+    let mut writer = NpzWriter::new(vec![]);
+    writer.add("a", &Matrix::new(vec![vec![true]])).unwrap();
+    assert!(writer.add("a", &Matrix::new(vec![vec![true]])).is_err());
+  }
+
+  #[test]
+  fn test_npz_corrupt_offsets() {
+    // A zip64 locator pointing to the end of the address space.
+    let mut bytes = ZIP64_LOCATOR.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&0u32.to_le_bytes());
+    bytes.extend_from_slice(&(u64::MAX - 2).to_le_bytes());
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&END_OF_DIRECTORY.to_le_bytes());
+    bytes.extend_from_slice(&[0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0]);
+    bytes.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0, 0]);
+    assert!(NpzArchive::new(&bytes[..]).is_err());
+    // A central directory entry whose local header lies past the archive.
+    let mut bytes = archive();
+    let end = bytes.len() - 22;
+    let directory = read_u32(&bytes, end + 16).unwrap() as usize;
+    bytes[directory + 42..directory + 46].copy_from_slice(&[0xfe, 0xff, 0xff, 0xff]);
+    assert!(NpzArchive::new(&bytes[..]).is_err());
+  }
+}
@@ -13,6 +13,15 @@
 //!
 //! let m = Matrix::new(vec![vec![0, 1], vec![2, 3]]);
 //! ```
+//!
+//! or more briefly by the macro `matrix!` or by parsing a literal:
+//!
+//! ```
+//! use matrix_algebra::{matrix, Matrix};
+//!
+//! let m = matrix![0, 1; 2, 3];
+//! assert_eq!("[0 1; 2 3]".parse::<Matrix<i32>>(), Ok(m));
+//! ```
 //! <br>
 //! <br>
 //! Common arithmetic operators are implemented on the type `Matrix`:
@@ -56,8 +65,229 @@
 //! assert_eq!(s*m, m*2);
 //!
 //! ```
+//! <br>
+//! <br>
+//! The algebraic structure of a scalar type is described by the traits
+//! `Zero`, `One`, `Semiring`, `Ring`, `Field` and `RealField`. They are implemented
+//! for all primitive numeric types and allow writing generic code:
+//!
+//! ```
+//! use matrix_algebra::{Matrix, Semiring};
+//!
+//! fn square<T: Semiring>(m: &Matrix<T>) -> Matrix<T> {
+//!   m * m
+//! }
+//!
+//! assert_eq!(square(&Matrix::<i32>::identity(2)), Matrix::identity(2));
+//! ```
+//! <br>
+//! <br>
+//! Exact computations are possible with the scalar types `Rational` and `BigInt`.
+//! Over any field the matrix supports `rref`, `rank`, `inverse` and `null_space`,
+//! while `det` uses fraction-free elimination and also works for integers:
+//!
+//! ```
+//! use matrix_algebra::{Matrix, Rational64};
+//!
+//! let m = Matrix::new(vec![vec![2, 1], vec![7, 4]]);
+//! assert_eq!(m.det(), 1);
+//!
+//! let q = Matrix::new(vec![vec![Rational64::new(1, 2), Rational64::new(1, 3)]]);
+//! assert_eq!(q.rank(), 1);
+//! ```
+//! <br>
+//! <br>
+//! Finite fields are supported by the scalar type `Zp<P>`. For GF(2) the
+//! type `Gf2Matrix` stores 64 entries per machine word.
+//! <br>
+//! <br>
+//! Integer matrices, including those over `BigInt`, provide the Hermite and
+//! Smith normal forms and can solve linear Diophantine systems:
+//!
+//! ```
+//! use matrix_algebra::Matrix;
+//!
+//! let a = Matrix::new(vec![vec![2, 4, 4], vec![-6, 6, 12], vec![10, -4, -16]]);
+//! let (d, u, v) = a.smith_normal_form();
+//!
+//! assert_eq!(d, Matrix::new(vec![vec![2, 0, 0], vec![0, 6, 0], vec![0, 0, 12]]));
+//! assert_eq!(&(&u * &a) * &v, d);
+//! ```
+//! <br>
+//! <br>
+//! Rows of integer or rational matrices can be treated as a lattice basis and
+//! reduced with `lll_reduce` or `bkz_reduce`:
+//!
+//! ```
+//! use matrix_algebra::{Matrix, Rational64};
+//!
+//! let b = Matrix::new(vec![vec![1, 1, 1], vec![-1, 0, 2], vec![3, 5, 6]]);
+//! let (reduced, u) = b.lll_reduce(Rational64::new(3, 4));
+//!
+//! assert_eq!(reduced.get_row(0), Matrix::new(vec![vec![0, 1, 0]]));
+//! assert_eq!(&u * &b, reduced);
+//! ```
+//! <br>
+//! <br>
+//! Matrices can be multiplied over other semirings such as `MinPlus`, `MaxPlus`,
+//! `MaxTimes` or `Boolean`. The closure then computes shortest paths or reachability:
+//!
+//! ```
+//! use matrix_algebra::{Matrix, MinPlus};
+//!
+//! let inf = f64::INFINITY;
+//! let w = Matrix::new(vec![vec![inf, 4.0, 1.0], vec![inf, inf, inf], vec![inf, 2.0, inf]]);
+//!
+//! let d = w.semiring_closure::<MinPlus<f64>>();
+//!
+//! assert_eq!(d.get_row(0), Matrix::new(vec![vec![0.0, 3.0, 1.0]]));
+//! ```
+//! <br>
+//! <br>
+//! The module `graph` interprets a matrix as the adjacency matrix of a graph
+//! and provides Laplacians, shortest paths, connected components, PageRank
+//! and spectral bisection:
+//!
+//! ```
+//! use matrix_algebra::{graph, Matrix};
+//!
+//! let a = Matrix::new(vec![vec![0, 1, 0], vec![1, 0, 0], vec![0, 0, 0]]);
+//!
+//! assert_eq!(graph::connected_components(&a), vec![0, 0, 1]);
+//! ```
+//! <br>
+//! <br>
+//! Computations written generically over the trait `Real` can be differentiated
+//! exactly by evaluating them on `Dual` numbers:
+//!
+//! ```
+//! use matrix_algebra::{autodiff, Matrix, Real};
+//!
+//! fn f<T: Real>(x: &Matrix<T>) -> T {
+//!   x.get(0, 0).sin() * x.get(1, 0)
+//! }
+//!
+//! let x = Matrix::new(vec![vec![0.0], vec![2.0]]);
+//! assert_eq!(autodiff::gradient(f, &x), Matrix::new(vec![vec![2.0], vec![0.0]]));
+//! ```
+//! <br>
+//! <br>
+//! Gradients of many parameters are obtained in reverse mode by recording the
+//! matrix operations on a `Tape`:
+//!
+//! ```
+//! use matrix_algebra::autodiff::Tape;
+//! use matrix_algebra::Matrix;
+//!
+//! let tape = Tape::new();
+//! let w = tape.var(Matrix::new(vec![vec![1.0, 2.0]]));
+//! let x = tape.var(Matrix::new(vec![vec![3.0], vec![4.0]]));
+//! let grads = (&w * &x).sum().backward();
+//!
+//! assert_eq!(grads.wrt(&w), Matrix::new(vec![vec![3.0, 4.0]]));
+//! ```
+//! <br>
+//! <br>
+//! The module `nn` provides dense layers, losses and optimizers for training
+//! small networks on samples stored as matrix rows:
+//!
+//! ```
+//! use matrix_algebra::nn::{mse, Activation, Dense, Rng, Sequential, Sgd};
+//! use matrix_algebra::Matrix;
+//!
+//! let mut rng = Rng::new(0);
+//! let mut net = Sequential::new(vec![Dense::new(2, 1, Activation::Identity, &mut rng)]);
+//! let mut sgd = Sgd::new(0.1f32);
+//! let x = Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+//! let y = Matrix::new(vec![vec![1.0], vec![-1.0]]);
+//!
+//! for _ in 0..100 {
+//!   let (_, grad) = mse(&net.forward(&x), &y);
+//!   net.backward(&grad);
+//!   net.step(&mut sgd);
+//! }
+//! assert!(mse(&net.predict(&x), &y).0 < 1e-3);
+//! ```
+//! <br>
+//! <br>
+//! Real square matrices have the functions `expm`, `logm`, `sqrtm` and `powm`, and
+//! `apply_spectral` applies any `Func` to the eigenvalues:
+//!
+//! ```
+//! use matrix_algebra::Matrix;
+//!
+//! let a: Matrix<f64> = Matrix::new(vec![vec![4.0, 1.0], vec![0.0, 9.0]]);
+//! let r = a.sqrtm().unwrap();
+//!
+//! assert!((&(&r * &r) - &a).iter().all(|(e, _, _)| e.abs() < 1e-12));
+//! ```
+//! <br>
+//! <br>
+//! The module `equations` solves Sylvester, Lyapunov and algebraic Riccati
+//! equations, e.g. for the LQR gain of a double integrator:
+//!
+//! ```
+//! use matrix_algebra::{equations, Matrix};
+//!
+//! let a: Matrix<f64> = Matrix::new(vec![vec![0.0, 1.0], vec![0.0, 0.0]]);
+//! let b = Matrix::new(vec![vec![0.0], vec![1.0]]);
+//! let x = equations::solve_care(&a, &b, &Matrix::identity(2), &Matrix::identity(1)).unwrap();
+//! let k = &b.trans() * &x;
+//!
+//! assert!((k.get(0, 0) - 1.0).abs() < 1e-9 && (k.get(0, 1) - 3f64.sqrt()).abs() < 1e-9);
+//! ```
+//! <br>
+//! <br>
+//! The module `control` wraps such computations around a `StateSpace` model:
+//!
+//! ```
+//! use matrix_algebra::control::StateSpace;
+//! use matrix_algebra::Matrix;
+//!
+//! let a: Matrix<f64> = Matrix::new(vec![vec![0.0, 1.0], vec![0.0, 0.0]]);
+//! let b = Matrix::new(vec![vec![0.0], vec![1.0]]);
+//! let sys = StateSpace::new(a, b, Matrix::new(vec![vec![1.0, 0.0]]), Matrix::zeros(1, 1));
+//! let k = sys.place_poles(&[(-1.0, 0.0), (-2.0, 0.0)]).unwrap();
+//!
+//! assert!((&k - &Matrix::new(vec![vec![2.0, 3.0]])).iter().all(|(e, _, _)| e.abs() < 1e-12));
+//! assert!((sys.c2d(0.5).step_response(3).unwrap()[0].get(2, 0) - 0.5).abs() < 1e-12);
+//! ```
+//! <br>
+//! <br>
+//! The module `io` reads and writes matrices in file formats such as CSV:
+//!
+//! ```
+//! use matrix_algebra::io::CsvOptions;
+//! use matrix_algebra::Matrix;
+//!
+//! let text = "a,b\n1,2\n3,4\n";
+//! let (header, m) = Matrix::<i32>::read_csv_with_header(text.as_bytes(), &CsvOptions::default())
+//!   .unwrap();
+//!
+//! assert_eq!(header, vec!["a", "b"]);
+//! assert_eq!(m, Matrix::new(vec![vec![1, 2], vec![3, 4]]));
+//! ```
 //!
+//! With the feature `serde`, `Matrix` and `Scalar` implement `Serialize` and
+//! `Deserialize`. A matrix is represented by its shape and its entries in row-major
+//! order.
+pub mod autodiff;
+pub mod control;
+pub mod equations;
+pub mod graph;
+pub mod io;
 pub mod matrix;
+pub mod nn;
+pub use matrix::big_int::BigInt;
+pub use matrix::complex::Complex;
+pub use matrix::dual::Dual;
+pub use matrix::finite_field::Zp;
 pub use matrix::func::Func;
+pub use matrix::gf2_matrix::Gf2Matrix;
+pub use matrix::num::{Field, Integer, One, Real, RealField, Ring, Semiring, Zero};
+pub use matrix::rational::{BigRational, Rational, Rational128, Rational64};
 pub use matrix::scalar::Scalar;
+pub use matrix::semiring::{
+  Boolean, Infinity, MaxPlus, MaxTimes, MinPlus, NegInfinity, SemiringWrapper,
+};
 pub use matrix::Matrix;
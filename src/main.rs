@@ -1,10 +1,9 @@
-mod matrix;
-use matrix::func::Func;
+use matrix_algebra::{Func, Matrix};
 
 fn main(){
-  let m = matrix::Matrix::new(vec![vec![0, 1], vec![2, 3]]);
-  println!("{:?}", m.trans());
+  let m = Matrix::new(vec![vec![0, 1], vec![2, 3]]);
+  println!("{}", m.trans());
 
-  let sin = Func::new(|x| f64::sin(x));
+  let sin = Func::new(f64::sin);
   println!("{}", sin.0(2.0));
 }
\ No newline at end of file
@@ -1,35 +1,63 @@
+pub mod big_int;
+pub mod complex;
+pub mod dual;
+pub mod finite_field;
+pub mod gf2_matrix;
 mod matrix_add;
+mod matrix_cholesky;
+mod matrix_display;
 mod matrix_div;
+mod matrix_eigen;
+mod matrix_elimination;
+mod matrix_function;
 mod matrix_iter_mut;
 mod matrix_iterator;
+mod matrix_kron;
+mod matrix_lattice;
+mod matrix_lu;
 mod matrix_mul;
 mod matrix_neg;
+mod matrix_normal_form;
+mod matrix_parse;
+mod matrix_polynomial;
 mod matrix_rem;
 mod matrix_scalar_mul;
+mod matrix_schur;
+mod matrix_semiring;
+#[cfg(feature = "serde")]
+mod matrix_serde;
 mod matrix_sub;
+mod matrix_table;
+pub use matrix_display::MatrixDisplay;
+pub use matrix_parse::ParseMatrixError;
+pub use matrix_table::{LatexEnvironment, MatrixTable};
 use matrix_iter_mut::MatrixIteratorMut;
 use matrix_iterator::MatrixIterator;
 pub mod func;
 mod func_apply;
+pub mod num;
+pub mod rational;
 pub mod scalar;
+pub mod semiring;
+use num::Semiring;
 
 /// A matrix can be created by use of any scalar type that as the very least
-/// implements `Copy` and `PartialEq`.
+/// implements `Clone` and `PartialEq`.
 #[derive(PartialEq, Debug, Clone)]
-pub struct Matrix<T: Copy + PartialEq> {
+pub struct Matrix<T: Clone + PartialEq> {
   data: Vec<T>,
   n_rows: usize,
   n_cols: usize,
 }
 
-impl<T: Copy + PartialEq> Matrix<T> {
+impl<T: Clone + PartialEq> Matrix<T> {
   /// Creates a matrix by using the given 2-d vector.
   pub fn new(mut v: Vec<Vec<T>>) -> Matrix<T> {
     let n_rows = v.len();
     let n_cols = v[0].len();
     let mut data = vec![];
     for row in v.iter_mut() {
-      while row.len() > 0 {
+      while !row.is_empty() {
         data.push(row.remove(0));
       }
     }
@@ -53,16 +81,31 @@ impl<T: Copy + PartialEq> Matrix<T> {
     }
   }
 
+  /// Returns the number of rows.
+  pub fn n_rows(&self) -> usize {
+    self.n_rows
+  }
+
+  /// Returns the number of columns.
+  pub fn n_cols(&self) -> usize {
+    self.n_cols
+  }
+
   /// Obtain the element at row `i` and column `j`.
   pub fn get(&self, i: usize, j: usize) -> T {
-    return self.data[i * self.n_cols + j];
+    self.data[i * self.n_cols + j].clone()
+  }
+
+  /// Sets the element at row `i` and column `j` to `value`.
+  pub fn set(&mut self, i: usize, j: usize, value: T) {
+    self.data[i * self.n_cols + j] = value;
   }
 
   /// Obtain the `i`'th row as a 1-d matrix.
   pub fn get_row(&self, i: usize) -> Matrix<T> {
     let mut row = vec![];
     for e in &self.data[i * self.n_cols..(i + 1) * self.n_cols] {
-      row.push(*e);
+      row.push(e.clone());
     }
     let n_cols = row.len();
     Matrix::create_from_data(row, 1, n_cols)
@@ -73,12 +116,37 @@ impl<T: Copy + PartialEq> Matrix<T> {
     let col = self
       .iter()
       .filter(|(_, _, col)| *col == i)
-      .map(|(e, _, _)| *e)
+      .map(|(e, _, _)| e.clone())
       .collect::<Vec<T>>();
     let n_rows = col.len();
     Matrix::create_from_data(col, n_rows, 1)
   }
 
+  /// Creates a matrix from the rows with the given indices, in the given order.
+  pub fn select_rows(&self, rows: &[usize]) -> Matrix<T> {
+    let mut data = Vec::with_capacity(rows.len() * self.n_cols);
+    for &i in rows {
+      data.extend_from_slice(&self.data[i * self.n_cols..(i + 1) * self.n_cols]);
+    }
+    Matrix::create_from_data(data, rows.len(), self.n_cols)
+  }
+
+  /// Swaps the rows `i` and `j` in place.
+  pub fn swap_rows(&mut self, i: usize, j: usize) {
+    if i != j {
+      for k in 0..self.n_cols {
+        self.data.swap(i * self.n_cols + k, j * self.n_cols + k);
+      }
+    }
+  }
+
+  /// Applies `f` to every element and returns the resulting matrix, which may have
+  /// a different scalar type.
+  pub fn map<U: Clone + PartialEq, F: Fn(T) -> U>(&self, f: F) -> Matrix<U> {
+    let data = self.data.iter().cloned().map(f).collect();
+    Matrix::create_from_data(data, self.n_rows, self.n_cols)
+  }
+
   /// Create an immutable iterator over the matrix.
   /// The iteration is performed row after row.
   pub fn iter<'a>(&'a self) -> MatrixIterator<'a, T> {
@@ -96,13 +164,29 @@ impl<T: Copy + PartialEq> Matrix<T> {
     let mut data = vec![];
     for j in 0..self.n_cols {
       for i in 0..self.n_rows {
-        data.push(self.data[j + i * self.n_cols]);
+        data.push(self.data[j + i * self.n_cols].clone());
       }
     }
     Matrix::create_from_data(data, self.n_cols, self.n_rows)
   }
 }
 
+impl<T: Semiring> Matrix<T> {
+  /// Creates a matrix of the given dimension having all elements set to zero.
+  pub fn zeros(n_rows: usize, n_cols: usize) -> Matrix<T> {
+    Matrix::create_from_data(vec![T::zero(); n_rows * n_cols], n_rows, n_cols)
+  }
+
+  /// Creates the `n` x `n` identity matrix.
+  pub fn identity(n: usize) -> Matrix<T> {
+    let mut m = Matrix::zeros(n, n);
+    for i in 0..n {
+      m.data[i * n + i] = T::one();
+    }
+    m
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -130,6 +214,13 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_set() {
+    let mut m = Matrix::new(vec![vec![0, 1], vec![2, 3]]);
+    m.set(1, 0, 5);
+    assert_eq!(m, Matrix::new(vec![vec![0, 1], vec![5, 3]]));
+  }
+
   #[test]
   fn test_get_row_1() {
     let m = Matrix::new(vec![vec![0, 1], vec![2, 3]]);
@@ -154,6 +245,39 @@ mod tests {
     assert_eq!(m.get_col(1), Matrix::create_from_data(vec![1, 3], 2, 1));
   }
 
+  #[test]
+  fn test_select_rows() {
+    let m = Matrix::new(vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+    assert_eq!(m.select_rows(&[2, 0, 2]), Matrix::new(vec![vec![4, 5], vec![0, 1], vec![4, 5]]));
+  }
+
+  #[test]
+  fn test_swap_rows() {
+    let mut m = Matrix::new(vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+    m.swap_rows(0, 2);
+    assert_eq!(m, Matrix::new(vec![vec![4, 5], vec![2, 3], vec![0, 1]]));
+  }
+
+  #[test]
+  fn test_map() {
+    let m = Matrix::new(vec![vec![0, 1], vec![2, 3]]);
+    assert_eq!(m.map(|x| x > 1), Matrix::new(vec![vec![false, false], vec![true, true]]));
+  }
+
+  #[test]
+  fn test_zeros() {
+    let m: Matrix<i32> = Matrix::zeros(2, 3);
+    assert_eq!(m, Matrix::create_from_data(vec![0; 6], 2, 3));
+  }
+
+  #[test]
+  fn test_identity() {
+    let m: Matrix<f64> = Matrix::identity(2);
+    assert_eq!(m, Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 1.0]]));
+    let a = Matrix::new(vec![vec![1, 2], vec![3, 4]]);
+    assert_eq!(&a * &Matrix::identity(2), a);
+  }
+
   #[test]
   fn test_trans() {
     let m = Matrix::new(vec![vec![0, 1], vec![2, 3]]);
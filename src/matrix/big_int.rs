@@ -0,0 +1,563 @@
+use super::num::{Integer, One, Ring, Semiring, Zero};
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use core::str::FromStr;
+
+/// An arbitrary-precision signed integer.
+///
+/// ```
+/// use matrix_algebra::BigInt;
+///
+/// let a: BigInt = "123456789012345678901234567890".parse().unwrap();
+/// let b = BigInt::from(1_000_000_007);
+///
+/// assert_eq!((&a * &b / &b), a);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+  negative: bool,
+  // Digits in base 2^32, least significant first, without leading zeros.
+  // Zero is represented by an empty vector and is never negative.
+  mag: Vec<u32>,
+}
+
+/// The error returned when parsing a `BigInt` from a string fails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseBigIntError;
+
+impl fmt::Display for ParseBigIntError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "invalid digit found in string")
+  }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+fn trim(mag: &mut Vec<u32>) {
+  while let Some(&0) = mag.last() {
+    mag.pop();
+  }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+  if a.len() != b.len() {
+    return a.len().cmp(&b.len());
+  }
+  for (x, y) in a.iter().rev().zip(b.iter().rev()) {
+    if x != y {
+      return x.cmp(y);
+    }
+  }
+  Ordering::Equal
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+  let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+  let mut res = Vec::with_capacity(long.len() + 1);
+  let mut carry = 0u64;
+  for (i, x) in long.iter().enumerate() {
+    let s = *x as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+    res.push(s as u32);
+    carry = s >> 32;
+  }
+  if carry > 0 {
+    res.push(carry as u32);
+  }
+  res
+}
+
+// Requires |a| >= |b|.
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+  let mut res = Vec::with_capacity(a.len());
+  let mut borrow = 0i64;
+  for (i, x) in a.iter().enumerate() {
+    let mut d = *x as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+    borrow = 0;
+    if d < 0 {
+      d += 1 << 32;
+      borrow = 1;
+    }
+    res.push(d as u32);
+  }
+  trim(&mut res);
+  res
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+  if a.is_empty() || b.is_empty() {
+    return vec![];
+  }
+  let mut res = vec![0u32; a.len() + b.len()];
+  for (i, x) in a.iter().enumerate() {
+    let mut carry = 0u64;
+    for (j, y) in b.iter().enumerate() {
+      let t = *x as u64 * *y as u64 + res[i + j] as u64 + carry;
+      res[i + j] = t as u32;
+      carry = t >> 32;
+    }
+    res[i + b.len()] = carry as u32;
+  }
+  trim(&mut res);
+  res
+}
+
+// Shifts left by `s < 32` bits, always appending one more digit.
+fn shl_bits(a: &[u32], s: u32) -> Vec<u32> {
+  let mut res = Vec::with_capacity(a.len() + 1);
+  let mut carry = 0u32;
+  for x in a {
+    res.push((x << s) | carry);
+    carry = if s == 0 { 0 } else { x >> (32 - s) };
+  }
+  res.push(carry);
+  res
+}
+
+fn shr_bits(a: &[u32], s: u32) -> Vec<u32> {
+  let mut res = vec![0u32; a.len()];
+  for i in 0..a.len() {
+    let hi = if s == 0 || i + 1 == a.len() {
+      0
+    } else {
+      a[i + 1] << (32 - s)
+    };
+    res[i] = (a[i] >> s) | hi;
+  }
+  trim(&mut res);
+  res
+}
+
+// Long division following Knuth's algorithm D. Requires a non-zero divisor.
+fn div_rem_mag(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+  if cmp_mag(u, v) == Ordering::Less {
+    return (vec![], u.to_vec());
+  }
+  if v.len() == 1 {
+    let d = v[0] as u64;
+    let mut q = vec![0u32; u.len()];
+    let mut r = 0u64;
+    for i in (0..u.len()).rev() {
+      let cur = (r << 32) | u[i] as u64;
+      q[i] = (cur / d) as u32;
+      r = cur % d;
+    }
+    trim(&mut q);
+    let mut r = vec![r as u32];
+    trim(&mut r);
+    return (q, r);
+  }
+  let n = v.len();
+  let m = u.len() - n;
+  let s = v[n - 1].leading_zeros();
+  let mut vn = shl_bits(v, s);
+  vn.pop();
+  let mut un = shl_bits(u, s);
+  let mut q = vec![0u32; m + 1];
+  let base = 1u64 << 32;
+  for j in (0..=m).rev() {
+    let num = ((un[j + n] as u64) << 32) | un[j + n - 1] as u64;
+    let mut qhat = num / vn[n - 1] as u64;
+    let mut rhat = num % vn[n - 1] as u64;
+    while qhat >= base || qhat * vn[n - 2] as u64 > ((rhat << 32) | un[j + n - 2] as u64) {
+      qhat -= 1;
+      rhat += vn[n - 1] as u64;
+      if rhat >= base {
+        break;
+      }
+    }
+    let mut borrow = 0i64;
+    let mut carry = 0u64;
+    for i in 0..n {
+      let p = qhat * vn[i] as u64 + carry;
+      carry = p >> 32;
+      let t = un[i + j] as i64 - borrow - (p & 0xffff_ffff) as i64;
+      un[i + j] = t as u32;
+      borrow = if t < 0 { 1 } else { 0 };
+    }
+    let t = un[j + n] as i64 - borrow - carry as i64;
+    un[j + n] = t as u32;
+    if t < 0 {
+      qhat -= 1;
+      let mut c = 0u64;
+      for i in 0..n {
+        let s = un[i + j] as u64 + vn[i] as u64 + c;
+        un[i + j] = s as u32;
+        c = s >> 32;
+      }
+      un[j + n] = un[j + n].wrapping_add(c as u32);
+    }
+    q[j] = qhat as u32;
+  }
+  trim(&mut q);
+  (q, shr_bits(&un[..n], s))
+}
+
+impl BigInt {
+  fn from_parts(negative: bool, mut mag: Vec<u32>) -> BigInt {
+    trim(&mut mag);
+    BigInt {
+      negative: negative && !mag.is_empty(),
+      mag,
+    }
+  }
+
+  /// Returns `true` if the number is strictly less than zero.
+  pub fn is_negative(&self) -> bool {
+    self.negative
+  }
+
+  /// Returns the absolute value.
+  pub fn abs(&self) -> BigInt {
+    BigInt::from_parts(false, self.mag.clone())
+  }
+
+  /// Returns `-1`, `0` or `1` depending on the sign of the number.
+  pub fn signum(&self) -> i32 {
+    if self.mag.is_empty() {
+      0
+    } else if self.negative {
+      -1
+    } else {
+      1
+    }
+  }
+
+  /// Raises the number to the power `exp`.
+  pub fn pow(&self, mut exp: u32) -> BigInt {
+    let mut base = self.clone();
+    let mut res = BigInt::one();
+    while exp > 0 {
+      if exp & 1 == 1 {
+        res = &res * &base;
+      }
+      exp >>= 1;
+      if exp > 0 {
+        base = &base * &base;
+      }
+    }
+    res
+  }
+
+  /// Computes quotient and remainder at once. The quotient is truncated
+  /// towards zero and the remainder has the sign of `self`, like for the
+  /// primitive integer types.
+  pub fn div_rem(&self, rhs: &BigInt) -> (BigInt, BigInt) {
+    if rhs.mag.is_empty() {
+      panic!("attempt to divide by zero");
+    }
+    let (q, r) = div_rem_mag(&self.mag, &rhs.mag);
+    (
+      BigInt::from_parts(self.negative != rhs.negative, q),
+      BigInt::from_parts(self.negative, r),
+    )
+  }
+
+  /// Converts to `i128` if the value fits.
+  pub fn to_i128(&self) -> Option<i128> {
+    if self.mag.len() > 4 {
+      return None;
+    }
+    let mut abs = 0u128;
+    for d in self.mag.iter().rev() {
+      abs = (abs << 32) | *d as u128;
+    }
+    if self.negative {
+      if abs <= i128::MAX as u128 + 1 {
+        Some((abs as i128).wrapping_neg())
+      } else {
+        None
+      }
+    } else if abs <= i128::MAX as u128 {
+      Some(abs as i128)
+    } else {
+      None
+    }
+  }
+
+  /// Converts to `i64` if the value fits.
+  pub fn to_i64(&self) -> Option<i64> {
+    self.to_i128().and_then(|v| i64::try_from(v).ok())
+  }
+
+  /// Converts to the nearest `f64`, possibly losing precision.
+  pub fn to_f64(&self) -> f64 {
+    let v = self
+      .mag
+      .iter()
+      .rev()
+      .fold(0.0, |acc, d| acc * 4294967296.0 + *d as f64);
+    if self.negative {
+      -v
+    } else {
+      v
+    }
+  }
+}
+
+impl From<i128> for BigInt {
+  fn from(v: i128) -> BigInt {
+    let mut abs = v.unsigned_abs();
+    let mut mag = vec![];
+    while abs > 0 {
+      mag.push(abs as u32);
+      abs >>= 32;
+    }
+    BigInt::from_parts(v < 0, mag)
+  }
+}
+
+macro_rules! big_int_from {
+  ($($T:ty),*) => {
+    $(
+      impl From<$T> for BigInt {
+        fn from(v: $T) -> BigInt {
+          BigInt::from(v as i128)
+        }
+      }
+    )*
+  };
+}
+big_int_from!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl PartialOrd for BigInt {
+  fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for BigInt {
+  fn cmp(&self, other: &BigInt) -> Ordering {
+    match (self.negative, other.negative) {
+      (false, true) => Ordering::Greater,
+      (true, false) => Ordering::Less,
+      (false, false) => cmp_mag(&self.mag, &other.mag),
+      (true, true) => cmp_mag(&other.mag, &self.mag),
+    }
+  }
+}
+
+fn add_signed(a: &BigInt, b_negative: bool, b: &BigInt) -> BigInt {
+  if a.negative == b_negative {
+    return BigInt::from_parts(a.negative, add_mag(&a.mag, &b.mag));
+  }
+  match cmp_mag(&a.mag, &b.mag) {
+    Ordering::Less => BigInt::from_parts(b_negative, sub_mag(&b.mag, &a.mag)),
+    _ => BigInt::from_parts(a.negative, sub_mag(&a.mag, &b.mag)),
+  }
+}
+
+fn big_add(a: &BigInt, b: &BigInt) -> BigInt {
+  add_signed(a, b.negative, b)
+}
+
+fn big_sub(a: &BigInt, b: &BigInt) -> BigInt {
+  add_signed(a, !b.negative && !b.mag.is_empty(), b)
+}
+
+fn big_mul(a: &BigInt, b: &BigInt) -> BigInt {
+  BigInt::from_parts(a.negative != b.negative, mul_mag(&a.mag, &b.mag))
+}
+
+fn big_div(a: &BigInt, b: &BigInt) -> BigInt {
+  a.div_rem(b).0
+}
+
+fn big_rem(a: &BigInt, b: &BigInt) -> BigInt {
+  a.div_rem(b).1
+}
+
+macro_rules! big_int_op {
+  ($Trait:ident, $method:ident, $f:ident) => {
+    impl $Trait<BigInt> for BigInt {
+      type Output = BigInt;
+      fn $method(self, rhs: BigInt) -> BigInt {
+        $f(&self, &rhs)
+      }
+    }
+    impl $Trait<&BigInt> for BigInt {
+      type Output = BigInt;
+      fn $method(self, rhs: &BigInt) -> BigInt {
+        $f(&self, rhs)
+      }
+    }
+    impl $Trait<BigInt> for &BigInt {
+      type Output = BigInt;
+      fn $method(self, rhs: BigInt) -> BigInt {
+        $f(self, &rhs)
+      }
+    }
+    impl $Trait<&BigInt> for &BigInt {
+      type Output = BigInt;
+      fn $method(self, rhs: &BigInt) -> BigInt {
+        $f(self, rhs)
+      }
+    }
+  };
+}
+big_int_op!(Add, add, big_add);
+big_int_op!(Sub, sub, big_sub);
+big_int_op!(Mul, mul, big_mul);
+big_int_op!(Div, div, big_div);
+big_int_op!(Rem, rem, big_rem);
+
+impl Neg for BigInt {
+  type Output = BigInt;
+  fn neg(self) -> BigInt {
+    let negative = !self.negative;
+    BigInt::from_parts(negative, self.mag)
+  }
+}
+
+impl Neg for &BigInt {
+  type Output = BigInt;
+  fn neg(self) -> BigInt {
+    -self.clone()
+  }
+}
+
+impl Zero for BigInt {
+  fn zero() -> BigInt {
+    BigInt {
+      negative: false,
+      mag: vec![],
+    }
+  }
+  fn is_zero(&self) -> bool {
+    self.mag.is_empty()
+  }
+}
+
+impl One for BigInt {
+  fn one() -> BigInt {
+    BigInt {
+      negative: false,
+      mag: vec![1],
+    }
+  }
+}
+
+impl Semiring for BigInt {}
+impl Ring for BigInt {}
+
+impl Integer for BigInt {
+  fn checked_add(&self, rhs: &BigInt) -> Option<BigInt> {
+    Some(self + rhs)
+  }
+  fn checked_sub(&self, rhs: &BigInt) -> Option<BigInt> {
+    Some(self - rhs)
+  }
+  fn checked_mul(&self, rhs: &BigInt) -> Option<BigInt> {
+    Some(self * rhs)
+  }
+  fn to_f64(&self) -> f64 {
+    BigInt::to_f64(self)
+  }
+}
+
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+
+impl fmt::Display for BigInt {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut chunks = vec![];
+    let mut mag = self.mag.clone();
+    while !mag.is_empty() {
+      let (q, r) = div_rem_mag(&mag, &[DECIMAL_CHUNK]);
+      chunks.push(r.first().cloned().unwrap_or(0));
+      mag = q;
+    }
+    let mut s = chunks.pop().unwrap_or(0).to_string();
+    for c in chunks.iter().rev() {
+      s.push_str(&format!("{:09}", c));
+    }
+    f.pad_integral(!self.negative, "", &s)
+  }
+}
+
+impl fmt::Debug for BigInt {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    fmt::Display::fmt(self, f)
+  }
+}
+
+impl FromStr for BigInt {
+  type Err = ParseBigIntError;
+  fn from_str(s: &str) -> Result<BigInt, ParseBigIntError> {
+    let (negative, digits) = match s.as_bytes().first() {
+      Some(b'-') => (true, &s[1..]),
+      Some(b'+') => (false, &s[1..]),
+      _ => (false, s),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+      return Err(ParseBigIntError);
+    }
+    let mut mag: Vec<u32> = vec![];
+    let head = digits.len() % 9;
+    let mut start = 0;
+    let mut end = if head == 0 { 9 } else { head };
+    while start < digits.len() {
+      let chunk: u32 = digits[start..end].parse().map_err(|_| ParseBigIntError)?;
+      let scale = 10u32.pow((end - start) as u32);
+      mag = add_mag(&mul_mag(&mag, &[scale]), &[chunk]);
+      trim(&mut mag);
+      start = end;
+      end += 9;
+    }
+    Ok(BigInt::from_parts(negative, mag))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn big(s: &str) -> BigInt {
+    s.parse().unwrap()
+  }
+
+  #[test]
+  fn test_parse_display() {
+    let s = "-123456789012345678901234567890";
+    assert_eq!(big(s).to_string(), s);
+    assert_eq!(big("+0").to_string(), "0");
+    assert_eq!(big("1000000000").to_string(), "1000000000");
+    assert!("12a".parse::<BigInt>().is_err());
+  }
+
+  #[test]
+  fn test_add_sub() {
+    let a = big("18446744073709551615");
+    assert_eq!(&a + BigInt::one(), big("18446744073709551616"));
+    assert_eq!(BigInt::from(5) - BigInt::from(7), BigInt::from(-2));
+    assert_eq!(BigInt::from(-5) + BigInt::from(5), BigInt::zero());
+  }
+
+  #[test]
+  fn test_mul_div() {
+    let a = big("987654321987654321987654321");
+    let b = big("-123456789123456789");
+    let c = &a * &b;
+    assert_eq!(&c / &b, a);
+    assert_eq!(&c % &b, BigInt::zero());
+    let (q, r) = (&a + BigInt::from(17)).div_rem(&b);
+    assert_eq!(q * &b + r, &a + BigInt::from(17));
+  }
+
+  #[test]
+  fn test_div_rem_sign() {
+    for (x, y) in [(7i64, 2i64), (-7, 2), (7, -2), (-7, -2)].iter() {
+      let (q, r) = BigInt::from(*x).div_rem(&BigInt::from(*y));
+      assert_eq!((q.to_i64(), r.to_i64()), (Some(x / y), Some(x % y)));
+    }
+  }
+
+  #[test]
+  fn test_pow_and_conversions() {
+    assert_eq!(BigInt::from(2).pow(100).to_string(), "1267650600228229401496703205376");
+    assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+    assert_eq!(BigInt::from(2).pow(64).to_i64(), None);
+    assert_eq!(BigInt::from(-3).to_f64(), -3.0);
+    assert!(BigInt::from(-3) < BigInt::from(2));
+  }
+}
@@ -0,0 +1,163 @@
+use super::num::{Field, One, RealField, Ring, Semiring, Zero};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use std::fmt;
+
+/// A complex number `re + im * i`.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Complex<T> {
+  pub re: T,
+  pub im: T,
+}
+
+impl<T: Field> Complex<T> {
+  /// Creates the complex number `re + im * i`.
+  pub fn new(re: T, im: T) -> Complex<T> {
+    Complex { re, im }
+  }
+
+  /// Returns the complex conjugate `re - im * i`.
+  pub fn conj(&self) -> Complex<T> {
+    Complex::new(self.re.clone(), -self.im.clone())
+  }
+
+  /// Returns the squared modulus `re^2 + im^2`.
+  pub fn norm_sqr(&self) -> T {
+    self.re.clone() * self.re.clone() + self.im.clone() * self.im.clone()
+  }
+}
+
+impl<T: RealField> Complex<T> {
+  /// Returns the modulus `sqrt(re^2 + im^2)`.
+  pub fn norm(&self) -> T {
+    self.norm_sqr().sqrt()
+  }
+}
+
+impl<T: Field> From<T> for Complex<T> {
+  fn from(re: T) -> Complex<T> {
+    Complex::new(re, T::zero())
+  }
+}
+
+impl<T: Field> Add for Complex<T> {
+  type Output = Complex<T>;
+  fn add(self, rhs: Complex<T>) -> Complex<T> {
+    Complex::new(self.re + rhs.re, self.im + rhs.im)
+  }
+}
+
+impl<T: Field> Sub for Complex<T> {
+  type Output = Complex<T>;
+  fn sub(self, rhs: Complex<T>) -> Complex<T> {
+    Complex::new(self.re - rhs.re, self.im - rhs.im)
+  }
+}
+
+impl<T: Field> Mul for Complex<T> {
+  type Output = Complex<T>;
+  fn mul(self, rhs: Complex<T>) -> Complex<T> {
+    let re = self.re.clone() * rhs.re.clone() - self.im.clone() * rhs.im.clone();
+    Complex::new(re, self.re * rhs.im + self.im * rhs.re)
+  }
+}
+
+impl<T: Field> Div for Complex<T> {
+  type Output = Complex<T>;
+  fn div(self, rhs: Complex<T>) -> Complex<T> {
+    let d = rhs.norm_sqr();
+    let n = self * rhs.conj();
+    Complex::new(n.re / d.clone(), n.im / d)
+  }
+}
+
+macro_rules! complex_ref_op {
+  ($($Trait:ident, $method:ident);*) => {
+    $(
+      impl<T: Field> $Trait<&Complex<T>> for Complex<T> {
+        type Output = Complex<T>;
+        fn $method(self, rhs: &Complex<T>) -> Complex<T> {
+          self.$method(rhs.clone())
+        }
+      }
+      impl<T: Field> $Trait<Complex<T>> for &Complex<T> {
+        type Output = Complex<T>;
+        fn $method(self, rhs: Complex<T>) -> Complex<T> {
+          self.clone().$method(rhs)
+        }
+      }
+      impl<T: Field> $Trait<&Complex<T>> for &Complex<T> {
+        type Output = Complex<T>;
+        fn $method(self, rhs: &Complex<T>) -> Complex<T> {
+          self.clone().$method(rhs.clone())
+        }
+      }
+    )*
+  };
+}
+complex_ref_op!(Add, add; Sub, sub; Mul, mul; Div, div);
+
+impl<T: Field> Neg for Complex<T> {
+  type Output = Complex<T>;
+  fn neg(self) -> Complex<T> {
+    Complex::new(-self.re, -self.im)
+  }
+}
+
+impl<T: Field> Zero for Complex<T> {
+  fn zero() -> Complex<T> {
+    Complex::new(T::zero(), T::zero())
+  }
+  fn is_zero(&self) -> bool {
+    self.re.is_zero() && self.im.is_zero()
+  }
+}
+
+impl<T: Field> One for Complex<T> {
+  fn one() -> Complex<T> {
+    Complex::new(T::one(), T::zero())
+  }
+}
+
+impl<T: Field> Semiring for Complex<T> {}
+impl<T: Field> Ring for Complex<T> {}
+impl<T: Field> Field for Complex<T> {}
+
+impl<T: RealField + fmt::Display> fmt::Display for Complex<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.im < T::zero() {
+      write!(f, "{}-{}i", self.re, -self.im)
+    } else {
+      write!(f, "{}+{}i", self.re, self.im)
+    }
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::op_ref)]
+mod tests {
+  use super::super::Matrix;
+  use super::*;
+
+  #[test]
+  fn test_complex_arithmetic() {
+    let a = Complex::new(1.0, 2.0);
+    let b = Complex::new(3.0, -1.0);
+    assert_eq!(a * b, Complex::new(5.0, 5.0));
+    assert_eq!(&(a * b) / &b, a);
+    assert_eq!(a - a, Complex::zero());
+    assert_eq!(a * a.conj(), Complex::from(a.norm_sqr()));
+    assert_eq!(Complex::new(3.0, 4.0).norm(), 5.0);
+    assert_eq!(format!("{} {}", a, b), "1+2i 3-1i");
+  }
+
+  #[test]
+  fn test_complex_matrix() {
+    let i = Complex::new(0.0, 1.0);
+    let one = Complex::one();
+    // The rotation by 90 degrees has the eigenvector (1, -i) for the eigenvalue -i.
+    let m = Matrix::new(vec![vec![Complex::zero(), one], vec![-one, Complex::zero()]]);
+    let v = Matrix::new(vec![vec![one], vec![-i]]);
+    assert_eq!(&m * &v, &v * -i);
+    assert_eq!(m.det(), one);
+  }
+}
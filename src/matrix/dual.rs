@@ -0,0 +1,275 @@
+use super::num::{Field, One, Real, RealField, Ring, Semiring, Zero};
+use core::cmp::Ordering;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A dual number `re + eps * ε` with `ε * ε = 0`. Evaluating a function on
+/// `Dual::variable(x)` yields the function value in `re` and its derivative at `x`
+/// in `eps`. Nesting, as in `Dual<Dual<f64>>`, gives hyper-dual numbers which also
+/// carry second derivatives.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Dual<T> {
+  pub re: T,
+  pub eps: T,
+}
+
+impl<T: Field> Dual<T> {
+  /// Creates the dual number `re + eps * ε`.
+  pub fn new(re: T, eps: T) -> Dual<T> {
+    Dual { re, eps }
+  }
+
+  /// Creates a dual number whose derivative vanishes.
+  pub fn constant(re: T) -> Dual<T> {
+    Dual::new(re, T::zero())
+  }
+
+  /// Creates a dual number with derivative one, i.e. the variable being differentiated.
+  pub fn variable(re: T) -> Dual<T> {
+    Dual::new(re, T::one())
+  }
+}
+
+/// Dual numbers are ordered by their value `re`. Numbers of equal value but
+/// different derivatives are incomparable, which keeps the order consistent with
+/// `PartialEq`.
+impl<T: PartialOrd> PartialOrd for Dual<T> {
+  fn partial_cmp(&self, other: &Dual<T>) -> Option<Ordering> {
+    match self.re.partial_cmp(&other.re)? {
+      Ordering::Equal if self.eps != other.eps => None,
+      ordering => Some(ordering),
+    }
+  }
+}
+
+impl<T: Field> Add for Dual<T> {
+  type Output = Dual<T>;
+  fn add(self, rhs: Dual<T>) -> Dual<T> {
+    Dual::new(self.re + rhs.re, self.eps + rhs.eps)
+  }
+}
+
+impl<T: Field> Sub for Dual<T> {
+  type Output = Dual<T>;
+  fn sub(self, rhs: Dual<T>) -> Dual<T> {
+    Dual::new(self.re - rhs.re, self.eps - rhs.eps)
+  }
+}
+
+impl<T: Field> Mul for Dual<T> {
+  type Output = Dual<T>;
+  fn mul(self, rhs: Dual<T>) -> Dual<T> {
+    let eps = self.re.clone() * rhs.eps + self.eps * rhs.re.clone();
+    Dual::new(self.re * rhs.re, eps)
+  }
+}
+
+impl<T: Field> Div for Dual<T> {
+  type Output = Dual<T>;
+  fn div(self, rhs: Dual<T>) -> Dual<T> {
+    let square = rhs.re.clone() * rhs.re.clone();
+    let eps = (self.eps * rhs.re.clone() - self.re.clone() * rhs.eps) / square;
+    Dual::new(self.re / rhs.re, eps)
+  }
+}
+
+macro_rules! dual_ref_op {
+  ($($Trait:ident, $method:ident);*) => {
+    $(
+      impl<T: Field> $Trait<&Dual<T>> for Dual<T> {
+        type Output = Dual<T>;
+        fn $method(self, rhs: &Dual<T>) -> Dual<T> {
+          self.$method(rhs.clone())
+        }
+      }
+      impl<T: Field> $Trait<Dual<T>> for &Dual<T> {
+        type Output = Dual<T>;
+        fn $method(self, rhs: Dual<T>) -> Dual<T> {
+          self.clone().$method(rhs)
+        }
+      }
+      impl<T: Field> $Trait<&Dual<T>> for &Dual<T> {
+        type Output = Dual<T>;
+        fn $method(self, rhs: &Dual<T>) -> Dual<T> {
+          self.clone().$method(rhs.clone())
+        }
+      }
+    )*
+  };
+}
+dual_ref_op!(Add, add; Sub, sub; Mul, mul; Div, div);
+
+impl<T: Field> Neg for Dual<T> {
+  type Output = Dual<T>;
+  fn neg(self) -> Dual<T> {
+    Dual::new(-self.re, -self.eps)
+  }
+}
+
+impl<T: Field> Zero for Dual<T> {
+  fn zero() -> Dual<T> {
+    Dual::constant(T::zero())
+  }
+  fn is_zero(&self) -> bool {
+    self.re.is_zero() && self.eps.is_zero()
+  }
+}
+
+impl<T: Field> One for Dual<T> {
+  fn one() -> Dual<T> {
+    Dual::constant(T::one())
+  }
+}
+
+impl<T: Field> Semiring for Dual<T> {}
+/// A dual number is invertible exactly if its value is, so elimination only
+/// pivots on those of non-zero value.
+impl<T: Field> Ring for Dual<T> {
+  fn is_pivot(&self) -> bool {
+    !self.re.is_zero()
+  }
+}
+impl<T: Field> Field for Dual<T> {}
+
+impl<T: RealField> RealField for Dual<T> {
+  fn sqrt(self) -> Dual<T> {
+    let s = self.re.sqrt();
+    Dual::new(s, self.eps / (s + s))
+  }
+  fn abs(self) -> Dual<T> {
+    if self.re < T::zero() {
+      -self
+    } else {
+      self
+    }
+  }
+  fn epsilon() -> Dual<T> {
+    Dual::constant(T::epsilon())
+  }
+}
+
+impl<T: Real> Real for Dual<T> {
+  fn from_f64(v: f64) -> Dual<T> {
+    Dual::constant(T::from_f64(v))
+  }
+  fn to_f64(self) -> f64 {
+    self.re.to_f64()
+  }
+  fn exp(self) -> Dual<T> {
+    let e = self.re.exp();
+    Dual::new(e, self.eps * e)
+  }
+  fn ln(self) -> Dual<T> {
+    Dual::new(self.re.ln(), self.eps / self.re)
+  }
+  fn sin(self) -> Dual<T> {
+    Dual::new(self.re.sin(), self.eps * self.re.cos())
+  }
+  fn cos(self) -> Dual<T> {
+    Dual::new(self.re.cos(), -self.eps * self.re.sin())
+  }
+  fn tan(self) -> Dual<T> {
+    let t = self.re.tan();
+    Dual::new(t, self.eps * (T::one() + t * t))
+  }
+  fn tanh(self) -> Dual<T> {
+    let t = self.re.tanh();
+    Dual::new(t, self.eps * (T::one() - t * t))
+  }
+  fn powi(self, n: i32) -> Dual<T> {
+    let d = T::from_f64(n as f64) * self.re.powi(n - 1);
+    Dual::new(self.re.powi(n), self.eps * d)
+  }
+  fn powf(self, n: Dual<T>) -> Dual<T> {
+    if n.eps.is_zero() {
+      let d = n.re * self.re.powf(n.re - T::one());
+      Dual::new(self.re.powf(n.re), self.eps * d)
+    } else {
+      (self.ln() * n).exp()
+    }
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::op_ref)]
+mod tests {
+  use super::super::func::Func;
+  use super::super::Matrix;
+  use super::*;
+
+  #[test]
+  fn test_dual_arithmetic() {
+    let x = Dual::variable(3.0);
+    let y = x * x + Dual::constant(2.0) * x;
+    assert_eq!(y, Dual::new(15.0, 8.0));
+    let z = Dual::constant(1.0) / &x;
+    assert_eq!(z, Dual::new(1.0 / 3.0, -1.0 / 9.0));
+    assert_eq!(&x - &x, Dual::zero());
+  }
+
+  #[test]
+  fn test_dual_elementary() {
+    let x = Dual::variable(0.5f64);
+    assert_eq!(x.sin().eps, 0.5f64.cos());
+    assert_eq!(x.exp().eps, 0.5f64.exp());
+    assert_eq!(x.ln().eps, 2.0);
+    assert_eq!(x.powi(3).eps, 0.75);
+    assert!((x.powf(Dual::constant(2.5)).eps - 2.5 * 0.5f64.powf(1.5)).abs() < 1e-15);
+    assert!((x.powf(x).eps - 0.5f64.powf(0.5) * (0.5f64.ln() + 1.0)).abs() < 1e-15);
+    assert_eq!(Dual::variable(4.0).sqrt(), Dual::new(2.0, 0.25));
+  }
+
+  #[test]
+  fn test_dual_matrix() {
+    let x = Dual::variable(2.0);
+    let c = |v: f64| Dual::constant(v);
+    let m = Matrix::new(vec![vec![x, c(1.0)], vec![c(3.0), x * x]]);
+    // d/dx det = d/dx (x^3 - 3) = 3 x^2
+    assert_eq!(m.det(), Dual::new(5.0, 12.0));
+    let tanh = Func::new(|v: Dual<f64>| v.tanh());
+    let t = tanh % Matrix::new(vec![vec![x]]);
+    assert_eq!(t.get(0, 0).eps, 1.0 - 2f64.tanh().powi(2));
+  }
+
+  #[test]
+  fn test_dual_pivot() {
+    let x = Dual::variable(2.0);
+    let c = |v: f64| Dual::constant(v);
+    let m = Matrix::new(vec![
+      vec![x - c(2.0), c(1.0), c(0.0)],
+      vec![c(1.0), c(0.0), c(1.0)],
+      vec![c(0.0), c(1.0), c(1.0)],
+    ]);
+    // det = -(x - 2) - 1, so the derivative is -1
+    assert_eq!(m.det(), Dual::new(-1.0, -1.0));
+    let inverse = m.inverse().unwrap();
+    let value = Matrix::new(vec![vec![1.0, 1.0, -1.0], vec![1.0, 0.0, 0.0], vec![-1.0, 0.0, 1.0]]);
+    assert_eq!(inverse.map(|e| e.re), value);
+    // d/dx M^-1 = -M^-1 (dM/dx) M^-1
+    let column = Matrix::new(vec![vec![1.0], vec![1.0], vec![-1.0]]);
+    let derivative = -(&column * &column.trans());
+    assert_eq!(inverse.map(|e| e.eps), derivative);
+    assert_eq!(&m * &inverse, Matrix::identity(3));
+    // A pivot of zero value keeps its derivative: det = x - 2
+    let m = Matrix::new(vec![vec![x - c(2.0), c(0.0)], vec![c(0.0), c(1.0)]]);
+    assert!(!m.get(0, 0).is_zero());
+    assert_eq!(m.det(), Dual::new(0.0, 1.0));
+    assert_eq!(m.inverse(), None);
+  }
+
+  #[test]
+  fn test_dual_order() {
+    assert!(Dual::new(1.0, 5.0) < Dual::new(2.0, 0.0));
+    assert!(Dual::new(2.0, -5.0) > Dual::new(1.0, 0.0));
+    assert_eq!(Dual::new(1.0, 0.0).partial_cmp(&Dual::new(1.0, 0.0)), Some(Ordering::Equal));
+    assert_eq!(Dual::new(1.0, 1.0).partial_cmp(&Dual::new(1.0, 0.0)), None);
+  }
+
+  #[test]
+  fn test_hyper_dual() {
+    let x = Dual::new(Dual::variable(2.0), Dual::constant(1.0));
+    let y = x.powi(3);
+    assert_eq!(y.re.re, 8.0);
+    assert_eq!(y.re.eps, 12.0);
+    assert_eq!(y.eps.eps, 12.0);
+  }
+}
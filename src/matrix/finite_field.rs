@@ -0,0 +1,262 @@
+use super::num::{Field, One, Ring, Semiring, Zero};
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use core::str::FromStr;
+
+/// An element of the finite field GF(P) of integers modulo the prime `P`, on
+/// which all the exact matrix algorithms like `rank` or `solve` work. Using a
+/// modulus which is not prime fails to compile, since the integers modulo it
+/// do not form a field.
+///
+/// ```
+/// use matrix_algebra::{Matrix, Zp};
+///
+/// type F7 = Zp<7>;
+/// let m = Matrix::new(vec![vec![F7::new(3), F7::new(1)], vec![F7::new(2), F7::new(5)]]);
+///
+/// assert_eq!(m.det(), F7::new(6));
+/// assert_eq!(&m * &m.inverse().unwrap(), Matrix::identity(2));
+/// ```
+///
+/// ```compile_fail
+/// use matrix_algebra::Zp;
+///
+/// let x = Zp::<6>::new(4);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Zp<const P: u64>(u64);
+
+const fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+  ((a as u128 * b as u128) % m as u128) as u64
+}
+
+const fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+  let mut res = 1;
+  while exp > 0 {
+    if exp & 1 == 1 {
+      res = mul_mod(res, base, m);
+    }
+    base = mul_mod(base, base, m);
+    exp >>= 1;
+  }
+  res
+}
+
+// The Miller–Rabin test, which is deterministic for 64-bit numbers with the
+// first twelve primes as bases.
+const fn is_prime(n: u64) -> bool {
+  const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+  if n < 2 {
+    return false;
+  }
+  let mut i = 0;
+  while i < BASES.len() {
+    if n.is_multiple_of(BASES[i]) {
+      return n == BASES[i];
+    }
+    i += 1;
+  }
+  let (mut d, mut s) = (n - 1, 0);
+  while d.is_multiple_of(2) {
+    d /= 2;
+    s += 1;
+  }
+  let mut i = 0;
+  while i < BASES.len() {
+    let mut x = pow_mod(BASES[i], d, n);
+    let mut r = 1;
+    while x != 1 && x != n - 1 && r < s {
+      x = mul_mod(x, x, n);
+      r += 1;
+    }
+    if x != 1 && x != n - 1 {
+      return false;
+    }
+    i += 1;
+  }
+  true
+}
+
+impl<const P: u64> Zp<P> {
+  // Evaluated wherever an element is created, such that a modulus which is not
+  // prime is rejected at compile time.
+  const PRIME: () = assert!(is_prime(P), "the modulus of Zp must be prime");
+
+  /// Creates the residue class of `v` modulo `P`.
+  pub fn new(v: u64) -> Zp<P> {
+    let () = Self::PRIME;
+    Zp(v % P)
+  }
+
+  /// Returns the canonical representative in `0..P`.
+  pub fn value(&self) -> u64 {
+    self.0
+  }
+
+  /// Raises the element to the power `exp`.
+  pub fn pow(&self, mut exp: u64) -> Zp<P> {
+    let mut base = *self;
+    let mut res = Zp::one();
+    while exp > 0 {
+      if exp & 1 == 1 {
+        res = res * base;
+      }
+      base = base * base;
+      exp >>= 1;
+    }
+    res
+  }
+
+  /// Returns the multiplicative inverse, or `None` if the element is zero.
+  pub fn inverse(&self) -> Option<Zp<P>> {
+    let (mut r0, mut r1) = (P as i128, self.0 as i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+      let q = r0 / r1;
+      let r = r0 - q * r1;
+      r0 = r1;
+      r1 = r;
+      let t = t0 - q * t1;
+      t0 = t1;
+      t1 = t;
+    }
+    if r0 != 1 {
+      return None;
+    }
+    Some(Zp(t0.rem_euclid(P as i128) as u64))
+  }
+
+  /// Divides by `rhs`, or returns `None` if `rhs` is zero.
+  pub fn checked_div(&self, rhs: &Zp<P>) -> Option<Zp<P>> {
+    rhs.inverse().map(|inv| *self * inv)
+  }
+}
+
+impl<const P: u64> From<i64> for Zp<P> {
+  fn from(v: i64) -> Zp<P> {
+    let () = Zp::<P>::PRIME;
+    Zp((v as i128).rem_euclid(P as i128) as u64)
+  }
+}
+
+impl<const P: u64> Add for Zp<P> {
+  type Output = Zp<P>;
+  fn add(self, rhs: Zp<P>) -> Zp<P> {
+    Zp(((self.0 as u128 + rhs.0 as u128) % P as u128) as u64)
+  }
+}
+
+impl<const P: u64> Sub for Zp<P> {
+  type Output = Zp<P>;
+  fn sub(self, rhs: Zp<P>) -> Zp<P> {
+    self + -rhs
+  }
+}
+
+impl<const P: u64> Mul for Zp<P> {
+  type Output = Zp<P>;
+  fn mul(self, rhs: Zp<P>) -> Zp<P> {
+    Zp(((self.0 as u128 * rhs.0 as u128) % P as u128) as u64)
+  }
+}
+
+impl<const P: u64> Div for Zp<P> {
+  type Output = Zp<P>;
+  // Division is multiplication by the inverse.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn div(self, rhs: Zp<P>) -> Zp<P> {
+    match self.checked_div(&rhs) {
+      Some(quotient) => quotient,
+      None => panic!("attempt to divide by zero"),
+    }
+  }
+}
+
+impl<const P: u64> Neg for Zp<P> {
+  type Output = Zp<P>;
+  fn neg(self) -> Zp<P> {
+    if self.0 == 0 {
+      self
+    } else {
+      Zp(P - self.0)
+    }
+  }
+}
+
+impl<const P: u64> Zero for Zp<P> {
+  fn zero() -> Zp<P> {
+    let () = Zp::<P>::PRIME;
+    Zp(0)
+  }
+  fn is_zero(&self) -> bool {
+    self.0 == 0
+  }
+}
+
+impl<const P: u64> One for Zp<P> {
+  fn one() -> Zp<P> {
+    Zp::new(1)
+  }
+}
+
+impl<const P: u64> Semiring for Zp<P> {}
+impl<const P: u64> Ring for Zp<P> {}
+impl<const P: u64> Field for Zp<P> {}
+
+impl<const P: u64> fmt::Display for Zp<P> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    fmt::Display::fmt(&self.0, f)
+  }
+}
+
+impl<const P: u64> FromStr for Zp<P> {
+  type Err = core::num::ParseIntError;
+  fn from_str(s: &str) -> Result<Zp<P>, Self::Err> {
+    s.trim().parse::<i64>().map(Zp::from)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Matrix;
+  use super::*;
+
+  type F7 = Zp<7>;
+  type F = Zp<1_000_000_007>;
+
+  #[test]
+  fn test_arithmetic() {
+    assert_eq!(F7::new(5) + F7::new(4), F7::new(2));
+    assert_eq!(F7::new(2) - F7::new(5), F7::new(4));
+    assert_eq!(F7::new(3) * F7::new(5), F7::new(1));
+    assert_eq!(F7::new(1) / F7::new(3), F7::new(5));
+    assert_eq!(-F7::new(0), F7::new(0));
+    assert_eq!(F7::from(-1), F7::new(6));
+  }
+
+  #[test]
+  fn test_inverse_and_pow() {
+    let a = F::new(123_456_789);
+    assert_eq!(a * a.inverse().unwrap(), F::one());
+    assert_eq!(a.pow(1_000_000_006), F::one());
+    assert_eq!(F::zero().inverse(), None);
+    assert_eq!(F7::new(3).checked_div(&F7::zero()), None);
+    assert!(is_prime(2) && is_prime(18_446_744_073_709_551_557));
+    assert!(!is_prime(0) && !is_prime(1) && !is_prime(3_215_031_751));
+  }
+
+  #[test]
+  fn test_matrix_over_gf_p() {
+    let m = Matrix::new(vec![
+      vec![F7::new(1), F7::new(2), F7::new(3)],
+      vec![F7::new(4), F7::new(5), F7::new(6)],
+      vec![F7::new(0), F7::new(1), F7::new(2)],
+    ]);
+    assert_eq!(m.rank(), 2);
+    let n = m.null_space();
+    assert_eq!(&m * &n, Matrix::zeros(3, 1));
+    let b = Matrix::new(vec![vec![F7::new(3)], vec![F7::new(2)], vec![F7::new(1)]]);
+    let x = m.solve(&b).unwrap();
+    assert_eq!(&m * &x, b);
+  }
+}
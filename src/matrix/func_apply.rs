@@ -3,12 +3,12 @@ use core::ops::Rem;
 
 macro_rules! elementw_func_apply {
   ($LHS:ty, $RHS:ty, $ScalarType:tt ) => {
-    impl<$ScalarType: Copy + PartialEq> Rem<$RHS> for $LHS {
+    impl<$ScalarType: Clone + PartialEq> Rem<$RHS> for $LHS {
       type Output = Matrix<$ScalarType>;
       fn rem(self, rhs: $RHS) -> Self::Output {
         let mut res: Vec<$ScalarType> = vec![];
         for (e, ..) in rhs.iter() {
-          res.push(self.0(*e));
+          res.push(self.0(e.clone()));
         }
         return Matrix::create_from_data(res, rhs.n_rows, rhs.n_cols);
       }
@@ -0,0 +1,401 @@
+use super::finite_field::Zp;
+use super::num::{One, Zero};
+use super::Matrix;
+use core::ops::{Add, Mul};
+
+// Number of columns eliminated at once by the Method of Four Russians.
+const M4RI_BLOCK: usize = 8;
+
+/// A matrix over GF(2) storing 64 entries per word. Addition is XOR and
+/// the product is computed with AND and popcount.
+///
+/// ```
+/// use matrix_algebra::Gf2Matrix;
+///
+/// let m = Gf2Matrix::new(vec![vec![1, 1, 0], vec![0, 1, 1], vec![1, 0, 1]]);
+///
+/// assert_eq!(m.rank(), 2);
+/// assert_eq!(&m * &m.null_space(), Gf2Matrix::zeros(3, 1));
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Gf2Matrix {
+  data: Vec<u64>,
+  n_rows: usize,
+  n_cols: usize,
+  words_per_row: usize,
+}
+
+impl Gf2Matrix {
+  /// Creates a matrix by using the given 2-d vector. Every entry is taken modulo 2.
+  pub fn new(v: Vec<Vec<u8>>) -> Gf2Matrix {
+    let mut m = Gf2Matrix::zeros(v.len(), v[0].len());
+    for (i, row) in v.iter().enumerate() {
+      if row.len() != m.n_cols {
+        panic!("not compatible dimension!");
+      }
+      for (j, e) in row.iter().enumerate() {
+        m.set(i, j, e % 2 == 1);
+      }
+    }
+    m
+  }
+
+  /// Creates a matrix of the given dimension having all elements set to zero.
+  pub fn zeros(n_rows: usize, n_cols: usize) -> Gf2Matrix {
+    let words_per_row = n_cols.div_ceil(64);
+    Gf2Matrix {
+      data: vec![0; n_rows * words_per_row],
+      n_rows,
+      n_cols,
+      words_per_row,
+    }
+  }
+
+  /// Creates the `n` x `n` identity matrix.
+  pub fn identity(n: usize) -> Gf2Matrix {
+    let mut m = Gf2Matrix::zeros(n, n);
+    for i in 0..n {
+      m.set(i, i, true);
+    }
+    m
+  }
+
+  /// Returns the number of rows.
+  pub fn n_rows(&self) -> usize {
+    self.n_rows
+  }
+
+  /// Returns the number of columns.
+  pub fn n_cols(&self) -> usize {
+    self.n_cols
+  }
+
+  /// Obtain the element at row `i` and column `j`.
+  pub fn get(&self, i: usize, j: usize) -> bool {
+    (self.data[i * self.words_per_row + j / 64] >> (j % 64)) & 1 == 1
+  }
+
+  /// Sets the element at row `i` and column `j`.
+  pub fn set(&mut self, i: usize, j: usize, v: bool) {
+    let word = &mut self.data[i * self.words_per_row + j / 64];
+    if v {
+      *word |= 1 << (j % 64);
+    } else {
+      *word &= !(1 << (j % 64));
+    }
+  }
+
+  fn row(&self, i: usize) -> &[u64] {
+    &self.data[i * self.words_per_row..(i + 1) * self.words_per_row]
+  }
+
+  fn swap_rows(&mut self, i: usize, j: usize) {
+    for k in 0..self.words_per_row {
+      self.data.swap(i * self.words_per_row + k, j * self.words_per_row + k);
+    }
+  }
+
+  // Adds the row `src` to the row `dst`, starting at word `from`.
+  fn xor_row(&mut self, dst: usize, src: usize, from: usize) {
+    let w = self.words_per_row;
+    for k in from..w {
+      self.data[dst * w + k] ^= self.data[src * w + k];
+    }
+  }
+
+  /// Transposes a copy of the matrix and returns the result.
+  pub fn trans(&self) -> Gf2Matrix {
+    let mut t = Gf2Matrix::zeros(self.n_cols, self.n_rows);
+    for i in 0..self.n_rows {
+      for j in 0..self.n_cols {
+        if self.get(i, j) {
+          t.set(j, i, true);
+        }
+      }
+    }
+    t
+  }
+
+  /// Computes the reduced row echelon form together with the indices of the pivot columns.
+  /// The elimination processes blocks of columns at once with the Method of Four Russians:
+  /// all combinations of the block's pivot rows are tabulated, so every other row is
+  /// reduced by a single table lookup.
+  pub fn rref_with_pivots(&self) -> (Gf2Matrix, Vec<usize>) {
+    let mut m = self.clone();
+    let mut pivots: Vec<usize> = vec![];
+    let mut r = 0;
+    let mut col = 0;
+    while col < m.n_cols && r < m.n_rows {
+      let col_end = usize::min(col + M4RI_BLOCK, m.n_cols);
+      let from = col / 64;
+      let mut block: Vec<(usize, usize)> = vec![];
+      for c in col..col_end {
+        let start = r + block.len();
+        let mut found = None;
+        for i in start..m.n_rows {
+          for &(pr, pc) in block.iter() {
+            if m.get(i, pc) {
+              m.xor_row(i, pr, from);
+            }
+          }
+          if m.get(i, c) {
+            found = Some(i);
+            break;
+          }
+        }
+        if let Some(i) = found {
+          m.swap_rows(i, start);
+          block.push((start, c));
+        }
+      }
+      for j in (0..block.len()).rev() {
+        let (pr, pc) = block[j];
+        for &(qr, _) in block[..j].iter() {
+          if m.get(qr, pc) {
+            m.xor_row(qr, pr, from);
+          }
+        }
+      }
+      if !block.is_empty() {
+        let w = m.words_per_row;
+        let mut table = vec![0u64; (1 << block.len()) * w];
+        for mask in 1..(1usize << block.len()) {
+          let low = mask.trailing_zeros() as usize;
+          let prev = mask & (mask - 1);
+          for k in from..w {
+            table[mask * w + k] = table[prev * w + k] ^ m.data[block[low].0 * w + k];
+          }
+        }
+        for i in 0..m.n_rows {
+          if i >= r && i < r + block.len() {
+            continue;
+          }
+          let idx = block
+            .iter()
+            .enumerate()
+            .fold(0usize, |acc, (b, &(_, pc))| acc | ((m.get(i, pc) as usize) << b));
+          if idx != 0 {
+            for k in from..w {
+              m.data[i * w + k] ^= table[idx * w + k];
+            }
+          }
+        }
+      }
+      r += block.len();
+      pivots.extend(block.iter().map(|(_, pc)| *pc));
+      col = col_end;
+    }
+    (m, pivots)
+  }
+
+  /// Computes the reduced row echelon form.
+  pub fn rref(&self) -> Gf2Matrix {
+    self.rref_with_pivots().0
+  }
+
+  /// Computes the rank, i.e. the number of linearly independent rows.
+  pub fn rank(&self) -> usize {
+    self.rref_with_pivots().1.len()
+  }
+
+  /// Solves `A x = b` for every column of `b` and returns a particular solution,
+  /// or `None` if the system is inconsistent. Free variables are set to zero.
+  pub fn solve(&self, b: &Gf2Matrix) -> Option<Gf2Matrix> {
+    if b.n_rows != self.n_rows {
+      panic!("dimensions do not match!");
+    }
+    let (n, k) = (self.n_cols, b.n_cols);
+    let mut augmented = Gf2Matrix::zeros(self.n_rows, n + k);
+    for i in 0..self.n_rows {
+      for j in 0..n + k {
+        let v = if j < n { self.get(i, j) } else { b.get(i, j - n) };
+        augmented.set(i, j, v);
+      }
+    }
+    let (r, pivots) = augmented.rref_with_pivots();
+    if pivots.last().is_some_and(|p| *p >= n) {
+      return None;
+    }
+    let mut x = Gf2Matrix::zeros(n, k);
+    for (row, p) in pivots.iter().enumerate() {
+      for j in 0..k {
+        x.set(*p, j, r.get(row, n + j));
+      }
+    }
+    Some(x)
+  }
+
+  /// Computes a basis of the null space `{x | A x = 0}`. The basis vectors are
+  /// returned as the columns of a matrix having `n_cols` rows.
+  pub fn null_space(&self) -> Gf2Matrix {
+    let (r, pivots) = self.rref_with_pivots();
+    let free = (0..self.n_cols)
+      .filter(|j| !pivots.contains(j))
+      .collect::<Vec<usize>>();
+    let mut basis = Gf2Matrix::zeros(self.n_cols, free.len());
+    for (k, f) in free.iter().enumerate() {
+      basis.set(*f, k, true);
+      for (row, p) in pivots.iter().enumerate() {
+        basis.set(*p, k, r.get(row, *f));
+      }
+    }
+    basis
+  }
+
+  /// Converts into a generic matrix over `Zp<2>`.
+  pub fn to_matrix(&self) -> Matrix<Zp<2>> {
+    let mut data = vec![];
+    for i in 0..self.n_rows {
+      for j in 0..self.n_cols {
+        data.push(if self.get(i, j) { Zp::one() } else { Zp::zero() });
+      }
+    }
+    Matrix::create_from_data(data, self.n_rows, self.n_cols)
+  }
+}
+
+impl From<&Matrix<Zp<2>>> for Gf2Matrix {
+  fn from(m: &Matrix<Zp<2>>) -> Gf2Matrix {
+    let mut res = Gf2Matrix::zeros(m.n_rows, m.n_cols);
+    for (e, row, col) in m.iter() {
+      res.set(row, col, !e.is_zero());
+    }
+    res
+  }
+}
+
+macro_rules! gf2_add {
+  ($LHS:ty, $RHS:ty) => {
+    impl Add<$RHS> for $LHS {
+      type Output = Gf2Matrix;
+      // Addition over GF(2) is XOR.
+      #[allow(clippy::suspicious_arithmetic_impl)]
+      fn add(self, rhs: $RHS) -> Self::Output {
+        if self.n_rows != rhs.n_rows || self.n_cols != rhs.n_cols {
+          panic!("dimensions do not match!");
+        }
+        let mut res = self.clone();
+        for (a, b) in res.data.iter_mut().zip(rhs.data.iter()) {
+          *a ^= *b;
+        }
+        return res;
+      }
+    }
+  };
+}
+gf2_add!(&Gf2Matrix, &Gf2Matrix);
+gf2_add!(Gf2Matrix, Gf2Matrix);
+gf2_add!(&Gf2Matrix, Gf2Matrix);
+gf2_add!(Gf2Matrix, &Gf2Matrix);
+
+macro_rules! gf2_mult {
+  ($LHS:ty, $RHS:ty) => {
+    impl Mul<$RHS> for $LHS {
+      type Output = Gf2Matrix;
+      fn mul(self, rhs: $RHS) -> Self::Output {
+        if rhs.n_rows != self.n_cols {
+          panic!("dimensions do not match!");
+        }
+        let t = rhs.trans();
+        let mut res = Gf2Matrix::zeros(self.n_rows, rhs.n_cols);
+        for i in 0..self.n_rows {
+          for j in 0..rhs.n_cols {
+            let ones: u32 = self
+              .row(i)
+              .iter()
+              .zip(t.row(j).iter())
+              .map(|(a, b)| (a & b).count_ones())
+              .sum();
+            res.set(i, j, ones % 2 == 1);
+          }
+        }
+        return res;
+      }
+    }
+  };
+}
+gf2_mult!(&Gf2Matrix, &Gf2Matrix);
+gf2_mult!(Gf2Matrix, Gf2Matrix);
+gf2_mult!(&Gf2Matrix, Gf2Matrix);
+gf2_mult!(Gf2Matrix, &Gf2Matrix);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // A deterministic pseudo-random matrix.
+  fn random(n_rows: usize, n_cols: usize, seed: u64) -> Gf2Matrix {
+    let mut state = seed;
+    let mut m = Gf2Matrix::zeros(n_rows, n_cols);
+    for i in 0..n_rows {
+      for j in 0..n_cols {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        m.set(i, j, state >> 63 == 1);
+      }
+    }
+    m
+  }
+
+  #[test]
+  fn test_get_set() {
+    let mut m = Gf2Matrix::zeros(2, 130);
+    m.set(1, 129, true);
+    m.set(0, 64, true);
+    assert!(m.get(1, 129) && m.get(0, 64) && !m.get(0, 0));
+    m.set(1, 129, false);
+    assert!(!m.get(1, 129));
+  }
+
+  #[test]
+  fn test_add_mul() {
+    let a = Gf2Matrix::new(vec![vec![1, 0], vec![1, 1]]);
+    assert_eq!(&a + &a, Gf2Matrix::zeros(2, 2));
+    assert_eq!(&a * &a, Gf2Matrix::new(vec![vec![1, 0], vec![0, 1]]));
+  }
+
+  #[test]
+  fn test_mul_matches_generic() {
+    let a = random(7, 100, 1);
+    let b = random(100, 9, 2);
+    assert_eq!((&a * &b).to_matrix(), a.to_matrix() * b.to_matrix());
+  }
+
+  #[test]
+  fn test_rref_matches_generic() {
+    for seed in 0..5 {
+      let m = random(20, 150, seed);
+      let (r, pivots) = m.rref_with_pivots();
+      let (expected, expected_pivots) = m.to_matrix().rref_with_pivots();
+      assert_eq!(r.to_matrix(), expected);
+      assert_eq!(pivots, expected_pivots);
+    }
+  }
+
+  #[test]
+  fn test_rank_and_null_space() {
+    let a = random(70, 30, 3);
+    let m = &a * &a.trans();
+    assert!(m.rank() <= 30);
+    assert_eq!(m.rank(), m.to_matrix().rank());
+    let n = m.null_space();
+    assert_eq!(n.n_cols(), 70 - m.rank());
+    assert_eq!(&m * &n, Gf2Matrix::zeros(70, n.n_cols()));
+  }
+
+  #[test]
+  fn test_solve() {
+    let a = random(40, 40, 4);
+    let x = random(40, 2, 5);
+    let b = &a * &x;
+    let y = a.solve(&b).unwrap();
+    assert_eq!(&a * &y, b);
+    let singular = Gf2Matrix::new(vec![vec![1, 1], vec![1, 1]]);
+    assert_eq!(singular.solve(&Gf2Matrix::new(vec![vec![1], vec![0]])), None);
+  }
+
+  #[test]
+  fn test_conversion() {
+    let a = random(3, 5, 6);
+    assert_eq!(Gf2Matrix::from(&a.to_matrix()), a);
+  }
+}
@@ -1,14 +1,14 @@
-use super::Matrix;
+use super::{num::Semiring, Matrix};
 use core::ops::Add;
 
 macro_rules! matrix_add {
   ($LHS:ty, $RHS:ty, $ScalarType:tt ) => {
-    impl<$ScalarType: Add<Output = $ScalarType> + Copy + PartialEq> Add<$RHS> for $LHS {
+    impl<$ScalarType: Semiring> Add<$RHS> for $LHS {
       type Output = Matrix<$ScalarType>;
       fn add(self, rhs: $RHS) -> Self::Output {
         let mut res: Vec<$ScalarType> = vec![];
         for (e, row, col) in self.iter() {
-          res.push(*e + rhs.get(row, col));
+          res.push(e.clone() + rhs.get(row, col));
         }
         return Matrix::create_from_data(res, self.n_rows, self.n_cols);
       }
@@ -0,0 +1,48 @@
+use super::num::RealField;
+use super::Matrix;
+
+impl<T: RealField> Matrix<T> {
+  /// Computes the Cholesky decomposition `A = L L^T` of a symmetric positive
+  /// definite matrix and returns the lower triangular `L`, or `None` if the
+  /// matrix is not positive definite. Only the lower triangle of `A` is read.
+  pub fn cholesky(&self) -> Option<Matrix<T>> {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let n = self.n_rows;
+    let mut l = Matrix::zeros(n, n);
+    for j in 0..n {
+      let mut d = self.data[j * n + j];
+      for k in 0..j {
+        d = d - l.data[j * n + k] * l.data[j * n + k];
+      }
+      // Written so that a NaN also fails the test.
+      let d = if d > T::zero() { d.sqrt() } else { return None };
+      l.data[j * n + j] = d;
+      for i in j + 1..n {
+        let mut s = self.data[i * n + j];
+        for k in 0..j {
+          s = s - l.data[i * n + k] * l.data[j * n + k];
+        }
+        l.data[i * n + j] = s / d;
+      }
+    }
+    Some(l)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_cholesky() {
+    let a = Matrix::new(vec![vec![4.0, 2.0, -2.0], vec![2.0, 10.0, 2.0], vec![-2.0, 2.0, 6.0]]);
+    let l = a.cholesky().unwrap();
+    assert_eq!(l.get_row(1), Matrix::new(vec![vec![1.0, 3.0, 0.0]]));
+    assert_eq!(l.get_row(2), Matrix::new(vec![vec![-1.0, 1.0, 2.0]]));
+    assert_eq!(&l * &l.trans(), a);
+    let indefinite = Matrix::new(vec![vec![1.0, 2.0], vec![2.0, 1.0]]);
+    assert_eq!(indefinite.cholesky(), None);
+  }
+}
@@ -0,0 +1,184 @@
+use super::Matrix;
+use std::fmt;
+
+/// Formats a matrix with its shape and scalar type as a header, followed by one
+/// line per row with right-aligned columns. The precision, width, fill and
+/// alignment of the format specifier apply to every entry, and `{:e}` and `{:E}`
+/// format the entries in scientific notation.
+///
+/// Large matrices are truncated: if a matrix has more entries than the
+/// threshold, only the first and last `edge_items` rows and columns are shown,
+/// separated by ellipses.
+#[derive(Debug, Clone, Copy)]
+pub struct MatrixDisplay<'a, T: Clone + PartialEq> {
+  matrix: &'a Matrix<T>,
+  edge_items: usize,
+  threshold: usize,
+}
+
+impl<'a, T: Clone + PartialEq> MatrixDisplay<'a, T> {
+  /// Sets the number of rows and columns shown at each end of a truncated matrix.
+  /// The default is 3.
+  pub fn edge_items(mut self, edge_items: usize) -> MatrixDisplay<'a, T> {
+    self.edge_items = edge_items;
+    self
+  }
+
+  /// Sets the number of entries above which a matrix is truncated. The default is
+  /// 1000, and `usize::MAX` disables truncation.
+  pub fn threshold(mut self, threshold: usize) -> MatrixDisplay<'a, T> {
+    self.threshold = threshold;
+    self
+  }
+
+  // The indices shown along an axis of length `n`, with `None` for the ellipsis.
+  fn visible(&self, n: usize) -> Vec<Option<usize>> {
+    let m = self.matrix;
+    if m.n_rows * m.n_cols > self.threshold && n > 2 * self.edge_items {
+      let head = (0..self.edge_items).map(Some);
+      let tail = (n - self.edge_items..n).map(Some);
+      head.chain(std::iter::once(None)).chain(tail).collect()
+    } else {
+      (0..n).map(Some).collect()
+    }
+  }
+
+  fn write<F>(&self, f: &mut fmt::Formatter, format_entry: F) -> fmt::Result
+  where
+    F: Fn(&T, Option<usize>) -> String,
+  {
+    let m = self.matrix;
+    write!(f, "{} x {} Matrix<{}>", m.n_rows, m.n_cols, short_type_name::<T>())?;
+    let (rows, cols) = (self.visible(m.n_rows), self.visible(m.n_cols));
+    let cells = rows
+      .iter()
+      .map(|i| {
+        cols
+          .iter()
+          .map(|j| match (i, j) {
+            (Some(i), Some(j)) => format_entry(&m.data[i * m.n_cols + j], f.precision()),
+            _ => String::from("..."),
+          })
+          .collect::<Vec<String>>()
+      })
+      .collect::<Vec<Vec<String>>>();
+    let widths = (0..cols.len())
+      .map(|k| {
+        let widest = cells.iter().map(|row| row[k].chars().count()).max().unwrap_or(0);
+        widest.max(f.width().unwrap_or(0))
+      })
+      .collect::<Vec<usize>>();
+    for row in &cells {
+      writeln!(f)?;
+      for (cell, &width) in row.iter().zip(&widths) {
+        let padding = width - cell.chars().count();
+        let (left, right) = match f.align() {
+          Some(fmt::Alignment::Left) => (0, padding),
+          Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+          _ => (padding, 0),
+        };
+        let fill = |n: usize| std::iter::repeat_n(f.fill(), n).collect::<String>();
+        write!(f, "  {}{}{}", fill(left), cell, fill(right))?;
+      }
+    }
+    Ok(())
+  }
+}
+
+// Strips the module paths from a type name, such that
+// `matrix_algebra::matrix::rational::Rational<i64>` becomes `Rational<i64>`.
+fn short_type_name<T>() -> String {
+  let name = std::any::type_name::<T>();
+  let mut short = String::new();
+  let mut segment = String::new();
+  for c in name.chars() {
+    if c.is_alphanumeric() || c == '_' {
+      segment.push(c);
+    } else if c == ':' {
+      segment.clear();
+    } else {
+      short.push_str(&segment);
+      segment.clear();
+      short.push(c);
+    }
+  }
+  short + &segment
+}
+
+impl<T: Clone + PartialEq> Matrix<T> {
+  /// Returns a value which formats the matrix with configurable truncation.
+  pub fn display(&self) -> MatrixDisplay<'_, T> {
+    MatrixDisplay {
+      matrix: self,
+      edge_items: 3,
+      threshold: 1000,
+    }
+  }
+}
+
+macro_rules! display_impl {
+  ($($Trait:ident, $spec:literal, $spec_precision:literal);*) => {
+    $(
+      impl<'a, T: Clone + PartialEq + fmt::$Trait> fmt::$Trait for MatrixDisplay<'a, T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+          self.write(f, |e, precision| match precision {
+            Some(p) => format!($spec_precision, e, p = p),
+            None => format!($spec, e),
+          })
+        }
+      }
+
+      impl<T: Clone + PartialEq + fmt::$Trait> fmt::$Trait for Matrix<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+          fmt::$Trait::fmt(&self.display(), f)
+        }
+      }
+    )*
+  };
+}
+display_impl!(Display, "{}", "{:.p$}"; LowerExp, "{:e}", "{:.p$e}"; UpperExp, "{:E}", "{:.p$E}");
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Rational64;
+
+  #[test]
+  fn test_display() {
+    let m = Matrix::new(vec![vec![1.0, -2.5, 300.0], vec![4.25, 5.0, -6.0]]);
+    assert_eq!(format!("{}", m), "2 x 3 Matrix<f64>\n     1  -2.5  300\n  4.25     5   -6");
+    assert_eq!(
+      format!("{:.2}", m),
+      "2 x 3 Matrix<f64>\n  1.00  -2.50  300.00\n  4.25   5.00   -6.00"
+    );
+    assert_eq!(format!("{:<4}", m), "2 x 3 Matrix<f64>\n  1     -2.5  300 \n  4.25  5     -6  ");
+    assert_eq!(format!("{:*^5.0}", m.get_row(0)), "1 x 3 Matrix<f64>\n  **1**  *-2**  *300*");
+    let r = Matrix::new(vec![vec![Rational64::new(1, 2), Rational64::new(-3, 4)]]);
+    assert_eq!(format!("{}", r), "1 x 2 Matrix<Rational<i64>>\n  1/2  -3/4");
+    let empty: Matrix<u8> = Matrix::zeros(0, 3);
+    assert_eq!(format!("{}", empty), "0 x 3 Matrix<u8>");
+  }
+
+  #[test]
+  fn test_exponent() {
+    let m = Matrix::new(vec![vec![1234.5, 0.001]]);
+    assert_eq!(format!("{:e}", m), "1 x 2 Matrix<f64>\n  1.2345e3  1e-3");
+    assert_eq!(format!("{:.1E}", m), "1 x 2 Matrix<f64>\n  1.2E3  1.0E-3");
+  }
+
+  #[test]
+  fn test_truncation() {
+    let m = Matrix::create_from_data((0..2000).collect::<Vec<i32>>(), 40, 50);
+    let text = format!("{}", m);
+    let lines = text.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 8);
+    assert_eq!(lines[0], "40 x 50 Matrix<i32>");
+    assert_eq!(lines[1], "     0     1     2  ...    47    48    49");
+    assert_eq!(lines[4], "   ...   ...   ...  ...   ...   ...   ...");
+    assert_eq!(lines[7], "  1950  1951  1952  ...  1997  1998  1999");
+    let text = format!("{}", m.display().edge_items(1));
+    let expected = "40 x 50 Matrix<i32>\n     0  ...    49\n   ...  ...   ...\n  1950  ...  1999";
+    assert_eq!(text, expected);
+    assert_eq!(format!("{}", m.display().threshold(usize::MAX)).lines().count(), 41);
+  }
+}
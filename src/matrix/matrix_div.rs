@@ -3,12 +3,12 @@ use core::ops::Div;
 
 macro_rules! matrix_div {
   ($LHS:ty, $RHS:ty, $ScalarType:tt ) => {
-    impl<$ScalarType: Div<Output = $ScalarType> + Copy + PartialEq> Div<$RHS> for $LHS {
+    impl<$ScalarType: Div<Output = $ScalarType> + Clone + PartialEq> Div<$RHS> for $LHS {
       type Output = Matrix<$ScalarType>;
       fn div(self, rhs: $RHS) -> Self::Output {
         let mut res: Vec<$ScalarType> = vec![];
         for (e, row, col) in self.iter() {
-          res.push(*e / rhs.get(row, col));
+          res.push(e.clone() / rhs.get(row, col));
         }
         return Matrix::create_from_data(res, self.n_rows, self.n_cols);
       }
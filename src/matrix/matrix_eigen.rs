@@ -0,0 +1,120 @@
+use super::num::RealField;
+use super::Matrix;
+
+const MAX_SWEEPS: usize = 100;
+
+impl<T: RealField> Matrix<T> {
+  /// Computes the eigenvalues and eigenvectors of a symmetric matrix by the cyclic
+  /// Jacobi method. The eigenvalues are returned in ascending order, the eigenvectors
+  /// are the corresponding columns of the orthogonal matrix. Only the upper triangle
+  /// of `self` is read.
+  pub fn symmetric_eigen(&self) -> (Vec<T>, Matrix<T>) {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let n = self.n_rows;
+    let mut a = self.clone();
+    for i in 0..n {
+      for j in 0..i {
+        a.data[i * n + j] = a.data[j * n + i];
+      }
+    }
+    let mut v = Matrix::identity(n);
+    let two = T::one() + T::one();
+    for _ in 0..MAX_SWEEPS {
+      let mut off = T::zero();
+      let mut total = T::zero();
+      for i in 0..n {
+        for j in 0..n {
+          let x = a.data[i * n + j] * a.data[i * n + j];
+          total = total + x;
+          if i != j {
+            off = off + x;
+          }
+        }
+      }
+      if off <= T::epsilon() * T::epsilon() * total {
+        break;
+      }
+      for p in 0..n {
+        for q in p + 1..n {
+          let apq = a.data[p * n + q];
+          if apq.is_zero() {
+            continue;
+          }
+          let theta = (a.data[q * n + q] - a.data[p * n + p]) / (two * apq);
+          let t = T::one() / (theta.abs() + (theta * theta + T::one()).sqrt());
+          let t = if theta < T::zero() { -t } else { t };
+          let c = T::one() / (t * t + T::one()).sqrt();
+          let s = t * c;
+          for k in 0..n {
+            let (akp, akq) = (a.data[k * n + p], a.data[k * n + q]);
+            a.data[k * n + p] = c * akp - s * akq;
+            a.data[k * n + q] = s * akp + c * akq;
+          }
+          for k in 0..n {
+            let (apk, aqk) = (a.data[p * n + k], a.data[q * n + k]);
+            a.data[p * n + k] = c * apk - s * aqk;
+            a.data[q * n + k] = s * apk + c * aqk;
+          }
+          for k in 0..n {
+            let (vkp, vkq) = (v.data[k * n + p], v.data[k * n + q]);
+            v.data[k * n + p] = c * vkp - s * vkq;
+            v.data[k * n + q] = s * vkp + c * vkq;
+          }
+        }
+      }
+    }
+    let mut order = (0..n).collect::<Vec<usize>>();
+    // NaN eigenvalues are not comparable and are placed last.
+    let is_nan = |x: T| x.partial_cmp(&x).is_none();
+    order.sort_by(|&i, &j| {
+      let (x, y) = (a.data[i * n + i], a.data[j * n + j]);
+      x.partial_cmp(&y).unwrap_or_else(|| is_nan(x).cmp(&is_nan(y)))
+    });
+    let values = order.iter().map(|&i| a.data[i * n + i]).collect();
+    let mut vectors = Matrix::zeros(n, n);
+    for (col, &i) in order.iter().enumerate() {
+      for k in 0..n {
+        vectors.data[k * n + col] = v.data[k * n + i];
+      }
+    }
+    (values, vectors)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>) {
+    for ((x, _, _), (y, _, _)) in a.iter().zip(b.iter()) {
+      assert!((x - y).abs() < 1e-10, "{:?} != {:?}", a, b);
+    }
+  }
+
+  #[test]
+  fn test_symmetric_eigen() {
+    let a = Matrix::new(vec![vec![2.0, -1.0, 0.0], vec![-1.0, 2.0, -1.0], vec![0.0, -1.0, 2.0]]);
+    let (values, v) = a.symmetric_eigen();
+    let s = 2f64.sqrt();
+    let expected = [2.0 - s, 2.0, 2.0 + s];
+    for (x, y) in values.iter().zip(expected.iter()) {
+      assert!((x - y).abs() < 1e-12);
+    }
+    assert_close(&(&v.trans() * &v), &Matrix::identity(3));
+    let mut d = Matrix::zeros(3, 3);
+    for (i, &value) in values.iter().enumerate() {
+      d.data[i * 3 + i] = value;
+    }
+    assert_close(&(&(&v * &d) * &v.trans()), &a);
+  }
+
+  #[test]
+  fn test_symmetric_eigen_nan() {
+    let a = Matrix::new(vec![vec![f64::NAN, 0.0, 0.0], vec![0.0, 3.0, 0.0], vec![0.0, 0.0, 1.0]]);
+    let (values, _) = a.symmetric_eigen();
+    assert_eq!(values[..2], [1.0, 3.0]);
+    assert!(values[2].is_nan());
+  }
+}
@@ -0,0 +1,267 @@
+use super::num::{Field, Ring};
+use super::Matrix;
+use core::ops::Div;
+
+// Pivots are chosen as the first non-zero entry of a column. This keeps the results
+// exact for exact scalar types like `Rational`, but gives no numerical stability
+// guarantees for floating point types.
+impl<T: Field> Matrix<T> {
+  /// Computes the reduced row echelon form together with the indices of the pivot columns.
+  pub fn rref_with_pivots(&self) -> (Matrix<T>, Vec<usize>) {
+    let mut m = self.clone();
+    let n_cols = m.n_cols;
+    let mut pivots = vec![];
+    let mut row = 0;
+    for col in 0..n_cols {
+      if row == m.n_rows {
+        break;
+      }
+      let pivot_row = match (row..m.n_rows).find(|i| m.data[i * n_cols + col].is_pivot()) {
+        Some(i) => i,
+        None => continue,
+      };
+      m.swap_rows(row, pivot_row);
+      let p = m.data[row * n_cols + col].clone();
+      for j in col..n_cols {
+        m.data[row * n_cols + j] = m.data[row * n_cols + j].clone() / p.clone();
+      }
+      for i in 0..m.n_rows {
+        let f = m.data[i * n_cols + col].clone();
+        if i == row || f.is_zero() {
+          continue;
+        }
+        for j in col..n_cols {
+          m.data[i * n_cols + j] =
+            m.data[i * n_cols + j].clone() - f.clone() * m.data[row * n_cols + j].clone();
+        }
+      }
+      pivots.push(col);
+      row += 1;
+    }
+    (m, pivots)
+  }
+
+  /// Computes the reduced row echelon form.
+  pub fn rref(&self) -> Matrix<T> {
+    self.rref_with_pivots().0
+  }
+
+  /// Computes the rank, i.e. the number of linearly independent rows.
+  pub fn rank(&self) -> usize {
+    self.rref_with_pivots().1.len()
+  }
+
+  /// Computes the inverse of a square matrix, or `None` if the matrix is singular.
+  pub fn inverse(&self) -> Option<Matrix<T>> {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let n = self.n_rows;
+    let mut augmented = vec![];
+    for i in 0..n {
+      augmented.extend_from_slice(&self.data[i * n..(i + 1) * n]);
+      augmented.extend((0..n).map(|j| if i == j { T::one() } else { T::zero() }));
+    }
+    let (r, pivots) = Matrix::create_from_data(augmented, n, 2 * n).rref_with_pivots();
+    if pivots.len() < n || pivots[n - 1] != n - 1 {
+      return None;
+    }
+    let mut data = vec![];
+    for i in 0..n {
+      data.extend_from_slice(&r.data[i * 2 * n + n..(i + 1) * 2 * n]);
+    }
+    Some(Matrix::create_from_data(data, n, n))
+  }
+
+  /// Solves `A x = b` for every column of `b` and returns a particular solution,
+  /// or `None` if the system is inconsistent. Free variables are set to zero.
+  pub fn solve(&self, b: &Matrix<T>) -> Option<Matrix<T>> {
+    if b.n_rows != self.n_rows {
+      panic!("dimensions do not match!");
+    }
+    let (n, k) = (self.n_cols, b.n_cols);
+    let mut augmented = vec![];
+    for i in 0..self.n_rows {
+      augmented.extend_from_slice(&self.data[i * n..(i + 1) * n]);
+      augmented.extend_from_slice(&b.data[i * k..(i + 1) * k]);
+    }
+    let (r, pivots) = Matrix::create_from_data(augmented, self.n_rows, n + k).rref_with_pivots();
+    if pivots.last().is_some_and(|p| *p >= n) {
+      return None;
+    }
+    let mut x = Matrix::zeros(n, k);
+    for (row, p) in pivots.iter().enumerate() {
+      for j in 0..k {
+        x.data[p * k + j] = r.data[row * (n + k) + n + j].clone();
+      }
+    }
+    Some(x)
+  }
+
+  /// Computes a basis of the null space `{x | A x = 0}`. The basis vectors are
+  /// returned as the columns of a matrix having `n_cols` rows.
+  pub fn null_space(&self) -> Matrix<T> {
+    let (r, pivots) = self.rref_with_pivots();
+    let n = self.n_cols;
+    let free = (0..n).filter(|j| !pivots.contains(j)).collect::<Vec<usize>>();
+    let mut basis = Matrix::zeros(n, free.len());
+    for (k, f) in free.iter().enumerate() {
+      basis.data[f * free.len() + k] = T::one();
+      for (row, p) in pivots.iter().enumerate() {
+        basis.data[p * free.len() + k] = -r.data[row * n + f].clone();
+      }
+    }
+    basis
+  }
+}
+
+impl<T: Ring + Div<Output = T>> Matrix<T> {
+  /// Computes the determinant with the fraction-free Bareiss algorithm.
+  /// All divisions are exact, so this works for integer matrices too.
+  pub fn det(&self) -> T {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let n = self.n_rows;
+    if n == 0 {
+      return T::one();
+    }
+    let mut m = self.clone();
+    let mut negate = false;
+    let mut prev = T::one();
+    for k in 0..n - 1 {
+      if !m.data[k * n + k].is_pivot() {
+        // Non-zero entries which are no pivots, as for dual numbers, are only
+        // used if the column has no pivot.
+        let pivot = (k + 1..n).find(|i| m.data[i * n + k].is_pivot());
+        match pivot.or_else(|| (k..n).find(|i| !m.data[i * n + k].is_zero())) {
+          Some(i) if i != k => {
+            m.swap_rows(k, i);
+            negate = !negate;
+          }
+          Some(_) => {}
+          None => return T::zero(),
+        }
+      }
+      let pivot = m.data[k * n + k].clone();
+      for i in k + 1..n {
+        for j in k + 1..n {
+          m.data[i * n + j] = (m.data[i * n + j].clone() * pivot.clone()
+            - m.data[i * n + k].clone() * m.data[k * n + j].clone())
+            / prev.clone();
+        }
+      }
+      prev = pivot;
+    }
+    let d = m.data[n * n - 1].clone();
+    if negate {
+      -d
+    } else {
+      d
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::big_int::BigInt;
+  use super::super::rational::{BigRational, Rational64};
+  use super::*;
+
+  fn r(n: i64, d: i64) -> Rational64 {
+    Rational64::new(n, d)
+  }
+
+  fn hilbert(n: usize) -> Matrix<Rational64> {
+    let data = (0..n * n)
+      .map(|k| r(1, (k / n + k % n + 1) as i64))
+      .collect();
+    Matrix::create_from_data(data, n, n)
+  }
+
+  #[test]
+  fn test_rref() {
+    let m = Matrix::new(vec![
+      vec![r(1, 1), r(2, 1), r(3, 1)],
+      vec![r(2, 1), r(4, 1), r(7, 1)],
+    ]);
+    assert_eq!(
+      m.rref_with_pivots(),
+      (
+        Matrix::new(vec![
+          vec![r(1, 1), r(2, 1), r(0, 1)],
+          vec![r(0, 1), r(0, 1), r(1, 1)]
+        ]),
+        vec![0, 2]
+      )
+    );
+  }
+
+  #[test]
+  fn test_rank() {
+    let m = Matrix::new(vec![
+      vec![r(1, 1), r(2, 1)],
+      vec![r(2, 1), r(4, 1)],
+      vec![r(0, 1), r(1, 1)],
+    ]);
+    assert_eq!(m.rank(), 2);
+    assert_eq!(Matrix::<Rational64>::zeros(2, 3).rank(), 0);
+  }
+
+  #[test]
+  fn test_inverse_hilbert() {
+    let h = hilbert(5);
+    let inv = h.inverse().unwrap();
+    assert_eq!(&h * &inv, Matrix::identity(5));
+    assert_eq!(inv.get(0, 0), r(25, 1));
+  }
+
+  #[test]
+  fn test_inverse_singular() {
+    let m = Matrix::new(vec![vec![r(1, 1), r(2, 1)], vec![r(2, 1), r(4, 1)]]);
+    assert_eq!(m.inverse(), None);
+  }
+
+  #[test]
+  fn test_solve() {
+    let a = Matrix::new(vec![vec![r(2, 1), r(1, 1)], vec![r(1, 1), r(3, 1)]]);
+    let b = Matrix::new(vec![vec![r(1, 1)], vec![r(2, 1)]]);
+    let x = a.solve(&b).unwrap();
+    assert_eq!(x, Matrix::new(vec![vec![r(1, 5)], vec![r(3, 5)]]));
+    let singular = Matrix::new(vec![vec![r(1, 1), r(1, 1)], vec![r(1, 1), r(1, 1)]]);
+    assert_eq!(singular.solve(&b), None);
+  }
+
+  #[test]
+  fn test_null_space() {
+    let m = Matrix::new(vec![
+      vec![r(1, 1), r(2, 1), r(3, 1), r(4, 1)],
+      vec![r(2, 1), r(4, 1), r(6, 1), r(8, 1)],
+      vec![r(1, 1), r(0, 1), r(1, 1), r(0, 1)],
+    ]);
+    let n = m.null_space();
+    assert_eq!((n.n_rows(), n.n_cols()), (4, 2));
+    assert_eq!(&m * &n, Matrix::zeros(3, 2));
+  }
+
+  #[test]
+  fn test_det_integer() {
+    let m = Matrix::new(vec![vec![0, 2, 1], vec![3, 1, 4], vec![5, 9, 2]]);
+    assert_eq!(m.det(), 50);
+    assert_eq!(Matrix::new(vec![vec![1, 2], vec![2, 4]]).det(), 0);
+  }
+
+  #[test]
+  fn test_det_exact() {
+    assert_eq!(hilbert(4).det(), r(1, 6048000));
+    let big = |n: i64| BigRational::from_integer(BigInt::from(n));
+    let m = Matrix::new(vec![
+      vec![big(1_000_000_000_000), big(1)],
+      vec![big(1), big(1_000_000_000_000)],
+    ]);
+    assert_eq!(
+      m.det(),
+      BigRational::from_integer("999999999999999999999999".parse().unwrap())
+    );
+  }
+}
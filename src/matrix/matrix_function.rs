@@ -0,0 +1,282 @@
+use super::func::Func;
+use super::num::Real;
+use super::Matrix;
+
+const MAX_ITERATIONS: usize = 100;
+
+// The coefficients of the [13/13] Padé approximant of the exponential and the
+// largest 1-norm for which it is accurate to double precision (Higham, 2005).
+const PADE_13: [f64; 14] = [
+  64764752532480000.0,
+  32382376266240000.0,
+  7771770303897600.0,
+  1187353796428800.0,
+  129060195264000.0,
+  10559470521600.0,
+  670442572800.0,
+  33522128640.0,
+  1323241920.0,
+  40840800.0,
+  960960.0,
+  16380.0,
+  182.0,
+  1.0,
+];
+const THETA_13: f64 = 5.371920351148152;
+
+impl<T: Real> Matrix<T> {
+  // Panics unless the matrix is square, and returns its dimension.
+  pub(crate) fn check_square(&self) -> usize {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    self.n_rows
+  }
+
+  // The maximum absolute column sum.
+  pub(crate) fn norm_1(&self) -> T {
+    (0..self.n_cols)
+      .map(|j| {
+        (0..self.n_rows).fold(T::zero(), |acc, i| acc + self.data[i * self.n_cols + j].abs())
+      })
+      .fold(T::zero(), |acc, s| if s > acc { s } else { acc })
+  }
+
+  pub(crate) fn lu_inverse(&self) -> Option<Matrix<T>> {
+    self.lu_solve(&Matrix::identity(self.n_rows))
+  }
+
+  /// Computes the matrix exponential by scaling and squaring with a [13/13] Padé
+  /// approximant. If an entry is infinite or NaN, all entries of the result are
+  /// NaN.
+  pub fn expm(&self) -> Matrix<T> {
+    self.check_square();
+    let n = self.n_rows;
+    let mut s = 0;
+    let mut norm = self.norm_1().to_f64();
+    if !norm.is_finite() {
+      return self.map(|_| T::from_f64(f64::NAN));
+    }
+    while norm > THETA_13 {
+      norm /= 2.0;
+      s += 1;
+    }
+    let a = self * T::from_f64(0.5f64.powi(s));
+    let b = |k: usize| T::from_f64(PADE_13[k]);
+    let id = Matrix::identity(n);
+    let a2 = &a * &a;
+    let a4 = &a2 * &a2;
+    let a6 = &a4 * &a2;
+    let u_inner = &(&(&a6 * b(13)) + &(&a4 * b(11))) + &(&a2 * b(9));
+    let u_outer = &(&(&a6 * b(7)) + &(&a4 * b(5))) + &(&(&a2 * b(3)) + &(&id * b(1)));
+    let u = &a * &(&(&a6 * &u_inner) + &u_outer);
+    let v_inner = &(&(&a6 * b(12)) + &(&a4 * b(10))) + &(&a2 * b(8));
+    let v_outer = &(&(&a6 * b(6)) + &(&a4 * b(4))) + &(&(&a2 * b(2)) + &(&id * b(0)));
+    let v = &(&a6 * &v_inner) + &v_outer;
+    let mut r = (&v - &u).lu_solve(&(&v + &u)).expect("Padé denominator is singular!");
+    for _ in 0..s {
+      r = &r * &r;
+    }
+    r
+  }
+
+  /// Computes the principal square root by the Denman–Beavers iteration. Returns
+  /// `None` if the iteration does not converge, e.g. if the matrix is singular or
+  /// has eigenvalues on the negative real axis.
+  pub fn sqrtm(&self) -> Option<Matrix<T>> {
+    self.check_square();
+    let n = self.n_rows;
+    let half = T::from_f64(0.5);
+    let tol = T::epsilon() * T::from_f64(n as f64 * 10.0);
+    let mut y = self.clone();
+    let mut z = Matrix::identity(n);
+    for _ in 0..MAX_ITERATIONS {
+      let y_inv = y.lu_inverse()?;
+      let z_inv = z.lu_inverse()?;
+      let y_next = &(&y + &z_inv) * half;
+      z = &(&z + &y_inv) * half;
+      let change = (&y_next - &y).norm_1();
+      y = y_next;
+      if change <= tol * y.norm_1() {
+        return Some(y);
+      }
+    }
+    None
+  }
+
+  /// Computes the principal logarithm by inverse scaling and squaring: square roots
+  /// are taken until the matrix is close to the identity, where the series of
+  /// `log(I + X)` converges fast. Returns `None` if a square root does not exist.
+  pub fn logm(&self) -> Option<Matrix<T>> {
+    self.check_square();
+    let n = self.n_rows;
+    let id = Matrix::identity(n);
+    let mut x = self.clone();
+    let mut k = 0;
+    while (&x - &id).norm_1() > T::from_f64(0.25) {
+      x = x.sqrtm()?;
+      k += 1;
+      if k > 64 {
+        return None;
+      }
+    }
+    let y = &x - &id;
+    let mut log = y.clone();
+    let mut power = y.clone();
+    for j in 2..MAX_ITERATIONS {
+      power = &power * &y;
+      let term = &power * T::from_f64(if j % 2 == 0 { -1.0 } else { 1.0 } / j as f64);
+      log = &log + &term;
+      if term.norm_1() <= T::epsilon() * log.norm_1() {
+        break;
+      }
+    }
+    Some(log * T::from_f64(2f64.powi(k)))
+  }
+
+  /// Computes the real power `A^p`. Integer powers are computed by repeated
+  /// multiplication and, if negative, inversion. Other powers are computed as
+  /// `expm(p * logm(A))`. Returns `None` if the inverse or logarithm does not exist.
+  pub fn powm(&self, p: T) -> Option<Matrix<T>> {
+    self.check_square();
+    let k = p.to_f64();
+    if k == k.round() && k.abs() < i32::MAX as f64 {
+      let m = self.pow(k.abs() as u32);
+      return if k < 0.0 { m.lu_inverse() } else { Some(m) };
+    }
+    Some((self.logm()? * p).expm())
+  }
+
+  /// Applies `f` to the eigenvalues of the matrix, i.e. computes `V f(D) V^-1` for
+  /// the eigendecomposition `A = V D V^-1`. Symmetric matrices are handled by the
+  /// Jacobi eigenvalue method; other matrices need real and distinct eigenvalues
+  /// and are handled by the Schur–Parlett recurrence. Returns `None` otherwise.
+  pub fn apply_spectral(&self, f: &Func<T>) -> Option<Matrix<T>> {
+    self.check_square();
+    let n = self.n_rows;
+    if *self == self.trans() {
+      let (values, v) = self.symmetric_eigen();
+      let mut d = Matrix::zeros(n, n);
+      for (i, &value) in values.iter().enumerate() {
+        d.data[i * n + i] = f.0(value);
+      }
+      return Some(&(&v * &d) * &v.trans());
+    }
+    let (q, s) = self.real_schur()?;
+    if (1..n).any(|i| !s.data[i * n + i - 1].is_zero()) {
+      return None;
+    }
+    let mut fs = Matrix::zeros(n, n);
+    for i in 0..n {
+      fs.data[i * n + i] = f.0(s.data[i * n + i]);
+    }
+    let scale = s.norm_1();
+    for p in 1..n {
+      for i in 0..n - p {
+        let j = i + p;
+        let gap = s.data[j * n + j] - s.data[i * n + i];
+        if gap.abs() <= T::epsilon() * scale {
+          return None;
+        }
+        let mut sum = s.data[i * n + j] * (fs.data[j * n + j] - fs.data[i * n + i]);
+        for k in i + 1..j {
+          sum = sum + s.data[i * n + k] * fs.data[k * n + j];
+          sum = sum - fs.data[i * n + k] * s.data[k * n + j];
+        }
+        fs.data[i * n + j] = sum / gap;
+      }
+    }
+    Some(&(&q * &fs) * &q.trans())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>, tol: f64) {
+    let scale = 1.0 + b.norm_1();
+    assert!((a - b).norm_1() < tol * scale, "{:?} != {:?}", a, b);
+  }
+
+  fn sample() -> Matrix<f64> {
+    Matrix::new(vec![vec![1.0, 0.5, 0.0], vec![0.2, 2.0, -0.3], vec![0.1, 0.0, 3.0]])
+  }
+
+  #[test]
+  fn test_expm() {
+    let t = 0.7f64;
+    let a = Matrix::new(vec![vec![0.0, t], vec![-t, 0.0]]);
+    let rotation = Matrix::new(vec![vec![t.cos(), t.sin()], vec![-t.sin(), t.cos()]]);
+    assert_close(&a.expm(), &rotation, 1e-15);
+    let nilpotent = Matrix::new(vec![vec![0.0, 1.0], vec![0.0, 0.0]]);
+    assert_close(&nilpotent.expm(), &Matrix::new(vec![vec![1.0, 1.0], vec![0.0, 1.0]]), 1e-15);
+    let big = &sample() * 4.0;
+    assert_close(&(&big.expm() * &big.expm()), &(&big * 2.0).expm(), 1e-13);
+    let diagonal = Matrix::new(vec![vec![20.0, 0.0], vec![0.0, -3.0]]);
+    let expected = Matrix::new(vec![vec![20f64.exp(), 0.0], vec![0.0, (-3f64).exp()]]);
+    assert_close(&diagonal.expm(), &expected, 1e-13);
+    for e in [f64::INFINITY, f64::NAN] {
+      let m = Matrix::new(vec![vec![1.0, e], vec![0.0, 1.0]]);
+      assert!(m.expm().iter().all(|(x, _, _)| x.is_nan()));
+    }
+  }
+
+  #[test]
+  fn test_markov_generator() {
+    let q = Matrix::new(vec![vec![-0.5, 0.5, 0.0], vec![0.25, -0.75, 0.5], vec![0.0, 1.0, -1.0]]);
+    let p = (&q * 2.0).expm();
+    for i in 0..3 {
+      let row_sum = (0..3).map(|j| p.get(i, j)).sum::<f64>();
+      assert!((row_sum - 1.0).abs() < 1e-14);
+    }
+  }
+
+  #[test]
+  fn test_sqrtm() {
+    let a = Matrix::new(vec![vec![4.0, 1.0], vec![0.0, 9.0]]);
+    let r = a.sqrtm().unwrap();
+    assert_close(&r, &Matrix::new(vec![vec![2.0, 0.2], vec![0.0, 3.0]]), 1e-14);
+    let s = sample().sqrtm().unwrap();
+    assert_close(&(&s * &s), &sample(), 1e-14);
+    let singular = Matrix::new(vec![vec![0.0, 0.0], vec![0.0, 1.0]]);
+    assert_eq!(singular.sqrtm(), None);
+  }
+
+  #[test]
+  fn test_logm() {
+    let a = sample();
+    assert_close(&a.logm().unwrap().expm(), &a, 1e-13);
+    let small = &sample() * 0.1;
+    assert_close(&small.expm().logm().unwrap(), &small, 1e-13);
+    let e = Matrix::new(vec![vec![1f64.exp(), 0.0], vec![0.0, 2f64.exp()]]);
+    assert_close(&e.logm().unwrap(), &Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 2.0]]), 1e-13);
+  }
+
+  #[test]
+  fn test_powm() {
+    let a = sample();
+    assert_close(&a.powm(3.0).unwrap(), &a.pow(3), 1e-15);
+    assert_close(&(&a.powm(-1.0).unwrap() * &a), &Matrix::identity(3), 1e-14);
+    assert_close(&a.powm(0.5).unwrap(), &a.sqrtm().unwrap(), 1e-12);
+    let third = a.powm(1.0 / 3.0).unwrap();
+    assert_close(&(&(&third * &third) * &third), &a, 1e-12);
+  }
+
+  #[test]
+  fn test_apply_spectral() {
+    let exp = Func::new(f64::exp);
+    let symmetric = Matrix::new(vec![
+      vec![2.0, -1.0, 0.0],
+      vec![-1.0, 2.0, -1.0],
+      vec![0.0, -1.0, 2.0],
+    ]);
+    assert_close(&symmetric.apply_spectral(&exp).unwrap(), &symmetric.expm(), 1e-13);
+    let a = sample();
+    assert_close(&a.apply_spectral(&exp).unwrap(), &a.expm(), 1e-12);
+    let sqrt = Func::new(f64::sqrt);
+    assert_close(&a.apply_spectral(&sqrt).unwrap(), &a.sqrtm().unwrap(), 1e-12);
+    let rotation = Matrix::new(vec![vec![0.0, 1.0], vec![-1.0, 0.0]]);
+    assert!(rotation.apply_spectral(&exp).is_none());
+  }
+}
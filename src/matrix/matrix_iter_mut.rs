@@ -1,4 +1,4 @@
-pub struct MatrixIteratorMut<'a, T: Copy> {
+pub struct MatrixIteratorMut<'a, T> {
   row_idx: usize,
   col_idx: usize,
   data: &'a mut [T],
@@ -6,7 +6,7 @@ pub struct MatrixIteratorMut<'a, T: Copy> {
   n_cols: usize,
 }
 
-impl<'a, T: Copy> MatrixIteratorMut<'a, T> {
+impl<'a, T> MatrixIteratorMut<'a, T> {
   pub fn new(
     row_idx: usize,
     col_idx: usize,
@@ -24,25 +24,25 @@ impl<'a, T: Copy> MatrixIteratorMut<'a, T> {
   }
 }
 
-impl<'a, T: Copy> std::iter::Iterator for MatrixIteratorMut<'a, T> {
+impl<'a, T> std::iter::Iterator for MatrixIteratorMut<'a, T> {
   type Item = (&'a mut T, usize, usize);
   fn next(&mut self) -> Option<Self::Item> {
     if self.col_idx == self.n_cols {
       self.row_idx += 1;
       self.col_idx = 0;
     }
-    return if self.row_idx == self.n_rows {
+    if self.row_idx == self.n_rows {
       None
     } else {
       let col_idx = self.col_idx;
       self.col_idx += 1;
-      let data = std::mem::replace(&mut self.data, &mut []);
+      let data = std::mem::take(&mut self.data);
       if let Some((v, rest)) = data.split_first_mut() {
         self.data = rest;
         Some((v, self.row_idx, col_idx))
       } else {
         None
       }
-    };
+    }
   }
 }
@@ -1,4 +1,4 @@
-pub struct MatrixIterator<'a, T: Copy> {
+pub struct MatrixIterator<'a, T> {
   row_idx: usize,
   col_idx: usize,
   idx: usize,
@@ -7,7 +7,7 @@ pub struct MatrixIterator<'a, T: Copy> {
   n_cols: usize,
 }
 
-impl<'a, T: Copy> MatrixIterator<'a, T> {
+impl<'a, T> MatrixIterator<'a, T> {
   pub fn new(
     row_idx: usize,
     col_idx: usize,
@@ -27,14 +27,14 @@ impl<'a, T: Copy> MatrixIterator<'a, T> {
   }
 }
 
-impl<'a, T: Copy> std::iter::Iterator for MatrixIterator<'a, T> {
+impl<'a, T> std::iter::Iterator for MatrixIterator<'a, T> {
   type Item = (&'a T, usize, usize);
   fn next(&mut self) -> Option<Self::Item> {
     if self.col_idx == self.n_cols {
       self.row_idx += 1;
       self.col_idx = 0;
     }
-    return if self.row_idx == self.n_rows {
+    if self.row_idx == self.n_rows {
       None
     } else {
       let col_idx = self.col_idx;
@@ -42,6 +42,6 @@ impl<'a, T: Copy> std::iter::Iterator for MatrixIterator<'a, T> {
       self.col_idx += 1;
       self.idx += 1;
       Some((&self.data[idx], self.row_idx, col_idx))
-    };
+    }
   }
 }
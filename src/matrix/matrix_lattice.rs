@@ -0,0 +1,471 @@
+use super::num::{Field, Integer, One, Zero};
+use super::rational::Rational;
+use super::Matrix;
+
+impl<T: Field> Matrix<T> {
+  /// Gram–Schmidt orthogonalization of the rows. Returns the orthogonalized rows `B*`
+  /// together with the unit lower triangular matrix `mu` of projection coefficients,
+  /// such that `self = mu * B*`. Linearly dependent rows yield zero rows in `B*`.
+  pub fn gram_schmidt(&self) -> (Matrix<T>, Matrix<T>) {
+    let (m, n) = (self.n_rows, self.n_cols);
+    let mut ortho: Vec<Vec<T>> = vec![];
+    let mut norms: Vec<T> = vec![];
+    let mut mu = Matrix::identity(m);
+    for i in 0..m {
+      let mut v = self.data[i * n..(i + 1) * n].to_vec();
+      for j in 0..i {
+        if norms[j].is_zero() {
+          continue;
+        }
+        let c = dot(&self.data[i * n..(i + 1) * n], &ortho[j]) / norms[j].clone();
+        for (vk, ok) in v.iter_mut().zip(ortho[j].iter()) {
+          *vk = vk.clone() - c.clone() * ok.clone();
+        }
+        mu.data[i * m + j] = c;
+      }
+      norms.push(dot(&v, &v));
+      ortho.push(v);
+    }
+    let data = ortho.into_iter().flatten().collect();
+    (Matrix::create_from_data(data, m, n), mu)
+  }
+}
+
+fn dot<T: Field>(a: &[T], b: &[T]) -> T {
+  a.iter()
+    .zip(b.iter())
+    .fold(T::zero(), |acc, (x, y)| acc + x.clone() * y.clone())
+}
+
+fn from_i64<I: Integer>(v: i64) -> I {
+  let mut res = I::zero();
+  let mut power = I::one();
+  let mut abs = v.unsigned_abs();
+  while abs > 0 {
+    if abs & 1 == 1 {
+      res = res + power.clone();
+    }
+    power = power.clone() + power;
+    abs >>= 1;
+  }
+  if v < 0 {
+    -res
+  } else {
+    res
+  }
+}
+
+// A lattice basis given by rows, the accumulated unimodular transformation and
+// the Gram–Schmidt data `mu` and `norms` (squared lengths of the orthogonalized rows).
+// The dimension `d` of the ambient space is kept for empty bases.
+struct Lattice<I> {
+  d: usize,
+  b: Vec<Vec<Rational<I>>>,
+  u: Vec<Vec<I>>,
+  mu: Vec<Vec<Rational<I>>>,
+  norms: Vec<Rational<I>>,
+}
+
+impl<I: Integer> Lattice<I> {
+  fn new(m: &Matrix<Rational<I>>) -> Lattice<I> {
+    let n = m.n_rows;
+    let b = (0..n)
+      .map(|i| m.data[i * m.n_cols..(i + 1) * m.n_cols].to_vec())
+      .collect();
+    let u = (0..n)
+      .map(|i| (0..n).map(|j| if i == j { I::one() } else { I::zero() }).collect())
+      .collect();
+    let mut lattice = Lattice {
+      d: m.n_cols,
+      b,
+      u,
+      mu: vec![],
+      norms: vec![],
+    };
+    lattice.orthogonalize();
+    lattice
+  }
+
+  fn orthogonalize(&mut self) {
+    let rows = self.b.iter().flatten().cloned().collect();
+    let basis = Matrix::create_from_data(rows, self.b.len(), self.d);
+    let (ortho, mu) = basis.gram_schmidt();
+    let (n, d) = (ortho.n_rows, ortho.n_cols);
+    self.norms = (0..n)
+      .map(|i| dot(&ortho.data[i * d..(i + 1) * d], &ortho.data[i * d..(i + 1) * d]))
+      .collect();
+    if self.norms.iter().any(|b| b.is_zero()) {
+      panic!("basis vectors must be linearly independent!");
+    }
+    self.mu = (0..n).map(|i| mu.data[i * n..(i + 1) * n].to_vec()).collect();
+  }
+
+  // Subtracts `round(mu[k][j])` times the row `j` from the row `k` if `|mu[k][j]| > 1/2`.
+  fn size_reduce(&mut self, k: usize, j: usize) {
+    let twice = self.mu[k][j].clone() + self.mu[k][j].clone();
+    if twice <= Rational::one() && -twice <= Rational::one() {
+      return;
+    }
+    let q = self.mu[k][j].round();
+    let qr = Rational::from_integer(q.clone());
+    for c in 0..self.b[k].len() {
+      self.b[k][c] = self.b[k][c].clone() - qr.clone() * self.b[j][c].clone();
+    }
+    for c in 0..self.u[k].len() {
+      self.u[k][c] = self.u[k][c].clone() - q.clone() * self.u[j][c].clone();
+    }
+    for l in 0..j {
+      self.mu[k][l] = self.mu[k][l].clone() - qr.clone() * self.mu[j][l].clone();
+    }
+    self.mu[k][j] = self.mu[k][j].clone() - qr;
+  }
+
+  // Swaps the rows `k - 1` and `k` and updates the Gram–Schmidt data.
+  fn swap(&mut self, k: usize) {
+    self.b.swap(k - 1, k);
+    self.u.swap(k - 1, k);
+    let m = self.mu[k][k - 1].clone();
+    let norm = self.norms[k].clone() + m.clone() * m.clone() * self.norms[k - 1].clone();
+    self.mu[k][k - 1] = m.clone() * self.norms[k - 1].clone() / norm.clone();
+    self.norms[k] = self.norms[k - 1].clone() * self.norms[k].clone() / norm.clone();
+    self.norms[k - 1] = norm;
+    for j in 0..k - 1 {
+      let t = self.mu[k - 1][j].clone();
+      self.mu[k - 1][j] = self.mu[k][j].clone();
+      self.mu[k][j] = t;
+    }
+    for i in k + 1..self.b.len() {
+      let t = self.mu[i][k].clone();
+      self.mu[i][k] = self.mu[i][k - 1].clone() - m.clone() * t.clone();
+      self.mu[i][k - 1] = t + self.mu[k][k - 1].clone() * self.mu[i][k].clone();
+    }
+  }
+
+  fn lll(&mut self, delta: &Rational<I>) {
+    let mut k = 1;
+    while k < self.b.len() {
+      for j in (0..k).rev() {
+        self.size_reduce(k, j);
+      }
+      let m = self.mu[k][k - 1].clone();
+      let bound = (delta.clone() - m.clone() * m) * self.norms[k - 1].clone();
+      if self.norms[k] >= bound {
+        k += 1;
+      } else {
+        self.swap(k);
+        k = usize::max(k - 1, 1);
+      }
+    }
+  }
+
+  // Squared length of the projection of `sum x_i b_{k+i}` orthogonally to `b_0..b_{k-1}`.
+  fn projected_norm(&self, k: usize, x: &[I]) -> Rational<I> {
+    let m = x.len();
+    let mut res = Rational::zero();
+    for i in 0..m {
+      let mut c = Rational::from_integer(x[i].clone());
+      for (j, xj) in x.iter().enumerate().skip(i + 1) {
+        c = c + Rational::from_integer(xj.clone()) * self.mu[k + j][k + i].clone();
+      }
+      res = res + c.clone() * c * self.norms[k + i].clone();
+    }
+    res
+  }
+
+  // Replaces the rows `k..k + x.len()` by a unimodular transformation of them whose
+  // first row is `sum x_i b_{k+i}`.
+  fn insert(&mut self, k: usize, x: &[I]) {
+    let m = x.len();
+    let column = Matrix::create_from_data(x.to_vec(), m, 1);
+    let (_, u) = column.hermite_normal_form();
+    let u_rational = Matrix::create_from_data(
+      u.data.iter().cloned().map(Rational::from_integer).collect(),
+      m,
+      m,
+    );
+    // `u * x = e_1`, so the first column of the inverse of `u` is `x`.
+    let w = u_rational.inverse().unwrap().trans();
+    let old_b = self.b[k..k + m].to_vec();
+    let old_u = self.u[k..k + m].to_vec();
+    for i in 0..m {
+      let coeffs = (0..m).map(|j| w.get(i, j).numer().clone()).collect::<Vec<I>>();
+      self.b[k + i] = (0..old_b[0].len())
+        .map(|c| {
+          (0..m).fold(Rational::zero(), |acc, j| {
+            acc + Rational::from_integer(coeffs[j].clone()) * old_b[j][c].clone()
+          })
+        })
+        .collect();
+      self.u[k + i] = (0..old_u[0].len())
+        .map(|c| (0..m).fold(I::zero(), |acc, j| acc + coeffs[j].clone() * old_u[j][c].clone()))
+        .collect();
+    }
+    self.orthogonalize();
+  }
+
+  fn bkz(&mut self, block_size: usize, delta: &Rational<I>) {
+    self.lll(delta);
+    let n = self.b.len();
+    if n < 2 || block_size < 2 {
+      return;
+    }
+    let mut unchanged = 0;
+    let mut k = 0;
+    while unchanged < n - 1 {
+      let end = usize::min(k + block_size, n);
+      let mu = (k..end)
+        .map(|i| (k..end).map(|j| self.mu[i][j].to_f64()).collect())
+        .collect::<Vec<Vec<f64>>>();
+      let norms = (k..end).map(|i| self.norms[i].to_f64()).collect::<Vec<f64>>();
+      let candidate = shortest_vector(&mu, &norms, delta.to_f64() * norms[0]).map(|x| {
+        let g = x.iter().fold(0, |acc, v| gcd(acc, *v));
+        x.iter().map(|v| from_i64::<I>(v / g)).collect::<Vec<I>>()
+      });
+      match candidate {
+        Some(x) if self.projected_norm(k, &x) < delta.clone() * self.norms[k].clone() => {
+          self.insert(k, &x);
+          self.lll(delta);
+          unchanged = 0;
+        }
+        _ => unchanged += 1,
+      }
+      k = (k + 1) % (n - 1);
+    }
+  }
+
+  fn into_matrices(self) -> (Matrix<Rational<I>>, Matrix<I>) {
+    let (n, d) = (self.b.len(), self.d);
+    (
+      Matrix::create_from_data(self.b.into_iter().flatten().collect(), n, d),
+      Matrix::create_from_data(self.u.into_iter().flatten().collect(), n, n),
+    )
+  }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+  if b == 0 {
+    a.abs()
+  } else {
+    gcd(b, a % b)
+  }
+}
+
+// Schnorr–Euchner enumeration of the shortest non-zero integer combination of a
+// block given by its projection coefficients `mu` and squared norms `b`. Only
+// combinations with squared length below `radius` are considered.
+fn shortest_vector(mu: &[Vec<f64>], b: &[f64], radius: f64) -> Option<Vec<i64>> {
+  let m = b.len();
+  let mut radius = radius;
+  let mut best = None;
+  let mut x = vec![0i64; m];
+  let mut center = vec![0f64; m];
+  let mut partial = vec![0f64; m + 1];
+  let mut dx = vec![1i64; m];
+  let mut ddx = vec![1i64; m];
+  let mut k = m - 1;
+  loop {
+    let d = x[k] as f64 - center[k];
+    let l = partial[k + 1] + d * d * b[k];
+    let mut step = true;
+    if l < radius {
+      if k == 0 {
+        if x.iter().any(|v| *v != 0) {
+          radius = l;
+          best = Some(x.clone());
+        }
+      } else {
+        partial[k] = l;
+        k -= 1;
+        center[k] = -(k + 1..m).map(|j| x[j] as f64 * mu[j][k]).sum::<f64>();
+        x[k] = center[k].round() as i64;
+        let s = if center[k] >= x[k] as f64 { 1 } else { -1 };
+        dx[k] = s;
+        ddx[k] = s;
+        step = false;
+      }
+    } else {
+      k += 1;
+      if k == m {
+        return best;
+      }
+    }
+    if step {
+      if partial[k + 1] == 0.0 {
+        x[k] += 1;
+      } else {
+        x[k] += dx[k];
+        ddx[k] = -ddx[k];
+        dx[k] = ddx[k] - dx[k];
+      }
+    }
+  }
+}
+
+fn check_delta<I: Integer>(delta: &Rational<I>) {
+  let quarter = Rational::new(I::one(), from_i64(4));
+  if *delta <= quarter || *delta > Rational::one() {
+    panic!("delta must lie in (1/4, 1]!");
+  }
+}
+
+impl<I: Integer> Matrix<Rational<I>> {
+  /// Reduces the lattice basis given by the rows with the Lenstra–Lenstra–Lovász
+  /// algorithm, using exact arithmetic. Returns the reduced basis together with the
+  /// unimodular integer matrix `U` such that `U * self` equals the reduced basis.
+  /// The rows must be linearly independent and `delta` must lie in `(1/4, 1]`. An
+  /// empty basis is returned unchanged.
+  pub fn lll_reduce(&self, delta: Rational<I>) -> (Matrix<Rational<I>>, Matrix<I>) {
+    check_delta(&delta);
+    let mut lattice = Lattice::new(self);
+    lattice.lll(&delta);
+    lattice.into_matrices()
+  }
+
+  /// Reduces the lattice basis given by the rows with the block Korkine–Zolotarev
+  /// algorithm of Schnorr and Euchner. Each block of `block_size` consecutive rows is
+  /// searched for a shortest vector by enumeration. A block size of two gives an LLL
+  /// reduced basis, larger blocks give shorter vectors at higher cost.
+  pub fn bkz_reduce(
+    &self,
+    block_size: usize,
+    delta: Rational<I>,
+  ) -> (Matrix<Rational<I>>, Matrix<I>) {
+    check_delta(&delta);
+    let mut lattice = Lattice::new(self);
+    lattice.bkz(block_size, &delta);
+    lattice.into_matrices()
+  }
+}
+
+impl<I: Integer> Matrix<I> {
+  fn to_rational(&self) -> Matrix<Rational<I>> {
+    let data = self.data.iter().cloned().map(Rational::from_integer).collect();
+    Matrix::create_from_data(data, self.n_rows, self.n_cols)
+  }
+
+  fn from_rational(m: Matrix<Rational<I>>) -> Matrix<I> {
+    let data = m.data.iter().map(|e| e.numer().clone()).collect();
+    Matrix::create_from_data(data, m.n_rows, m.n_cols)
+  }
+
+  /// Like the LLL reduction of rational bases, for a basis of integer rows.
+  pub fn lll_reduce(&self, delta: Rational<I>) -> (Matrix<I>, Matrix<I>) {
+    let (b, u) = self.to_rational().lll_reduce(delta);
+    (Matrix::from_rational(b), u)
+  }
+
+  /// Like the BKZ reduction of rational bases, for a basis of integer rows.
+  pub fn bkz_reduce(&self, block_size: usize, delta: Rational<I>) -> (Matrix<I>, Matrix<I>) {
+    let (b, u) = self.to_rational().bkz_reduce(block_size, delta);
+    (Matrix::from_rational(b), u)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::big_int::BigInt;
+  use super::super::rational::{BigRational, Rational64};
+  use super::*;
+
+  fn norm(m: &Matrix<BigInt>, i: usize) -> BigInt {
+    let row = m.get_row(i);
+    (&row * &row.trans()).get(0, 0)
+  }
+
+  fn is_lll_reduced(m: &Matrix<BigInt>, delta: &BigRational) -> bool {
+    let (ortho, mu) = m.to_rational().gram_schmidt();
+    let norms = (0..m.n_rows())
+      .map(|i| (&ortho.get_row(i) * &ortho.get_row(i).trans()).get(0, 0))
+      .collect::<Vec<BigRational>>();
+    let half = BigRational::new(BigInt::from(1), BigInt::from(2));
+    let size_reduced = (0..m.n_rows())
+      .all(|i| (0..i).all(|j| mu.get(i, j) <= half && -mu.get(i, j) <= half));
+    let lovasz = (1..m.n_rows()).all(|k| {
+      let c = mu.get(k, k - 1);
+      norms[k] >= (delta.clone() - c.clone() * c) * norms[k - 1].clone()
+    });
+    size_reduced && lovasz
+  }
+
+  fn big(v: Vec<Vec<i64>>) -> Matrix<BigInt> {
+    let m = Matrix::new(v);
+    let data = m.iter().map(|(e, ..)| BigInt::from(*e)).collect();
+    Matrix::create_from_data(data, m.n_rows(), m.n_cols())
+  }
+
+  fn three_quarters() -> BigRational {
+    BigRational::new(BigInt::from(3), BigInt::from(4))
+  }
+
+  #[test]
+  fn test_gram_schmidt() {
+    let m = Matrix::new(vec![vec![3.0f64, 1.0], vec![2.0, 2.0]]);
+    let (ortho, mu) = m.gram_schmidt();
+    assert_eq!(&mu * &ortho, m);
+    let r0 = ortho.get_row(0);
+    let r1 = ortho.get_row(1);
+    assert!((&r0 * &r1.trans()).get(0, 0).abs() < 1e-12);
+    assert_eq!(mu.get(1, 0), 0.8);
+  }
+
+  #[test]
+  fn test_lll_reduce() {
+    let b = Matrix::new(vec![vec![1, 1, 1], vec![-1, 0, 2], vec![3, 5, 6]]);
+    let (reduced, u) = b.lll_reduce(Rational64::new(3, 4));
+    assert_eq!(&u * &b, reduced);
+    assert_eq!(u.det().abs(), 1);
+    assert_eq!(
+      reduced,
+      Matrix::new(vec![vec![0, 1, 0], vec![1, 0, 1], vec![-1, 0, 2]])
+    );
+  }
+
+  #[test]
+  fn test_empty_basis() {
+    let b: Matrix<i64> = Matrix::zeros(0, 3);
+    let (reduced, u) = b.lll_reduce(Rational64::new(3, 4));
+    assert_eq!((reduced.n_rows(), reduced.n_cols()), (0, 3));
+    assert_eq!((u.n_rows(), u.n_cols()), (0, 0));
+    let (reduced, _) = b.bkz_reduce(2, Rational64::new(3, 4));
+    assert_eq!((reduced.n_rows(), reduced.n_cols()), (0, 3));
+  }
+
+  #[test]
+  fn test_lll_reduce_rational_basis() {
+    let r = |n, d| Rational64::new(n, d);
+    let b = Matrix::new(vec![vec![r(1, 2), r(1, 3)], vec![r(3, 2), r(2, 3)]]);
+    let (reduced, u) = b.lll_reduce(r(3, 4));
+    assert_eq!(&u.to_rational() * &b, reduced);
+    assert_eq!(u.det().abs(), 1);
+  }
+
+  #[test]
+  fn test_integer_relation() {
+    // Finds small integers a, b, c with 1 a + 3 b + 5 c = 0.
+    let n = 1000;
+    let b = big(vec![vec![1, 0, 0, n], vec![0, 1, 0, 3 * n], vec![0, 0, 1, 5 * n]]);
+    let (reduced, _) = b.lll_reduce(three_quarters());
+    assert_eq!(reduced.get(0, 3), BigInt::from(0));
+    assert!(norm(&reduced, 0) <= BigInt::from(6));
+    assert!(is_lll_reduced(&reduced, &three_quarters()));
+  }
+
+  #[test]
+  fn test_bkz_reduce() {
+    let b = big(vec![
+      vec![1, 0, 0, 0, 0, 0, 3451],
+      vec![0, 1, 0, 0, 0, 0, 2877],
+      vec![0, 0, 1, 0, 0, 0, 9023],
+      vec![0, 0, 0, 1, 0, 0, 1234],
+      vec![0, 0, 0, 0, 1, 0, 7777],
+      vec![0, 0, 0, 0, 0, 1, 4321],
+    ]);
+    let delta = BigRational::new(BigInt::from(99), BigInt::from(100));
+    let (lll, _) = b.lll_reduce(delta.clone());
+    let (bkz, u) = b.bkz_reduce(6, delta.clone());
+    assert_eq!(&u * &b, bkz);
+    assert!(u.det() == BigInt::from(1) || u.det() == BigInt::from(-1));
+    assert!(is_lll_reduced(&bkz, &delta));
+    assert!(norm(&bkz, 0) <= norm(&lll, 0));
+  }
+}
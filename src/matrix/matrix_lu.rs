@@ -0,0 +1,104 @@
+use super::num::RealField;
+use super::Matrix;
+
+// Pivots are chosen by largest absolute value (partial pivoting), which is the
+// numerically stable choice for floating point types.
+impl<T: RealField> Matrix<T> {
+  /// Computes the LU decomposition with partial pivoting of a square matrix.
+  /// Returns `(L, U, p)` with `L` unit lower triangular, `U` upper triangular and
+  /// the row permutation `p` such that row `i` of `L * U` is row `p[i]` of `self`.
+  pub fn lu(&self) -> (Matrix<T>, Matrix<T>, Vec<usize>) {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let n = self.n_rows;
+    let mut u = self.clone();
+    let mut l = Matrix::identity(n);
+    let mut perm = (0..n).collect::<Vec<usize>>();
+    for k in 0..n {
+      let mut p = k;
+      for i in k + 1..n {
+        if u.data[i * n + k].abs() > u.data[p * n + k].abs() {
+          p = i;
+        }
+      }
+      if p != k {
+        u.swap_rows(p, k);
+        perm.swap(p, k);
+        for j in 0..k {
+          l.data.swap(p * n + j, k * n + j);
+        }
+      }
+      let pivot = u.data[k * n + k];
+      if !pivot.is_pivot() {
+        continue;
+      }
+      for i in k + 1..n {
+        let f = u.data[i * n + k] / pivot;
+        l.data[i * n + k] = f;
+        u.data[i * n + k] = T::zero();
+        for j in k + 1..n {
+          u.data[i * n + j] = u.data[i * n + j] - f * u.data[k * n + j];
+        }
+      }
+    }
+    (l, u, perm)
+  }
+
+  /// Solves `A X = B` by LU decomposition with partial pivoting, or returns `None`
+  /// if the matrix is singular.
+  pub fn lu_solve(&self, b: &Matrix<T>) -> Option<Matrix<T>> {
+    if b.n_rows != self.n_rows {
+      panic!("dimensions do not match!");
+    }
+    let (l, u, perm) = self.lu();
+    let (n, m) = (self.n_rows, b.n_cols);
+    if (0..n).any(|i| !u.data[i * n + i].is_pivot()) {
+      return None;
+    }
+    let mut x = b.select_rows(&perm);
+    for c in 0..m {
+      for i in 0..n {
+        let mut s = x.data[i * m + c];
+        for k in 0..i {
+          s = s - l.data[i * n + k] * x.data[k * m + c];
+        }
+        x.data[i * m + c] = s;
+      }
+      for i in (0..n).rev() {
+        let mut s = x.data[i * m + c];
+        for k in i + 1..n {
+          s = s - u.data[i * n + k] * x.data[k * m + c];
+        }
+        x.data[i * m + c] = s / u.data[i * n + i];
+      }
+    }
+    Some(x)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_lu() {
+    let a = Matrix::new(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0], vec![7.0, 8.0, 10.0]]);
+    let (l, u, perm) = a.lu();
+    assert_eq!(perm[0], 2);
+    let diff = &(&l * &u) - &a.select_rows(&perm);
+    assert!(diff.iter().all(|(e, _, _)| e.abs() < 1e-12));
+    assert!((0..3).all(|i| (i + 1..3).all(|j| l.get(i, j) == 0.0 && u.get(j, i) == 0.0)));
+  }
+
+  #[test]
+  fn test_lu_solve() {
+    // The first pivot is tiny, which requires pivoting to stay accurate.
+    let a = Matrix::new(vec![vec![1e-20, 1.0], vec![1.0, 1.0]]);
+    let b = Matrix::new(vec![vec![1.0], vec![2.0]]);
+    let x = a.lu_solve(&b).unwrap();
+    assert!((x.get(0, 0) - 1.0).abs() < 1e-12 && (x.get(1, 0) - 1.0).abs() < 1e-12);
+    let singular = Matrix::new(vec![vec![1.0, 2.0], vec![2.0, 4.0]]);
+    assert_eq!(singular.lu_solve(&b), None);
+  }
+}
@@ -1,13 +1,9 @@
-use super::Matrix;
-use core::ops::{Add, Mul};
-use std::iter::Sum;
+use super::{num::Semiring, Matrix};
+use core::ops::Mul;
 
 macro_rules! matrix_mult {
   ($LHS:ty, $RHS:ty, $ScalarType:tt ) => {
-    impl<
-        $ScalarType: Sum + Mul<Output = $ScalarType> + Add<Output = $ScalarType> + Copy + PartialEq,
-      > Mul<$RHS> for $LHS
-    {
+    impl<$ScalarType: Semiring> Mul<$RHS> for $LHS {
       type Output = Matrix<$ScalarType>;
       fn mul(self, rhs: $RHS) -> Self::Output {
         if rhs.n_rows != self.n_cols {
@@ -20,8 +16,9 @@ macro_rules! matrix_mult {
               self.data[i * self.n_cols..(i + 1) * self.n_cols]
                 .iter()
                 .enumerate()
-                .map(|(rhs_row_idx, v)| *v * rhs.data[j + rhs_row_idx * rhs.n_cols])
-                .sum(),
+                .fold($ScalarType::zero(), |acc, (rhs_row_idx, v)| {
+                  acc + v.clone() * rhs.data[j + rhs_row_idx * rhs.n_cols].clone()
+                }),
             );
           }
         }
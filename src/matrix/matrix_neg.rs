@@ -1,14 +1,14 @@
-use super::Matrix;
+use super::{num::Ring, Matrix};
 use core::ops::Neg;
 
 macro_rules! matrix_neg {
   ($RHS:ty, $ScalarType:tt ) => {
-    impl<$ScalarType: Neg<Output = $ScalarType> + Copy + PartialEq> Neg for $RHS {
+    impl<$ScalarType: Ring> Neg for $RHS {
       type Output = Matrix<$ScalarType>;
       fn neg(self) -> Self::Output {
         let mut res: Vec<$ScalarType> = vec![];
         for (e, _, _) in self.iter() {
-          res.push(-*e);
+          res.push(-e.clone());
         }
         return Matrix::create_from_data(res, self.n_rows, self.n_cols);
       }
@@ -29,6 +29,7 @@ mod tests {
   }
 
   #[test]
+  #[allow(double_negations)]
   fn test_neg_2() {
     let m1 = Matrix::new(vec![vec![1, 0], vec![0, 1]]);
     assert_eq!(--&m1, Matrix::create_from_data(vec![1, 0, 0, 1], 2, 2));
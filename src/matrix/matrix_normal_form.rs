@@ -0,0 +1,311 @@
+use super::num::Integer;
+use super::Matrix;
+
+// Returns `(g, s, t)` with `s * a + t * b = g = gcd(a, b)` and `g >= 0`.
+fn extended_gcd<I: Integer>(a: &I, b: &I) -> (I, I, I) {
+  let (mut r0, mut r1) = (a.clone(), b.clone());
+  let (mut s0, mut s1) = (I::one(), I::zero());
+  let (mut t0, mut t1) = (I::zero(), I::one());
+  while !r1.is_zero() {
+    let q = r0.clone() / r1.clone();
+    let r = r0 - q.clone() * r1.clone();
+    r0 = r1;
+    r1 = r;
+    let s = s0 - q.clone() * s1.clone();
+    s0 = s1;
+    s1 = s;
+    let t = t0 - q * t1.clone();
+    t0 = t1;
+    t1 = t;
+  }
+  if r0 < I::zero() {
+    (-r0, -s0, -t0)
+  } else {
+    (r0, s0, t0)
+  }
+}
+
+// Division rounding towards negative infinity, for a positive divisor.
+fn floor_div<I: Integer>(a: &I, b: &I) -> I {
+  let q = a.clone() / b.clone();
+  if (a.clone() % b.clone()) < I::zero() {
+    q - I::one()
+  } else {
+    q
+  }
+}
+
+impl<I: Integer> Matrix<I> {
+  fn at(&self, i: usize, j: usize) -> &I {
+    &self.data[i * self.n_cols + j]
+  }
+
+  // Replaces the rows `i` and `j` by `a * r_i + b * r_j` and `c * r_i + d * r_j`.
+  fn combine_rows(&mut self, i: usize, j: usize, a: &I, b: &I, c: &I, d: &I) {
+    let n = self.n_cols;
+    for k in 0..n {
+      let (x, y) = (self.data[i * n + k].clone(), self.data[j * n + k].clone());
+      self.data[i * n + k] = a.clone() * x.clone() + b.clone() * y.clone();
+      self.data[j * n + k] = c.clone() * x + d.clone() * y;
+    }
+  }
+
+  // Adds `f` times the row `src` to the row `dst`.
+  fn add_row_multiple(&mut self, dst: usize, src: usize, f: &I) {
+    let n = self.n_cols;
+    for k in 0..n {
+      self.data[dst * n + k] =
+        self.data[dst * n + k].clone() + f.clone() * self.data[src * n + k].clone();
+    }
+  }
+
+  // Adds `f` times the column `src` to the column `dst`.
+  fn add_col_multiple(&mut self, dst: usize, src: usize, f: &I) {
+    let n = self.n_cols;
+    for k in 0..self.n_rows {
+      self.data[k * n + dst] =
+        self.data[k * n + dst].clone() + f.clone() * self.data[k * n + src].clone();
+    }
+  }
+
+  fn swap_cols(&mut self, i: usize, j: usize) {
+    for k in 0..self.n_rows {
+      self.data.swap(k * self.n_cols + i, k * self.n_cols + j);
+    }
+  }
+
+  fn negate_row(&mut self, i: usize) {
+    let n = self.n_cols;
+    for k in 0..n {
+      self.data[i * n + k] = -self.data[i * n + k].clone();
+    }
+  }
+
+  /// Computes the row-style Hermite normal form `H` together with a unimodular
+  /// matrix `U` such that `U * A = H`. The matrix `H` is in row echelon form, its
+  /// pivots are positive and the entries above each pivot lie in `0..pivot`.
+  pub fn hermite_normal_form(&self) -> (Matrix<I>, Matrix<I>) {
+    let m = self.n_rows;
+    let mut h = self.clone();
+    let mut u = Matrix::identity(m);
+    let mut row = 0;
+    for col in 0..self.n_cols {
+      if row == m {
+        break;
+      }
+      for i in row + 1..m {
+        if h.at(i, col).is_zero() {
+          continue;
+        }
+        let (a, b) = (h.at(row, col).clone(), h.at(i, col).clone());
+        let (g, s, t) = extended_gcd(&a, &b);
+        let (c, d) = (-(b / g.clone()), a / g);
+        h.combine_rows(row, i, &s, &t, &c, &d);
+        u.combine_rows(row, i, &s, &t, &c, &d);
+      }
+      if h.at(row, col).is_zero() {
+        continue;
+      }
+      if *h.at(row, col) < I::zero() {
+        h.negate_row(row);
+        u.negate_row(row);
+      }
+      let pivot = h.at(row, col).clone();
+      for i in 0..row {
+        let q = -floor_div(h.at(i, col), &pivot);
+        h.add_row_multiple(i, row, &q);
+        u.add_row_multiple(i, row, &q);
+      }
+      row += 1;
+    }
+    (h, u)
+  }
+
+  /// Computes the Smith normal form `D` together with unimodular matrices `U` and `V`
+  /// such that `U * A * V = D`. The matrix `D` is diagonal with non-negative entries,
+  /// each dividing the next one.
+  pub fn smith_normal_form(&self) -> (Matrix<I>, Matrix<I>, Matrix<I>) {
+    let (m, n) = (self.n_rows, self.n_cols);
+    let mut d = self.clone();
+    let mut u = Matrix::identity(m);
+    let mut v = Matrix::identity(n);
+    for t in 0..usize::min(m, n) {
+      loop {
+        let mut best: Option<(usize, usize)> = None;
+        for i in t..m {
+          for j in t..n {
+            let e = d.at(i, j);
+            let smaller = best.is_none_or(|(bi, bj)| abs(e) < abs(d.at(bi, bj)));
+            if !e.is_zero() && smaller {
+              best = Some((i, j));
+            }
+          }
+        }
+        let (bi, bj) = match best {
+          Some(p) => p,
+          None => return (d, u, v),
+        };
+        d.swap_rows(t, bi);
+        u.swap_rows(t, bi);
+        d.swap_cols(t, bj);
+        v.swap_cols(t, bj);
+        let pivot = d.at(t, t).clone();
+        let mut cleared = true;
+        for i in t + 1..m {
+          let q = -(d.at(i, t).clone() / pivot.clone());
+          d.add_row_multiple(i, t, &q);
+          u.add_row_multiple(i, t, &q);
+          cleared = cleared && d.at(i, t).is_zero();
+        }
+        for j in t + 1..n {
+          let q = -(d.at(t, j).clone() / pivot.clone());
+          d.add_col_multiple(j, t, &q);
+          v.add_col_multiple(j, t, &q);
+          cleared = cleared && d.at(t, j).is_zero();
+        }
+        if !cleared {
+          continue;
+        }
+        let not_divisible = (t + 1..m)
+          .find(|&i| (t + 1..n).any(|j| !(d.at(i, j).clone() % pivot.clone()).is_zero()));
+        match not_divisible {
+          Some(i) => {
+            d.add_row_multiple(t, i, &I::one());
+            u.add_row_multiple(t, i, &I::one());
+          }
+          None => break,
+        }
+      }
+      if *d.at(t, t) < I::zero() {
+        d.negate_row(t);
+        u.negate_row(t);
+      }
+    }
+    (d, u, v)
+  }
+
+  /// Finds an integer solution of `A x = b` for every column of `b`, or returns
+  /// `None` if there is none. All integer solutions are obtained by adding
+  /// integer combinations of the columns of `integer_null_space()`.
+  pub fn solve_diophantine(&self, b: &Matrix<I>) -> Option<Matrix<I>> {
+    if b.n_rows != self.n_rows {
+      panic!("dimensions do not match!");
+    }
+    let (d, u, v) = self.smith_normal_form();
+    let c = &u * b;
+    let mut y = Matrix::zeros(self.n_cols, b.n_cols);
+    for i in 0..self.n_rows {
+      for j in 0..b.n_cols {
+        let rhs = c.at(i, j);
+        let pivot = if i < self.n_cols { d.at(i, i).clone() } else { I::zero() };
+        if pivot.is_zero() {
+          if !rhs.is_zero() {
+            return None;
+          }
+        } else if (rhs.clone() % pivot.clone()).is_zero() {
+          y.data[i * b.n_cols + j] = rhs.clone() / pivot;
+        } else {
+          return None;
+        }
+      }
+    }
+    Some(&v * &y)
+  }
+
+  /// Computes a basis of the lattice of integer solutions of `A x = 0`,
+  /// returned as the columns of a matrix having `n_cols` rows.
+  pub fn integer_null_space(&self) -> Matrix<I> {
+    let (d, _, v) = self.smith_normal_form();
+    let rank = (0..usize::min(self.n_rows, self.n_cols))
+      .filter(|&i| !d.at(i, i).is_zero())
+      .count();
+    let k = self.n_cols - rank;
+    let mut data = vec![];
+    for i in 0..self.n_cols {
+      data.extend_from_slice(&v.data[i * self.n_cols + rank..(i + 1) * self.n_cols]);
+    }
+    Matrix::create_from_data(data, self.n_cols, k)
+  }
+}
+
+fn abs<I: Integer>(a: &I) -> I {
+  if *a < I::zero() {
+    -a.clone()
+  } else {
+    a.clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::big_int::BigInt;
+  use super::*;
+
+  fn is_unimodular(u: &Matrix<i64>) -> bool {
+    u.det() == 1 || u.det() == -1
+  }
+
+  #[test]
+  fn test_hermite_normal_form() {
+    let a = Matrix::new(vec![vec![2, 3, 6, 2], vec![5, 6, 1, 6], vec![8, 3, 1, 1]]);
+    let (h, u) = a.hermite_normal_form();
+    assert_eq!(&u * &a, h);
+    assert!(is_unimodular(&u));
+    assert_eq!(
+      h,
+      Matrix::new(vec![vec![1, 0, 50, -11], vec![0, 3, 28, -2], vec![0, 0, 61, -13]])
+    );
+  }
+
+  #[test]
+  fn test_hermite_normal_form_rank_deficient() {
+    let a = Matrix::new(vec![vec![4, 6], vec![6, 9], vec![2, 3]]);
+    let (h, u) = a.hermite_normal_form();
+    assert_eq!(&u * &a, h);
+    assert_eq!(h, Matrix::new(vec![vec![2, 3], vec![0, 0], vec![0, 0]]));
+  }
+
+  #[test]
+  fn test_smith_normal_form() {
+    let a = Matrix::new(vec![vec![2, 4, 4], vec![-6, 6, 12], vec![10, -4, -16]]);
+    let (d, u, v) = a.smith_normal_form();
+    assert_eq!(&(&u * &a) * &v, d);
+    assert!(is_unimodular(&u) && is_unimodular(&v));
+    assert_eq!(d, Matrix::new(vec![vec![2, 0, 0], vec![0, 6, 0], vec![0, 0, 12]]));
+  }
+
+  #[test]
+  fn test_smith_normal_form_big_int() {
+    let b = |n: i64| BigInt::from(n);
+    let a = Matrix::new(vec![vec![b(6), b(4)], vec![b(4), b(6)], vec![b(2), b(2)]]);
+    let (d, u, v) = a.smith_normal_form();
+    assert_eq!(&(&u * &a) * &v, d);
+    assert_eq!(d, Matrix::new(vec![vec![b(2), b(0)], vec![b(0), b(2)], vec![b(0), b(0)]]));
+  }
+
+  #[test]
+  fn test_solve_diophantine() {
+    let a = Matrix::new(vec![vec![6, 10, 15]]);
+    let b = Matrix::new(vec![vec![1]]);
+    let x = a.solve_diophantine(&b).unwrap();
+    assert_eq!(&a * &x, b);
+    let n = a.integer_null_space();
+    assert_eq!((n.n_rows(), n.n_cols()), (3, 2));
+    assert_eq!(&a * &n, Matrix::zeros(1, 2));
+    let even = Matrix::new(vec![vec![2, 4], vec![6, 8]]);
+    assert_eq!(even.solve_diophantine(&Matrix::new(vec![vec![1], vec![0]])), None);
+  }
+
+  #[test]
+  fn test_integer_det() {
+    let b = |n: i64| BigInt::from(n);
+    let a = Matrix::new(vec![
+      vec![b(1 << 40), b(3), b(5)],
+      vec![b(7), b(1 << 40), b(11)],
+      vec![b(13), b(17), b(1 << 40)],
+    ]);
+    let x = b(1 << 40);
+    let expected = x.pow(3) - x * b(11 * 17 + 5 * 13 + 3 * 7) + b(3 * 11 * 13 + 5 * 7 * 17);
+    assert_eq!(a.det(), expected);
+  }
+}
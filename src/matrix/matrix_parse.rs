@@ -0,0 +1,183 @@
+use super::Matrix;
+use std::fmt;
+use std::str::FromStr;
+
+/// An error which can be returned when parsing a matrix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseMatrixError {
+  /// The brackets are unbalanced or misplaced.
+  Syntax,
+  /// The entry at the given row and column is not a valid scalar.
+  Entry { row: usize, col: usize },
+  /// The given row differs in length from the first row.
+  RowLength { row: usize },
+}
+
+impl fmt::Display for ParseMatrixError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ParseMatrixError::Syntax => write!(f, "unbalanced or misplaced brackets"),
+      ParseMatrixError::Entry { row, col } => write!(f, "invalid entry at ({}, {})", row, col),
+      ParseMatrixError::RowLength { row } => {
+        write!(f, "row {} differs in length from the first row", row)
+      }
+    }
+  }
+}
+
+impl std::error::Error for ParseMatrixError {}
+
+// Splits a matrix literal into its rows. Nested brackets delimit the rows in
+// NumPy style, separated by commas with an optional trailing comma or, as NumPy
+// prints them, by line breaks. Otherwise semicolons and line breaks delimit them.
+fn split_rows(s: &str) -> Result<Vec<&str>, ParseMatrixError> {
+  let mut s = s.trim();
+  if let Some(inner) = s.strip_prefix('[') {
+    s = inner.strip_suffix(']').ok_or(ParseMatrixError::Syntax)?.trim();
+    if s.starts_with('[') {
+      let mut rows = vec![];
+      while let Some(rest) = s.strip_prefix('[') {
+        let end = rest.find(']').ok_or(ParseMatrixError::Syntax)?;
+        rows.push(&rest[..end]);
+        let after = &rest[end + 1..];
+        s = after.trim_start();
+        if let Some(next) = s.strip_prefix(',') {
+          s = next.trim_start();
+        } else if !s.is_empty() && !after[..after.len() - s.len()].contains('\n') {
+          return Err(ParseMatrixError::Syntax);
+        }
+      }
+      return if s.is_empty() { Ok(rows) } else { Err(ParseMatrixError::Syntax) };
+    }
+  }
+  if s.contains(['[', ']']) {
+    return Err(ParseMatrixError::Syntax);
+  }
+  Ok(s.split([';', '\n']).map(str::trim).filter(|row| !row.is_empty()).collect())
+}
+
+// Splits a row into its entries, which are separated by commas if there are any
+// and by whitespace otherwise. A trailing comma after the last entry is allowed.
+fn split_entries(row: &str) -> Vec<&str> {
+  let row = match row.trim().strip_suffix(',') {
+    Some(init) if !init.trim().is_empty() => init,
+    _ => row,
+  };
+  if row.contains(',') {
+    row.split(',').map(str::trim).collect()
+  } else {
+    row.split_whitespace().collect()
+  }
+}
+
+/// Accepts MATLAB style literals (`"[1 2; 3 4]"`), nested NumPy style lists
+/// (`"[[1, 2], [3, 4]]"`) and blocks of whitespace separated entries with one row
+/// per line. Entries are separated by commas or whitespace, and are parsed by
+/// `FromStr` of the scalar type. As in Python, a trailing comma may follow the
+/// last entry of a row and the last row of a nested list.
+impl<T: FromStr + Clone + PartialEq> FromStr for Matrix<T> {
+  type Err = ParseMatrixError;
+  fn from_str(s: &str) -> Result<Matrix<T>, ParseMatrixError> {
+    let rows = split_rows(s)?;
+    let n_cols = rows.first().map_or(0, |row| split_entries(row).len());
+    let mut data = Vec::with_capacity(rows.len() * n_cols);
+    for (i, row) in rows.iter().enumerate() {
+      let entries = split_entries(row);
+      if entries.len() != n_cols {
+        return Err(ParseMatrixError::RowLength { row: i });
+      }
+      for (j, entry) in entries.iter().enumerate() {
+        data.push(entry.parse().map_err(|_| ParseMatrixError::Entry { row: i, col: j })?);
+      }
+    }
+    Ok(Matrix::create_from_data(data, rows.len(), n_cols))
+  }
+}
+
+/// Creates a matrix from rows separated by semicolons, whose entries are
+/// separated by commas. Rows of different lengths are a compile-time error.
+///
+/// ```
+/// use matrix_algebra::{matrix, Matrix};
+///
+/// let m = matrix![1, 2; 3, 4];
+/// assert_eq!(m, Matrix::new(vec![vec![1, 2], vec![3, 4]]));
+/// ```
+///
+/// ```compile_fail
+/// use matrix_algebra::matrix;
+///
+/// let m = matrix![1, 2; 3];
+/// ```
+#[macro_export]
+macro_rules! matrix {
+  () => {
+    $crate::Matrix::create_from_data(vec![], 0, 0)
+  };
+  (@count $($e:expr),*) => {
+    0usize $(+ $crate::matrix!(@one $e))*
+  };
+  (@one $e:expr) => {
+    1usize
+  };
+  ($($($e:expr),+);+ $(;)?) => {{
+    const LENGTHS: &[usize] = &[$($crate::matrix!(@count $($e),+)),+];
+    const _: () = {
+      let mut i = 1;
+      while i < LENGTHS.len() {
+        if LENGTHS[i] != LENGTHS[0] {
+          panic!("all rows of a matrix must have the same length");
+        }
+        i += 1;
+      }
+    };
+    $crate::Matrix::create_from_data(vec![$($($e),+),+], LENGTHS.len(), LENGTHS[0])
+  }};
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Rational64;
+
+  #[test]
+  fn test_parse() {
+    let m = Matrix::new(vec![vec![1, 2], vec![3, 4]]);
+    assert_eq!("[1 2; 3 4]".parse::<Matrix<i32>>(), Ok(m.clone()));
+    assert_eq!("[1, 2; 3, 4;]".parse::<Matrix<i32>>(), Ok(m.clone()));
+    assert_eq!("[[1,2],[3,4]]".parse::<Matrix<i32>>(), Ok(m.clone()));
+    assert_eq!("[[1 2]\n [3 4]]".parse::<Matrix<i32>>(), Ok(m.clone()));
+    assert_eq!("[[1, 2,], [3, 4, ],]".parse::<Matrix<i32>>(), Ok(m.clone()));
+    assert_eq!("  1  2\n\n  3  4\n".parse::<Matrix<i32>>(), Ok(m));
+    let r = "[1/2, -3/4]".parse::<Matrix<Rational64>>().unwrap();
+    assert_eq!(r, Matrix::new(vec![vec![Rational64::new(1, 2), Rational64::new(-3, 4)]]));
+    assert_eq!("[1, 2,]".parse::<Matrix<i32>>(), Ok(Matrix::new(vec![vec![1, 2]])));
+    assert_eq!("[]".parse::<Matrix<f64>>().map(|m| (m.n_rows(), m.n_cols())), Ok((0, 0)));
+    assert_eq!("[[]]".parse::<Matrix<f64>>().map(|m| (m.n_rows(), m.n_cols())), Ok((1, 0)));
+  }
+
+  #[test]
+  fn test_parse_errors() {
+    assert_eq!("[1 2; 3]".parse::<Matrix<i32>>(), Err(ParseMatrixError::RowLength { row: 1 }));
+    let e = "[1 2; 3 x]".parse::<Matrix<i32>>();
+    assert_eq!(e, Err(ParseMatrixError::Entry { row: 1, col: 1 }));
+    assert_eq!("[1, , 2]".parse::<Matrix<i32>>(), Err(ParseMatrixError::Entry { row: 0, col: 1 }));
+    assert_eq!("[1 2".parse::<Matrix<i32>>(), Err(ParseMatrixError::Syntax));
+    assert_eq!("[[1, 2], 3]".parse::<Matrix<i32>>(), Err(ParseMatrixError::Syntax));
+    assert_eq!("[[1, 2] [3, 4]]".parse::<Matrix<i32>>(), Err(ParseMatrixError::Syntax));
+    assert_eq!("[[1,2][3,4]]".parse::<Matrix<i32>>(), Err(ParseMatrixError::Syntax));
+    assert_eq!("[[1, 2],, [3, 4]]".parse::<Matrix<i32>>(), Err(ParseMatrixError::Syntax));
+    assert_eq!("[1, 2,,]".parse::<Matrix<i32>>(), Err(ParseMatrixError::Entry { row: 0, col: 2 }));
+    assert_eq!("[,]".parse::<Matrix<i32>>(), Err(ParseMatrixError::Entry { row: 0, col: 0 }));
+    assert_eq!("1 [2]".parse::<Matrix<i32>>(), Err(ParseMatrixError::Syntax));
+  }
+
+  #[test]
+  fn test_macro() {
+    assert_eq!(matrix![1, 2; 3, 4; 5, 6], Matrix::new(vec![vec![1, 2], vec![3, 4], vec![5, 6]]));
+    assert_eq!(matrix![0.5, 1.0 / 4.0], Matrix::new(vec![vec![0.5, 0.25]]));
+    assert_eq!(matrix![1; 2;], Matrix::new(vec![vec![1], vec![2]]));
+    let empty: Matrix<i32> = matrix![];
+    assert_eq!((empty.n_rows(), empty.n_cols()), (0, 0));
+  }
+}
@@ -0,0 +1,168 @@
+use super::num::{Real, Ring, Semiring};
+use super::Matrix;
+use core::ops::Div;
+
+// Polynomials are given by their coefficients in ascending order of powers, so
+// `[c0, c1, c2]` stands for `c0 + c1 x + c2 x^2`.
+
+fn from_usize<T: Semiring>(n: usize) -> T {
+  (0..n).fold(T::zero(), |acc, _| acc + T::one())
+}
+
+impl<T: Semiring> Matrix<T> {
+  /// Evaluates the polynomial `c[0] I + c[1] A + c[2] A^2 + ...` at a square
+  /// matrix. The Paterson–Stockmeyer scheme needs about `2 sqrt(d)` matrix
+  /// products for a polynomial of degree `d` instead of the `d` of Horner's rule.
+  pub fn polyval(&self, coeffs: &[T]) -> Matrix<T> {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let n = self.n_rows;
+    if coeffs.is_empty() {
+      return Matrix::zeros(n, n);
+    }
+    let mut s = 1;
+    while s * s < coeffs.len() {
+      s += 1;
+    }
+    let mut powers = vec![Matrix::identity(n)];
+    for k in 1..=s.min(coeffs.len() - 1) {
+      powers.push(&powers[k - 1] * self);
+    }
+    let block = |chunk: &[T]| {
+      chunk
+        .iter()
+        .zip(powers.iter())
+        .fold(Matrix::zeros(n, n), |acc, (c, p)| &acc + &(p * c.clone()))
+    };
+    let mut chunks = coeffs.chunks(s).rev();
+    let mut result = block(chunks.next().unwrap());
+    for chunk in chunks {
+      result = &(&result * &powers[s]) + &block(chunk);
+    }
+    result
+  }
+}
+
+impl<T: Ring + Div<Output = T>> Matrix<T> {
+  /// Computes the coefficients of the characteristic polynomial `det(x I - A)`
+  /// in ascending order by the Faddeev–LeVerrier algorithm. The leading
+  /// coefficient is one. All divisions are exact, so this works for integer
+  /// matrices too, but over a finite field `Zp` it needs `p` greater than `n`.
+  pub fn characteristic_polynomial(&self) -> Vec<T> {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let n = self.n_rows;
+    let mut coeffs = vec![T::zero(); n + 1];
+    coeffs[n] = T::one();
+    let mut m = Matrix::zeros(n, n);
+    for k in 1..=n {
+      m = &(self * &m) + &(&Matrix::identity(n) * coeffs[n - k + 1].clone());
+      let am = self * &m;
+      let trace = (0..n).fold(T::zero(), |acc, i| acc + am.data[i * n + i].clone());
+      coeffs[n - k] = -(trace / from_usize(k));
+    }
+    coeffs
+  }
+}
+
+impl<T: Real> Matrix<T> {
+  /// Computes the coefficients of the characteristic polynomial `det(x I - A)`
+  /// in ascending order from the Hessenberg form of the matrix. This is the
+  /// numerically preferable alternative to `characteristic_polynomial` for
+  /// floating point matrices.
+  pub fn characteristic_polynomial_hessenberg(&self) -> Vec<T> {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let (_, h) = self.hessenberg();
+    let n = self.n_rows;
+    // p[k] is the characteristic polynomial of the leading k x k block of h.
+    let mut p: Vec<Vec<T>> = vec![vec![T::one()]];
+    for k in 0..n {
+      let mut next = vec![T::zero(); k + 2];
+      for (d, c) in p[k].iter().enumerate() {
+        next[d + 1] = next[d + 1] + *c;
+        next[d] = next[d] - h.data[k * n + k] * *c;
+      }
+      let mut product = T::one();
+      for i in (0..k).rev() {
+        product = product * h.data[(i + 1) * n + i];
+        let f = h.data[i * n + k] * product;
+        for (d, c) in p[i].iter().enumerate() {
+          next[d] = next[d] - f * *c;
+        }
+      }
+      p.push(next);
+    }
+    p.pop().unwrap()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Rational64, Zp};
+
+  #[test]
+  fn test_polyval() {
+    let a = Matrix::new(vec![vec![1, 2, 0], vec![0, 1, -1], vec![3, 0, 2]]);
+    for len in 0..12 {
+      let coeffs = (0..len).map(|k| k as i64 - 4).collect::<Vec<i64>>();
+      let horner = coeffs
+        .iter()
+        .rev()
+        .fold(Matrix::zeros(3, 3), |acc, c| &(&acc * &a) + &(&Matrix::identity(3) * *c));
+      assert_eq!(a.polyval(&coeffs), horner);
+    }
+  }
+
+  #[test]
+  fn test_fibonacci() {
+    let q = Matrix::new(vec![vec![1u64, 1], vec![1, 0]]);
+    assert_eq!(q.pow(90).get(0, 1), 2880067194370816120);
+    // F(n + 2) = F(n + 1) + F(n), so q^2 - q - I vanishes.
+    let q = Matrix::new(vec![vec![1i64, 1], vec![1, 0]]);
+    assert_eq!(q.polyval(&[-1, -1, 1]), Matrix::zeros(2, 2));
+  }
+
+  #[test]
+  fn test_characteristic_polynomial() {
+    let a = Matrix::new(vec![vec![2i64, -1, 0], vec![1, 3, 4], vec![0, 5, -2]]);
+    let p = a.characteristic_polynomial();
+    assert_eq!(p, vec![54, -23, -3, 1]);
+    assert_eq!(p[0], -a.det());
+    assert_eq!(a.polyval(&p), Matrix::zeros(3, 3));
+    let r = a.map(|e| Rational64::new(e, 2));
+    let q = r.characteristic_polynomial();
+    assert_eq!(q[0], Rational64::new(27, 4));
+    assert_eq!(r.polyval(&q), Matrix::zeros(3, 3));
+    let z = a.map(Zp::<7>::from);
+    assert_eq!(z.polyval(&z.characteristic_polynomial()), Matrix::zeros(3, 3));
+  }
+
+  #[test]
+  fn test_characteristic_polynomial_hessenberg() {
+    let a: Matrix<f64> = Matrix::new(vec![
+      vec![2.0, -1.0, 0.0, 1.0],
+      vec![1.0, 3.0, 4.0, 0.5],
+      vec![0.0, 5.0, -2.0, 1.0],
+      vec![1.5, 0.0, 2.0, 1.0],
+    ]);
+    let p = a.characteristic_polynomial_hessenberg();
+    let q = a.characteristic_polynomial();
+    assert_eq!(p.len(), 5);
+    for (x, y) in p.iter().zip(q.iter()) {
+      assert!((x - y).abs() < 1e-10);
+    }
+    assert!(a.polyval(&p).iter().all(|(e, _, _)| e.abs() < 1e-10));
+  }
+
+  #[test]
+  #[should_panic(expected = "matrix must be square!")]
+  fn test_characteristic_polynomial_hessenberg_not_square() {
+    let a: Matrix<f64> = Matrix::new(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+    a.characteristic_polynomial_hessenberg();
+  }
+}
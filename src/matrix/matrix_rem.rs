@@ -1,14 +1,16 @@
-use super::Matrix;
-use core::ops::{Mul, Rem};
+use super::{num::Semiring, Matrix};
+use core::ops::Rem;
 
 macro_rules! elementw_mult {
   ($LHS:ty, $RHS:ty, $ScalarType:tt ) => {
-    impl<$ScalarType: Mul<Output = $ScalarType> + Copy + PartialEq> Rem<$RHS> for $LHS {
+    impl<$ScalarType: Semiring> Rem<$RHS> for $LHS {
       type Output = Matrix<$ScalarType>;
+      // `%` denotes the element-wise (Hadamard) product.
+      #[allow(clippy::suspicious_arithmetic_impl)]
       fn rem(self, rhs: $RHS) -> Self::Output {
         let mut res: Vec<$ScalarType> = vec![];
         for (e, row, col) in self.iter() {
-          res.push(*e * rhs.get(row, col));
+          res.push(e.clone() * rhs.get(row, col));
         }
         return Matrix::create_from_data(res, self.n_rows, self.n_cols);
       }
@@ -1,12 +1,12 @@
-use super::{scalar::Scalar, Matrix};
+use super::{num::Semiring, scalar::Scalar, Matrix};
 use core::ops::Mul;
 
 macro_rules! scalar_mult {
   ($LHS:ty, $RHS:ty, $ScalarType:tt ) => {
-    impl<$ScalarType: Mul<Output = $ScalarType> + Copy> Mul<$RHS> for $LHS {
+    impl<$ScalarType: Mul<Output = $ScalarType> + Clone> Mul<$RHS> for $LHS {
       type Output = Scalar<$ScalarType>;
       fn mul(self, rhs: $RHS) -> Self::Output {
-        return Scalar(self.0 * rhs.0);
+        return Scalar(self.0.clone() * rhs.0.clone());
       }
     }
   };
@@ -18,12 +18,12 @@ scalar_mult!(Scalar<T>, &Scalar<T>, T);
 
 macro_rules! scalar_matrix_mult {
   ($LHS:ty, $RHS:ty, $ScalarType:tt ) => {
-    impl<$ScalarType: Mul<Output = $ScalarType> + Copy + PartialEq> Mul<$RHS> for $LHS {
+    impl<$ScalarType: Semiring> Mul<$RHS> for $LHS {
       type Output = Matrix<$ScalarType>;
       fn mul(self, rhs: $RHS) -> Self::Output {
         let mut res: Vec<$ScalarType> = vec![];
         for v in rhs.data.iter() {
-          res.push(self.0 * *v);
+          res.push(self.0.clone() * v.clone());
         }
         return Matrix::create_from_data(res, rhs.n_rows, rhs.n_cols);
       }
@@ -37,12 +37,12 @@ scalar_matrix_mult!(&Scalar<T>, &Matrix<T>, T);
 
 macro_rules! scalar_type_matrix_mult {
   ($LHS:ty, $ScalarType:tt ) => {
-    impl<$ScalarType: Mul<Output = $ScalarType> + Copy + PartialEq> Mul<$ScalarType> for $LHS {
+    impl<$ScalarType: Semiring> Mul<$ScalarType> for $LHS {
       type Output = Matrix<$ScalarType>;
       fn mul(self, rhs: $ScalarType) -> Self::Output {
         let mut res: Vec<$ScalarType> = vec![];
         for v in self.data.iter() {
-          res.push(rhs * *v);
+          res.push(rhs.clone() * v.clone());
         }
         return Matrix::create_from_data(res, self.n_rows, self.n_cols);
       }
@@ -53,6 +53,7 @@ scalar_type_matrix_mult!(Matrix<T>, T);
 scalar_type_matrix_mult!(&Matrix<T>, T);
 
 #[cfg(test)]
+#[allow(clippy::op_ref)]
 mod tests {
   use super::*;
 
@@ -0,0 +1,382 @@
+use super::num::Real;
+use super::Matrix;
+
+const MAX_ITERATIONS: usize = 100;
+
+impl<T: Real> Matrix<T> {
+  /// Reduces a square matrix to upper Hessenberg form by Householder reflections.
+  /// Returns `(Q, H)` with `Q` orthogonal and `A = Q * H * Q^T`.
+  // The Householder vector is indexed in step with the rows and columns of `H`.
+  #[allow(clippy::needless_range_loop)]
+  pub fn hessenberg(&self) -> (Matrix<T>, Matrix<T>) {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let n = self.n_rows;
+    let mut h = self.clone();
+    let mut q = Matrix::identity(n);
+    let mut v = vec![T::zero(); n];
+    for m in 1..n.saturating_sub(1) {
+      let scale = (m..n).fold(T::zero(), |acc, i| acc + h.data[i * n + m - 1].abs());
+      if scale.is_zero() {
+        continue;
+      }
+      let mut norm2 = T::zero();
+      for i in m..n {
+        v[i] = h.data[i * n + m - 1] / scale;
+        norm2 = norm2 + v[i] * v[i];
+      }
+      let g = if v[m] > T::zero() { -norm2.sqrt() } else { norm2.sqrt() };
+      let beta = norm2 - v[m] * g;
+      v[m] = v[m] - g;
+      // Applies I - v v^T / beta from the left and from the right.
+      for j in 0..n {
+        let f = (m..n).fold(T::zero(), |acc, i| acc + v[i] * h.data[i * n + j]) / beta;
+        for i in m..n {
+          h.data[i * n + j] = h.data[i * n + j] - f * v[i];
+        }
+      }
+      for i in 0..n {
+        let f = (m..n).fold(T::zero(), |acc, j| acc + v[j] * h.data[i * n + j]) / beta;
+        for j in m..n {
+          h.data[i * n + j] = h.data[i * n + j] - f * v[j];
+        }
+        let f = (m..n).fold(T::zero(), |acc, j| acc + v[j] * q.data[i * n + j]) / beta;
+        for j in m..n {
+          q.data[i * n + j] = q.data[i * n + j] - f * v[j];
+        }
+      }
+      h.data[m * n + m - 1] = scale * g;
+      for i in m + 1..n {
+        h.data[i * n + m - 1] = T::zero();
+      }
+    }
+    (q, h)
+  }
+
+  /// Computes the real Schur decomposition by the Francis double shift QR algorithm.
+  /// Returns `(Q, S)` with `Q` orthogonal and `A = Q * S * Q^T`, where `S` is upper
+  /// quasi-triangular: real eigenvalues appear on its diagonal and complex conjugate
+  /// pairs as 2 x 2 blocks. Returns `None` if the iteration does not converge.
+  pub fn real_schur(&self) -> Option<(Matrix<T>, Matrix<T>)> {
+    let (mut q, mut h) = self.hessenberg();
+    let n = self.n_rows;
+    let eps = T::epsilon();
+    let two = T::one() + T::one();
+    let norm = h.data.iter().fold(T::zero(), |acc, e| acc + e.abs());
+    // `hi` is the last row of the active window, `lo` its first.
+    let mut hi = n;
+    let mut iter = 0;
+    let mut shift = T::zero();
+    while hi > 0 {
+      let last = hi - 1;
+      let mut lo = last;
+      while lo > 0 {
+        let s = h.data[(lo - 1) * n + lo - 1].abs() + h.data[lo * n + lo].abs();
+        let s = if s.is_zero() { norm } else { s };
+        if h.data[lo * n + lo - 1].abs() < eps * s {
+          h.data[lo * n + lo - 1] = T::zero();
+          break;
+        }
+        lo -= 1;
+      }
+      if lo == last {
+        h.data[last * n + last] = h.data[last * n + last] + shift;
+        hi -= 1;
+        iter = 0;
+      } else if lo + 1 == last {
+        let (a, b) = (last - 1, last);
+        h.data[a * n + a] = h.data[a * n + a] + shift;
+        h.data[b * n + b] = h.data[b * n + b] + shift;
+        let w = h.data[b * n + a] * h.data[a * n + b];
+        let p = (h.data[a * n + a] - h.data[b * n + b]) / two;
+        let disc = p * p + w;
+        if disc >= T::zero() {
+          // A real pair, the block is triangularized by a rotation.
+          let z = disc.sqrt();
+          let z = if p >= T::zero() { p + z } else { p - z };
+          let x = h.data[b * n + a];
+          let s = x.abs() + z.abs();
+          let (p, q0) = (x / s, z / s);
+          let r = (p * p + q0 * q0).sqrt();
+          let (sn, cs) = (p / r, q0 / r);
+          for j in a..n {
+            let z = h.data[a * n + j];
+            h.data[a * n + j] = cs * z + sn * h.data[b * n + j];
+            h.data[b * n + j] = cs * h.data[b * n + j] - sn * z;
+          }
+          for i in 0..=b {
+            let z = h.data[i * n + a];
+            h.data[i * n + a] = cs * z + sn * h.data[i * n + b];
+            h.data[i * n + b] = cs * h.data[i * n + b] - sn * z;
+          }
+          for i in 0..n {
+            let z = q.data[i * n + a];
+            q.data[i * n + a] = cs * z + sn * q.data[i * n + b];
+            q.data[i * n + b] = cs * q.data[i * n + b] - sn * z;
+          }
+          h.data[b * n + a] = T::zero();
+        }
+        hi -= 2;
+        iter = 0;
+      } else {
+        let mut x = h.data[last * n + last];
+        let mut y = h.data[(last - 1) * n + last - 1];
+        let mut w = h.data[last * n + last - 1] * h.data[(last - 1) * n + last];
+        // Exceptional shifts to break cycles.
+        if iter == 10 {
+          shift = shift + x;
+          for i in 0..=last {
+            h.data[i * n + i] = h.data[i * n + i] - x;
+          }
+          let s = h.data[last * n + last - 1].abs() + h.data[(last - 1) * n + last - 2].abs();
+          x = T::from_f64(0.75) * s;
+          y = x;
+          w = T::from_f64(-0.4375) * s * s;
+        }
+        if iter == 30 {
+          let s = (y - x) / two;
+          let s = s * s + w;
+          if s > T::zero() {
+            let s = if y < x { -s.sqrt() } else { s.sqrt() };
+            let s = x - w / ((y - x) / two + s);
+            for i in 0..=last {
+              h.data[i * n + i] = h.data[i * n + i] - s;
+            }
+            shift = shift + s;
+            x = T::from_f64(0.964);
+            y = x;
+            w = x;
+          }
+        }
+        iter += 1;
+        if iter > MAX_ITERATIONS {
+          return None;
+        }
+        francis_step(&mut h, &mut q, lo, last, (x, y, w));
+      }
+    }
+    for i in 0..n {
+      for j in 0..i.saturating_sub(1) {
+        h.data[i * n + j] = T::zero();
+      }
+    }
+    Some((q, h))
+  }
+
+  /// Computes the eigenvalues of a square matrix as pairs of real and imaginary
+  /// parts, in the order they appear on the diagonal of the real Schur form.
+  /// Returns `None` if the QR iteration does not converge.
+  pub fn eigenvalues(&self) -> Option<Vec<(T, T)>> {
+    let (_, s) = self.real_schur()?;
+    let n = self.n_rows;
+    let two = T::one() + T::one();
+    let mut values = vec![];
+    let mut i = 0;
+    while i < n {
+      if i + 1 < n && !s.data[(i + 1) * n + i].is_zero() {
+        let (a, b) = (s.data[i * n + i], s.data[i * n + i + 1]);
+        let (c, d) = (s.data[(i + 1) * n + i], s.data[(i + 1) * n + i + 1]);
+        let re = (a + d) / two;
+        let im = ((a - d) * (a - d) / (two * two) + b * c).abs().sqrt();
+        values.push((re, im));
+        values.push((re, -im));
+        i += 2;
+      } else {
+        values.push((s.data[i * n + i], T::zero()));
+        i += 1;
+      }
+    }
+    Some(values)
+  }
+}
+
+// Performs a double shift QR step on the window `lo..=last` of `h`, with shifts
+// given by the trace `x + y` and the determinant `x * y - w`.
+fn francis_step<T: Real>(
+  h: &mut Matrix<T>,
+  q: &mut Matrix<T>,
+  lo: usize,
+  last: usize,
+  (x, y, w): (T, T, T),
+) {
+  let n = h.n_rows;
+  let at = |h: &Matrix<T>, i: usize, j: usize| h.data[i * n + j];
+  let eps = T::epsilon();
+  // Looks for two consecutive small sub-diagonal elements.
+  let mut m = last - 2;
+  let (mut p, mut q0, mut r);
+  loop {
+    let z = at(h, m, m);
+    let (rr, s) = (x - z, y - z);
+    p = (rr * s - w) / at(h, m + 1, m) + at(h, m, m + 1);
+    q0 = at(h, m + 1, m + 1) - z - rr - s;
+    r = at(h, m + 2, m + 1);
+    let s = p.abs() + q0.abs() + r.abs();
+    p = p / s;
+    q0 = q0 / s;
+    r = r / s;
+    if m == lo {
+      break;
+    }
+    let u = at(h, m, m - 1).abs() * (q0.abs() + r.abs());
+    let v = p.abs() * (at(h, m - 1, m - 1).abs() + z.abs() + at(h, m + 1, m + 1).abs());
+    if u < eps * v {
+      break;
+    }
+    m -= 1;
+  }
+  for i in m + 2..=last {
+    h.data[i * n + i - 2] = T::zero();
+    if i > m + 2 {
+      h.data[i * n + i - 3] = T::zero();
+    }
+  }
+  for k in m..last {
+    let not_last = k != last - 1;
+    let mut scale = T::one();
+    if k != m {
+      p = at(h, k, k - 1);
+      q0 = at(h, k + 1, k - 1);
+      r = if not_last { at(h, k + 2, k - 1) } else { T::zero() };
+      scale = p.abs() + q0.abs() + r.abs();
+      if scale.is_zero() {
+        continue;
+      }
+      p = p / scale;
+      q0 = q0 / scale;
+      r = r / scale;
+    }
+    let s = (p * p + q0 * q0 + r * r).sqrt();
+    let s = if p < T::zero() { -s } else { s };
+    if s.is_zero() {
+      continue;
+    }
+    if k != m {
+      h.data[k * n + k - 1] = -s * scale;
+    } else if lo != m {
+      h.data[k * n + k - 1] = -h.data[k * n + k - 1];
+    }
+    p = p + s;
+    let (vx, vy, vz) = (p / s, q0 / s, r / s);
+    let (q1, r1) = (q0 / p, r / p);
+    for j in k..n {
+      let mut t = h.data[k * n + j] + q1 * h.data[(k + 1) * n + j];
+      if not_last {
+        t = t + r1 * h.data[(k + 2) * n + j];
+        h.data[(k + 2) * n + j] = h.data[(k + 2) * n + j] - t * vz;
+      }
+      h.data[k * n + j] = h.data[k * n + j] - t * vx;
+      h.data[(k + 1) * n + j] = h.data[(k + 1) * n + j] - t * vy;
+    }
+    for i in 0..=usize::min(last, k + 3) {
+      let mut t = vx * h.data[i * n + k] + vy * h.data[i * n + k + 1];
+      if not_last {
+        t = t + vz * h.data[i * n + k + 2];
+        h.data[i * n + k + 2] = h.data[i * n + k + 2] - t * r1;
+      }
+      h.data[i * n + k] = h.data[i * n + k] - t;
+      h.data[i * n + k + 1] = h.data[i * n + k + 1] - t * q1;
+    }
+    for i in 0..n {
+      let mut t = vx * q.data[i * n + k] + vy * q.data[i * n + k + 1];
+      if not_last {
+        t = t + vz * q.data[i * n + k + 2];
+        q.data[i * n + k + 2] = q.data[i * n + k + 2] - t * r1;
+      }
+      q.data[i * n + k] = q.data[i * n + k] - t;
+      q.data[i * n + k + 1] = q.data[i * n + k + 1] - t * q1;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>, tol: f64) {
+    for ((x, _, _), (y, _, _)) in a.iter().zip(b.iter()) {
+      assert!((x - y).abs() < tol, "{:?} != {:?}", a, b);
+    }
+  }
+
+  fn check_schur(a: &Matrix<f64>) -> Matrix<f64> {
+    let n = a.n_rows();
+    let (q, s) = a.real_schur().unwrap();
+    assert_close(&(&q.trans() * &q), &Matrix::identity(n), 1e-12);
+    assert_close(&(&(&q * &s) * &q.trans()), a, 1e-10);
+    for i in 0..n {
+      for j in 0..i {
+        let in_block = j + 1 == i
+          && (i + 1 == n || s.get(i + 1, i) == 0.0)
+          && (j == 0 || s.get(j, j - 1) == 0.0);
+        assert!(s.get(i, j) == 0.0 || in_block);
+      }
+    }
+    s
+  }
+
+  #[test]
+  fn test_hessenberg() {
+    let a = Matrix::new(vec![
+      vec![4.0, 1.0, -2.0, 2.0],
+      vec![1.0, 2.0, 0.0, 1.0],
+      vec![-2.0, 0.0, 3.0, -2.0],
+      vec![2.0, 1.0, -2.0, -1.0],
+    ]);
+    let (q, h) = a.hessenberg();
+    assert_close(&(&(&q * &h) * &q.trans()), &a, 1e-12);
+    assert_eq!((h.get(2, 0), h.get(3, 0), h.get(3, 1)), (0.0, 0.0, 0.0));
+  }
+
+  #[test]
+  fn test_real_schur() {
+    let a = Matrix::new(vec![
+      vec![1.0, 2.0, 3.0, 4.0],
+      vec![-1.0, 0.5, 2.0, 0.0],
+      vec![3.0, 1.0, -2.0, 1.0],
+      vec![0.5, -4.0, 1.0, 3.0],
+    ]);
+    check_schur(&a);
+    let rotation = Matrix::new(vec![
+      vec![0.0, -1.0, 0.0],
+      vec![1.0, 0.0, 0.0],
+      vec![0.0, 0.0, 2.0],
+    ]);
+    check_schur(&rotation);
+    let triangular = Matrix::new(vec![vec![1.0, 5.0], vec![0.0, 1.0]]);
+    check_schur(&triangular);
+  }
+
+  #[test]
+  fn test_real_schur_random() {
+    let mut rng = crate::nn::Rng::new(11);
+    for n in 1..=8 {
+      for _ in 0..5 {
+        let a = Matrix::create_from_data((0..n * n).map(|_| rng.normal()).collect(), n, n);
+        check_schur(&a);
+      }
+    }
+  }
+
+  #[test]
+  fn test_eigenvalues() {
+    let a = Matrix::new(vec![vec![2.0, 0.0, 0.0], vec![0.0, 3.0, 4.0], vec![0.0, -4.0, 3.0]]);
+    let mut values = a.eigenvalues().unwrap();
+    values.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    let expected = [(2.0f64, 0.0f64), (3.0, -4.0), (3.0, 4.0)];
+    for (v, e) in values.iter().zip(expected.iter()) {
+      assert!((v.0 - e.0).abs() < 1e-12 && (v.1 - e.1).abs() < 1e-12);
+    }
+    let companion = Matrix::new(vec![
+      vec![6.0, -11.0, 6.0],
+      vec![1.0, 0.0, 0.0],
+      vec![0.0, 1.0, 0.0],
+    ]);
+    let mut roots = companion.eigenvalues().unwrap().iter().map(|v| v.0).collect::<Vec<f64>>();
+    roots.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    for (r, e) in roots.iter().zip([1.0, 2.0, 3.0].iter()) {
+      assert!((r - e).abs() < 1e-10);
+    }
+  }
+}
@@ -0,0 +1,142 @@
+use super::num::Semiring;
+use super::semiring::SemiringWrapper;
+use super::Matrix;
+
+impl<T: Semiring> Matrix<T> {
+  /// Computes the `n`'th power of a square matrix by repeated squaring.
+  /// The power is taken in whatever semiring `T` describes.
+  pub fn pow(&self, mut n: u32) -> Matrix<T> {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let mut result = Matrix::identity(self.n_rows);
+    let mut base = self.clone();
+    while n > 0 {
+      if n & 1 == 1 {
+        result = &result * &base;
+      }
+      n >>= 1;
+      if n > 0 {
+        base = &base * &base;
+      }
+    }
+    result
+  }
+
+  /// Computes the closure (Kleene star) `I + A + A^2 + ...` of a square matrix.
+  /// The addition of `T` must be idempotent, as it is for `MinPlus`, `MaxPlus`,
+  /// `MaxTimes` and `Boolean`, so that the series terminates after `n - 1` terms.
+  /// Over `MinPlus` this yields all-pairs shortest paths (provided there are no
+  /// negative cycles) and over `Boolean` the reflexive transitive closure.
+  pub fn closure(&self) -> Matrix<T> {
+    if self.n_rows != self.n_cols {
+      panic!("matrix must be square!");
+    }
+    let mut result = &Matrix::identity(self.n_rows) + self;
+    let mut length = 1;
+    while length + 1 < self.n_rows {
+      let next = &result * &result;
+      if next == result {
+        break;
+      }
+      result = next;
+      length *= 2;
+    }
+    result
+  }
+}
+
+impl<T: Clone + PartialEq> Matrix<T> {
+  /// Multiplies two matrices over the semiring `S` wrapping the scalar type `T`,
+  /// e.g. `a.semiring_mul::<MinPlus<f64>>(&b)`.
+  pub fn semiring_mul<S: SemiringWrapper<T>>(&self, rhs: &Matrix<T>) -> Matrix<T> {
+    let product = &self.map(S::from) * &rhs.map(S::from);
+    product.map(S::into_inner)
+  }
+
+  /// Computes the `n`'th power of a square matrix over the semiring `S`.
+  pub fn semiring_pow<S: SemiringWrapper<T>>(&self, n: u32) -> Matrix<T> {
+    self.map(S::from).pow(n).map(S::into_inner)
+  }
+
+  /// Computes the closure (Kleene star) of a square matrix over the semiring `S`.
+  pub fn semiring_closure<S: SemiringWrapper<T>>(&self) -> Matrix<T> {
+    self.map(S::from).closure().map(S::into_inner)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::semiring::{Boolean, MaxPlus, MaxTimes, MinPlus};
+  use super::*;
+
+  const INF: f64 = f64::INFINITY;
+
+  #[test]
+  fn test_pow() {
+    let a = Matrix::new(vec![vec![1, 1], vec![1, 0]]);
+    assert_eq!(a.pow(0), Matrix::identity(2));
+    assert_eq!(a.pow(10), Matrix::new(vec![vec![89, 55], vec![55, 34]]));
+  }
+
+  #[test]
+  fn test_semiring_mul() {
+    let a = Matrix::new(vec![vec![0.0, 3.0], vec![INF, 0.0]]);
+    let b = Matrix::new(vec![vec![0.0, INF], vec![1.0, 0.0]]);
+    assert_eq!(
+      a.semiring_mul::<MinPlus<f64>>(&b),
+      Matrix::new(vec![vec![0.0, 3.0], vec![1.0, 0.0]])
+    );
+    let c = Matrix::new(vec![vec![0.0, 3.0], vec![-INF, 0.0]]);
+    let d = Matrix::new(vec![vec![0.0, -INF], vec![1.0, 0.0]]);
+    assert_eq!(
+      c.semiring_mul::<MaxPlus<f64>>(&d),
+      Matrix::new(vec![vec![4.0, 3.0], vec![1.0, 0.0]])
+    );
+  }
+
+  #[test]
+  fn test_all_pairs_shortest_paths() {
+    let w = Matrix::new(vec![
+      vec![INF, 4.0, 1.0, INF],
+      vec![INF, INF, INF, 1.0],
+      vec![INF, 2.0, INF, 5.0],
+      vec![INF, INF, INF, INF],
+    ]);
+    let d = w.semiring_closure::<MinPlus<f64>>();
+    assert_eq!(
+      d,
+      Matrix::new(vec![
+        vec![0.0, 3.0, 1.0, 4.0],
+        vec![INF, 0.0, INF, 1.0],
+        vec![INF, 2.0, 0.0, 3.0],
+        vec![INF, INF, INF, 0.0],
+      ])
+    );
+    let hops = w.map(MinPlus).pow(2).map(|e| e.0);
+    assert_eq!(hops.get(0, 1), 3.0);
+    assert_eq!(hops.get(0, 3), 5.0);
+  }
+
+  #[test]
+  fn test_transitive_closure() {
+    let adj = Matrix::new(vec![
+      vec![false, true, false, false],
+      vec![false, false, true, false],
+      vec![false, false, false, false],
+      vec![true, false, false, false],
+    ]);
+    let reach = adj.map(Boolean).closure();
+    assert_eq!(
+      reach.map(|b| b.0 as u8),
+      Matrix::new(vec![vec![1, 1, 1, 0], vec![0, 1, 1, 0], vec![0, 0, 1, 0], vec![1, 1, 1, 1]])
+    );
+  }
+
+  #[test]
+  fn test_most_reliable_path() {
+    let p = Matrix::new(vec![vec![0.0, 0.5, 0.25], vec![0.0, 0.0, 0.75], vec![0.0, 0.0, 0.0]]);
+    let r = p.semiring_closure::<MaxTimes<f64>>();
+    assert_eq!(r.get(0, 2), 0.375);
+  }
+}
@@ -0,0 +1,164 @@
+use super::scalar::Scalar;
+use super::Matrix;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+const FIELDS: &[&str] = &["n_rows", "n_cols", "data"];
+
+/// A matrix is serialized as a struct holding its shape and its entries in
+/// row-major order.
+impl<T: Clone + PartialEq + Serialize> Serialize for Matrix<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("Matrix", 3)?;
+    s.serialize_field("n_rows", &self.n_rows)?;
+    s.serialize_field("n_cols", &self.n_cols)?;
+    s.serialize_field("data", &self.data)?;
+    s.end()
+  }
+}
+
+enum Field {
+  NRows,
+  NCols,
+  Data,
+}
+
+impl<'de> Deserialize<'de> for Field {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Field, D::Error> {
+    struct FieldVisitor;
+    impl<'de> Visitor<'de> for FieldVisitor {
+      type Value = Field;
+      fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`n_rows`, `n_cols` or `data`")
+      }
+      fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+        match value {
+          "n_rows" => Ok(Field::NRows),
+          "n_cols" => Ok(Field::NCols),
+          "data" => Ok(Field::Data),
+          _ => Err(de::Error::unknown_field(value, FIELDS)),
+        }
+      }
+    }
+    deserializer.deserialize_identifier(FieldVisitor)
+  }
+}
+
+fn from_parts<T: Clone + PartialEq, E: de::Error>(
+  n_rows: usize,
+  n_cols: usize,
+  data: Vec<T>,
+) -> Result<Matrix<T>, E> {
+  match n_rows.checked_mul(n_cols) {
+    Some(n) if n == data.len() => Ok(Matrix::create_from_data(data, n_rows, n_cols)),
+    Some(n) => {
+      let expected = format!("{} entries for a {} x {} matrix", n, n_rows, n_cols);
+      Err(de::Error::invalid_length(data.len(), &&expected[..]))
+    }
+    None => Err(de::Error::custom(format!("a {} x {} matrix is too large", n_rows, n_cols))),
+  }
+}
+
+struct MatrixVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Clone + PartialEq + Deserialize<'de>> Visitor<'de> for MatrixVisitor<T> {
+  type Value = Matrix<T>;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "a matrix with fields `n_rows`, `n_cols` and `data`")
+  }
+
+  fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Matrix<T>, A::Error> {
+    let n_rows = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+    let n_cols = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+    let data = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+    from_parts(n_rows, n_cols, data)
+  }
+
+  fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Matrix<T>, A::Error> {
+    let (mut n_rows, mut n_cols, mut data) = (None, None, None);
+    while let Some(key) = map.next_key()? {
+      match key {
+        Field::NRows if n_rows.is_none() => n_rows = Some(map.next_value()?),
+        Field::NCols if n_cols.is_none() => n_cols = Some(map.next_value()?),
+        Field::Data if data.is_none() => data = Some(map.next_value()?),
+        Field::NRows => return Err(de::Error::duplicate_field("n_rows")),
+        Field::NCols => return Err(de::Error::duplicate_field("n_cols")),
+        Field::Data => return Err(de::Error::duplicate_field("data")),
+      }
+    }
+    let n_rows = n_rows.ok_or_else(|| de::Error::missing_field("n_rows"))?;
+    let n_cols = n_cols.ok_or_else(|| de::Error::missing_field("n_cols"))?;
+    let data = data.ok_or_else(|| de::Error::missing_field("data"))?;
+    from_parts(n_rows, n_cols, data)
+  }
+}
+
+/// Deserialization fails unless the number of entries matches the shape.
+impl<'de, T: Clone + PartialEq + Deserialize<'de>> Deserialize<'de> for Matrix<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Matrix<T>, D::Error> {
+    deserializer.deserialize_struct("Matrix", FIELDS, MatrixVisitor(PhantomData))
+  }
+}
+
+impl<T: Serialize> Serialize for Scalar<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct("Scalar", &self.0)
+  }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Scalar<T> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Scalar<T>, D::Error> {
+    struct ScalarVisitor<T>(PhantomData<T>);
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for ScalarVisitor<T> {
+      type Value = Scalar<T>;
+      fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a scalar")
+      }
+      fn visit_newtype_struct<D: Deserializer<'de>>(self, d: D) -> Result<Scalar<T>, D::Error> {
+        T::deserialize(d).map(Scalar)
+      }
+    }
+    deserializer.deserialize_newtype_struct("Scalar", ScalarVisitor(PhantomData))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_matrix_json() {
+    let m = Matrix::new(vec![vec![1.5, -2.0, 0.0], vec![4.0, 5.0, 6.25]]);
+    let json = serde_json::to_string(&m).unwrap();
+    assert_eq!(json, r#"{"n_rows":2,"n_cols":3,"data":[1.5,-2.0,0.0,4.0,5.0,6.25]}"#);
+    assert_eq!(serde_json::from_str::<Matrix<f64>>(&json).unwrap(), m);
+    let json = r#"{"data":[[1,2],[3,4]],"n_cols":1,"n_rows":2}"#;
+    let m: Matrix<Vec<i32>> = serde_json::from_str(json).unwrap();
+    assert_eq!(m.get(1, 0), vec![3, 4]);
+    let empty: Matrix<i64> = serde_json::from_str(r#"{"n_rows":0,"n_cols":5,"data":[]}"#).unwrap();
+    assert_eq!((empty.n_rows(), empty.n_cols()), (0, 5));
+  }
+
+  #[test]
+  fn test_invalid_matrix() {
+    let e = serde_json::from_str::<Matrix<i32>>(r#"{"n_rows":2,"n_cols":2,"data":[1,2,3]}"#);
+    let message = e.unwrap_err().to_string();
+    assert!(message.starts_with("invalid length 3, expected 4 entries for a 2 x 2 matrix"));
+    assert!(serde_json::from_str::<Matrix<i32>>(r#"{"n_rows":1,"data":[1]}"#).is_err());
+    let huge = format!(r#"{{"n_rows":{},"n_cols":2,"data":[]}}"#, usize::MAX);
+    assert!(serde_json::from_str::<Matrix<i32>>(&huge).is_err());
+    assert!(serde_json::from_str::<Matrix<i32>>("[1,1,[7]]").is_ok());
+  }
+
+  #[test]
+  fn test_scalar_json() {
+    assert_eq!(serde_json::to_string(&Scalar(2.5)).unwrap(), "2.5");
+    assert_eq!(serde_json::from_str::<Scalar<i64>>("-3").unwrap(), Scalar(-3));
+    let m = Matrix::new(vec![vec![Scalar(1u8), Scalar(2)]]);
+    let json = serde_json::to_string(&m).unwrap();
+    assert_eq!(serde_json::from_str::<Matrix<Scalar<u8>>>(&json).unwrap(), m);
+  }
+}
@@ -1,14 +1,14 @@
-use super::Matrix;
+use super::{num::Ring, Matrix};
 use core::ops::Sub;
 
 macro_rules! matrix_subtraction {
   ($LHS:ty, $RHS:ty, $ScalarType:tt ) => {
-    impl<$ScalarType: Sub<Output = $ScalarType> + Copy + PartialEq> Sub<$RHS> for $LHS {
+    impl<$ScalarType: Ring> Sub<$RHS> for $LHS {
       type Output = Matrix<$ScalarType>;
       fn sub(self, rhs: $RHS) -> Self::Output {
         let mut res: Vec<$ScalarType> = vec![];
         for (e, row, col) in self.iter() {
-          res.push(*e - rhs.get(row, col));
+          res.push(e.clone() - rhs.get(row, col));
         }
         return Matrix::create_from_data(res, self.n_rows, self.n_cols);
       }
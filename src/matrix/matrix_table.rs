@@ -0,0 +1,219 @@
+use super::Matrix;
+use std::fmt::Display;
+
+/// The LaTeX environment of an exported matrix, as provided by `amsmath`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatexEnvironment {
+  /// Parentheses around the matrix.
+  Pmatrix,
+  /// Square brackets around the matrix.
+  Bmatrix,
+}
+
+/// Exports a matrix as a LaTeX environment, a Markdown table or an HTML table.
+/// Every entry is converted to text by a formatting closure, and the rows and
+/// columns can be labelled.
+pub struct MatrixTable<'a, T: Clone + PartialEq> {
+  matrix: &'a Matrix<T>,
+  format: Box<dyn Fn(&T) -> String + 'a>,
+  row_labels: Option<Vec<String>>,
+  col_labels: Option<Vec<String>>,
+}
+
+impl<'a, T: Clone + PartialEq> MatrixTable<'a, T> {
+  /// Sets the closure converting an entry to text.
+  pub fn format<F: Fn(&T) -> String + 'a>(mut self, format: F) -> MatrixTable<'a, T> {
+    self.format = Box::new(format);
+    self
+  }
+
+  /// Labels the rows. Panics unless there is one label per row.
+  pub fn row_labels<S, I>(mut self, labels: I) -> MatrixTable<'a, T>
+  where
+    S: ToString,
+    I: IntoIterator<Item = S>,
+  {
+    let labels = labels.into_iter().map(|s| s.to_string()).collect::<Vec<String>>();
+    if labels.len() != self.matrix.n_rows {
+      panic!("not compatible dimension!");
+    }
+    self.row_labels = Some(labels);
+    self
+  }
+
+  /// Labels the columns. Panics unless there is one label per column.
+  pub fn col_labels<S, I>(mut self, labels: I) -> MatrixTable<'a, T>
+  where
+    S: ToString,
+    I: IntoIterator<Item = S>,
+  {
+    let labels = labels.into_iter().map(|s| s.to_string()).collect::<Vec<String>>();
+    if labels.len() != self.matrix.n_cols {
+      panic!("not compatible dimension!");
+    }
+    self.col_labels = Some(labels);
+    self
+  }
+
+  // The formatted entries, with the labels as an extra first row and column. The
+  // corner cell is empty when both kinds of labels are present.
+  fn cells(&self, escape: fn(&str) -> String) -> Vec<Vec<String>> {
+    let m = self.matrix;
+    let mut cells = vec![];
+    if let Some(col_labels) = &self.col_labels {
+      let corner = self.row_labels.as_ref().map(|_| String::new());
+      cells.push(corner.into_iter().chain(col_labels.iter().map(|s| escape(s))).collect());
+    }
+    for i in 0..m.n_rows {
+      let label = self.row_labels.as_ref().map(|labels| escape(&labels[i]));
+      let row = m.data[i * m.n_cols..(i + 1) * m.n_cols].iter().map(|e| escape(&(self.format)(e)));
+      cells.push(label.into_iter().chain(row).collect());
+    }
+    cells
+  }
+
+  /// Returns the matrix as a LaTeX environment. Entries and labels are written
+  /// verbatim, so they may contain LaTeX markup. Labels become an extra first row
+  /// and column inside the delimiters.
+  pub fn to_latex(&self, environment: LatexEnvironment) -> String {
+    let name = match environment {
+      LatexEnvironment::Pmatrix => "pmatrix",
+      LatexEnvironment::Bmatrix => "bmatrix",
+    };
+    let rows = self.cells(|s| s.to_string()).iter().map(|row| row.join(" & ")).collect::<Vec<_>>();
+    let mut text = format!("\\begin{{{}}}\n", name);
+    if !rows.is_empty() {
+      text += &format!("  {}\n", rows.join(" \\\\\n  "));
+    }
+    text + &format!("\\end{{{}}}", name)
+  }
+
+  /// Returns the matrix as a Markdown table with right-aligned columns. Since a
+  /// table needs a header, the header cells are empty without column labels.
+  pub fn to_markdown(&self) -> String {
+    let mut cells = self.cells(|s| s.replace('|', "\\|"));
+    if self.col_labels.is_none() {
+      let n_cols = self.matrix.n_cols + self.row_labels.as_ref().map_or(0, |_| 1);
+      cells.insert(0, vec![String::new(); n_cols]);
+    }
+    let widths = (0..cells[0].len())
+      .map(|k| cells.iter().map(|row| row[k].chars().count()).max().unwrap_or(0).max(3))
+      .collect::<Vec<usize>>();
+    let line = |row: &[String]| {
+      let row = row.iter().zip(&widths).map(|(cell, &width)| format!("{:>w$}", cell, w = width));
+      format!("| {} |", row.collect::<Vec<String>>().join(" | "))
+    };
+    let rule = widths.iter().map(|&width| format!("{}:", "-".repeat(width - 1)));
+    let mut lines = vec![line(&cells[0]), format!("| {} |", rule.collect::<Vec<_>>().join(" | "))];
+    lines.extend(cells[1..].iter().map(|row| line(row)));
+    lines.join("\n")
+  }
+
+  /// Returns the matrix as an HTML table. Labels are written as header cells, and
+  /// all text is escaped.
+  pub fn to_html(&self) -> String {
+    let cells = self.cells(escape_html);
+    let tag = |cell: &str, header: bool| {
+      let tag = if header { "th" } else { "td" };
+      format!("<{}>{}</{}>", tag, cell, tag)
+    };
+    let offset = self.col_labels.as_ref().map_or(0, |_| 1);
+    let row_labels = self.row_labels.is_some();
+    let mut text = String::from("<table>\n");
+    if self.col_labels.is_some() {
+      let header = cells[0].iter().map(|cell| tag(cell, true)).collect::<String>();
+      text += &format!("  <thead>\n    <tr>{}</tr>\n  </thead>\n", header);
+    }
+    text += "  <tbody>\n";
+    for row in &cells[offset..] {
+      let row = row.iter().enumerate().map(|(k, cell)| tag(cell, row_labels && k == 0));
+      text += &format!("    <tr>{}</tr>\n", row.collect::<String>());
+    }
+    text + "  </tbody>\n</table>"
+  }
+}
+
+fn escape_html(s: &str) -> String {
+  let mut escaped = String::with_capacity(s.len());
+  for c in s.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      _ => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+impl<T: Clone + PartialEq> Matrix<T> {
+  /// Returns an exporter which converts the entries to text by `format`.
+  pub fn table_with<'a, F: Fn(&T) -> String + 'a>(&'a self, format: F) -> MatrixTable<'a, T> {
+    MatrixTable {
+      matrix: self,
+      format: Box::new(format),
+      row_labels: None,
+      col_labels: None,
+    }
+  }
+}
+
+impl<T: Clone + PartialEq + Display> Matrix<T> {
+  /// Returns an exporter which converts the entries to text by `Display`.
+  pub fn table(&self) -> MatrixTable<'_, T> {
+    self.table_with(|e| e.to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_latex() {
+    let m = Matrix::new(vec![vec![1, -2], vec![3, 4]]);
+    let expected = "\\begin{pmatrix}\n  1 & -2 \\\\\n  3 & 4\n\\end{pmatrix}";
+    assert_eq!(m.table().to_latex(LatexEnvironment::Pmatrix), expected);
+    let m = Matrix::new(vec![vec![0.5, 0.25]]);
+    let text = m
+      .table_with(|e| format!("\\frac{{1}}{{{}}}", 1.0 / e))
+      .row_labels(vec!["x_1"])
+      .col_labels(vec!["a", "b"])
+      .to_latex(LatexEnvironment::Bmatrix);
+    let expected =
+      "\\begin{bmatrix}\n   & a & b \\\\\n  x_1 & \\frac{1}{2} & \\frac{1}{4}\n\\end{bmatrix}";
+    assert_eq!(text, expected);
+  }
+
+  #[test]
+  fn test_markdown() {
+    let m = Matrix::new(vec![vec![1.5, -20.0], vec![3.0, 4.0]]);
+    let text = m.table().format(|e| format!("{:.1}", e)).col_labels(vec!["a", "b|c"]);
+    let text = text.to_markdown();
+    assert_eq!(text, "|   a |  b\\|c |\n| --: | ----: |\n| 1.5 | -20.0 |\n| 3.0 |   4.0 |");
+    let text = m.table().row_labels(vec!["x", "y"]).to_markdown();
+    let expected = "|     |     |     |\n| --: | --: | --: |\n\
+      |   x | 1.5 | -20 |\n|   y |   3 |   4 |";
+    assert_eq!(text, expected);
+  }
+
+  #[test]
+  fn test_html() {
+    let m = Matrix::new(vec![vec![1, 2], vec![3, 4]]);
+    let text = m.table().row_labels(vec!["<r1>", "r2"]).col_labels(vec!["a", "b & c"]).to_html();
+    let expected = "<table>\n  <thead>\n    <tr><th></th><th>a</th><th>b &amp; c</th></tr>\n  \
+      </thead>\n  <tbody>\n    <tr><th>&lt;r1&gt;</th><td>1</td><td>2</td></tr>\n    \
+      <tr><th>r2</th><td>3</td><td>4</td></tr>\n  </tbody>\n</table>";
+    assert_eq!(text, expected);
+    let text = m.table().to_html();
+    assert!(text.starts_with("<table>\n  <tbody>\n    <tr><td>1</td><td>2</td></tr>\n"));
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_label_count() {
+    let m = Matrix::new(vec![vec![1, 2]]);
+    m.table().col_labels(vec!["a"]);
+  }
+}
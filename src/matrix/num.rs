@@ -0,0 +1,259 @@
+use core::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// Types having an additive identity.
+pub trait Zero: Sized + Add<Output = Self> {
+  /// Returns the additive identity.
+  fn zero() -> Self;
+
+  /// Returns `true` if `self` equals the additive identity.
+  fn is_zero(&self) -> bool;
+}
+
+/// Types having a multiplicative identity.
+pub trait One: Sized + Mul<Output = Self> {
+  /// Returns the multiplicative identity.
+  fn one() -> Self;
+}
+
+/// A scalar type supporting addition and multiplication with identities,
+/// like the unsigned integers. This is the least a scalar must provide
+/// for the matrix product.
+pub trait Semiring: Zero + One + Clone + PartialEq {}
+
+/// A semiring which additionally supports subtraction and negation,
+/// like the signed integers.
+pub trait Ring: Semiring + Sub<Output = Self> + Neg<Output = Self> {
+  /// Returns `true` if elimination may divide by `self`. This is the case for every
+  /// non-zero element, except in rings like the dual numbers where non-zero
+  /// elements can be zero divisors.
+  fn is_pivot(&self) -> bool {
+    !self.is_zero()
+  }
+}
+
+/// A totally ordered ring with truncating division and remainder, like `i64`.
+/// The checked operations detect overflow of fixed-width types; arbitrary
+/// precision types never fail.
+pub trait Integer: Ring + Ord + Div<Output = Self> + Rem<Output = Self> {
+  fn checked_add(&self, rhs: &Self) -> Option<Self>;
+  fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+  fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+
+  /// Converts to the nearest `f64`, possibly losing precision.
+  fn to_f64(&self) -> f64;
+
+  /// Returns the greatest common divisor, which is always non-negative. Panics if
+  /// it does not fit, as for `gcd(i64::MIN, 0)`.
+  fn gcd(&self, rhs: &Self) -> Self {
+    self.checked_gcd(rhs).unwrap_or_else(|| panic!("attempt to negate with overflow"))
+  }
+
+  /// Like `gcd`, but returns `None` if the greatest common divisor does not fit.
+  fn checked_gcd(&self, rhs: &Self) -> Option<Self> {
+    let mut a = self.clone();
+    let mut b = rhs.clone();
+    let minus_one = -Self::one();
+    while !b.is_zero() {
+      // The remainder by -1 is zero, but computing it overflows for `MIN`.
+      let r = if b == minus_one { Self::zero() } else { a % b.clone() };
+      a = b;
+      b = r;
+    }
+    if a < Self::zero() {
+      Self::zero().checked_sub(&a)
+    } else {
+      Some(a)
+    }
+  }
+}
+
+/// A ring in which every non-zero element can be inverted, like `f64`.
+/// Division by zero is not required to be meaningful.
+pub trait Field: Ring + Div<Output = Self> {}
+
+/// An ordered field approximating the real numbers, as required by
+/// numerical algorithms. Contrary to exact scalar types, these are always `Copy`.
+pub trait RealField: Field + PartialOrd + Copy {
+  /// Returns the square root of `self`.
+  fn sqrt(self) -> Self;
+
+  /// Returns the absolute value of `self`.
+  fn abs(self) -> Self;
+
+  /// Returns the difference between `1` and the next larger representable number.
+  fn epsilon() -> Self;
+}
+
+/// A real field which also provides the elementary functions, like `f64`.
+/// This is what is needed for differentiable and transcendental computations.
+pub trait Real: RealField {
+  /// Converts from `f64`, possibly losing precision.
+  fn from_f64(v: f64) -> Self;
+
+  /// Converts to the nearest `f64`, possibly losing precision.
+  fn to_f64(self) -> f64;
+
+  fn exp(self) -> Self;
+  fn ln(self) -> Self;
+  fn sin(self) -> Self;
+  fn cos(self) -> Self;
+  fn tan(self) -> Self;
+  fn tanh(self) -> Self;
+  fn powi(self, n: i32) -> Self;
+  fn powf(self, n: Self) -> Self;
+}
+
+macro_rules! semiring_impl {
+  ($($T:ty, $zero:expr, $one:expr);*) => {
+    $(
+      impl Zero for $T {
+        fn zero() -> $T {
+          $zero
+        }
+        fn is_zero(&self) -> bool {
+          *self == $zero
+        }
+      }
+      impl One for $T {
+        fn one() -> $T {
+          $one
+        }
+      }
+      impl Semiring for $T {}
+    )*
+  };
+}
+semiring_impl!(u8, 0, 1; u16, 0, 1; u32, 0, 1; u64, 0, 1; u128, 0, 1; usize, 0, 1);
+semiring_impl!(i8, 0, 1; i16, 0, 1; i32, 0, 1; i64, 0, 1; i128, 0, 1; isize, 0, 1);
+semiring_impl!(f32, 0.0, 1.0; f64, 0.0, 1.0);
+
+macro_rules! ring_impl {
+  ($($T:ty),*) => {
+    $(impl Ring for $T {})*
+  };
+}
+ring_impl!(i8, i16, i32, i64, i128, isize, f32, f64);
+
+macro_rules! integer_impl {
+  ($($T:ty),*) => {
+    $(
+      impl Integer for $T {
+        fn checked_add(&self, rhs: &$T) -> Option<$T> {
+          <$T>::checked_add(*self, *rhs)
+        }
+        fn checked_sub(&self, rhs: &$T) -> Option<$T> {
+          <$T>::checked_sub(*self, *rhs)
+        }
+        fn checked_mul(&self, rhs: &$T) -> Option<$T> {
+          <$T>::checked_mul(*self, *rhs)
+        }
+        fn to_f64(&self) -> f64 {
+          *self as f64
+        }
+      }
+    )*
+  };
+}
+integer_impl!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! real_field_impl {
+  ($($T:ty),*) => {
+    $(
+      impl Field for $T {}
+      impl RealField for $T {
+        fn sqrt(self) -> $T {
+          <$T>::sqrt(self)
+        }
+        fn abs(self) -> $T {
+          <$T>::abs(self)
+        }
+        fn epsilon() -> $T {
+          <$T>::EPSILON
+        }
+      }
+    )*
+  };
+}
+real_field_impl!(f32, f64);
+
+macro_rules! real_impl {
+  ($($T:ty),*) => {
+    $(
+      impl Real for $T {
+        fn from_f64(v: f64) -> $T {
+          v as $T
+        }
+        fn to_f64(self) -> f64 {
+          self as f64
+        }
+        fn exp(self) -> $T {
+          <$T>::exp(self)
+        }
+        fn ln(self) -> $T {
+          <$T>::ln(self)
+        }
+        fn sin(self) -> $T {
+          <$T>::sin(self)
+        }
+        fn cos(self) -> $T {
+          <$T>::cos(self)
+        }
+        fn tan(self) -> $T {
+          <$T>::tan(self)
+        }
+        fn tanh(self) -> $T {
+          <$T>::tanh(self)
+        }
+        fn powi(self, n: i32) -> $T {
+          <$T>::powi(self, n)
+        }
+        fn powf(self, n: $T) -> $T {
+          <$T>::powf(self, n)
+        }
+      }
+    )*
+  };
+}
+real_impl!(f32, f64);
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn sum_of_ones<T: Semiring>(n: usize) -> T {
+    (0..n).fold(T::zero(), |acc, _| acc + T::one())
+  }
+
+  #[test]
+  fn test_identities() {
+    assert_eq!(sum_of_ones::<u8>(3), 3);
+    assert_eq!(sum_of_ones::<i64>(4), 4);
+    assert_eq!(sum_of_ones::<f32>(2), 2.0);
+    assert!(0usize.is_zero());
+    assert!(!1i32.is_zero());
+  }
+
+  #[test]
+  fn test_integer() {
+    assert_eq!(Integer::gcd(&12i64, &-18), 6);
+    assert_eq!(Integer::gcd(&0i32, &0), 0);
+    assert_eq!(Integer::gcd(&i64::MIN, &-1), 1);
+    assert_eq!(Integer::checked_gcd(&i64::MIN, &6), Some(2));
+    assert_eq!(Integer::checked_gcd(&i64::MIN, &i64::MIN), None);
+    assert_eq!(Integer::checked_mul(&i64::MAX, &2), None);
+  }
+
+  #[test]
+  fn test_real_field() {
+    assert_eq!(RealField::sqrt(4.0f64), 2.0);
+    assert_eq!(RealField::abs(-1.5f32), 1.5);
+    assert_eq!(<f64 as RealField>::epsilon(), f64::EPSILON);
+  }
+
+  #[test]
+  fn test_real() {
+    assert_eq!(Real::exp(0.0f64), 1.0);
+    assert_eq!(<f32 as Real>::from_f64(0.5), 0.5f32);
+    assert_eq!(Real::powi(2.0f64, 3), 8.0);
+  }
+}
@@ -0,0 +1,408 @@
+use super::big_int::BigInt;
+use super::num::{Field, Integer, One, Ring, Semiring, Zero};
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+use core::str::FromStr;
+
+/// An exact fraction of two integers of type `I`, always kept in lowest terms
+/// with a positive denominator.
+///
+/// For fixed-width integers every operation detects overflow: the operators panic
+/// while the `checked_*` methods return `None`.
+///
+/// ```
+/// use matrix_algebra::{Matrix, Rational64};
+///
+/// let r = |n, d| Rational64::new(n, d);
+/// let m = Matrix::new(vec![vec![r(1, 1), r(1, 2)], vec![r(1, 2), r(1, 3)]]);
+/// let inv = m.inverse().unwrap();
+///
+/// assert_eq!(&m * &inv, Matrix::identity(2));
+/// assert_eq!(inv.get(0, 1), r(-6, 1));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Rational<I> {
+  numer: I,
+  denom: I,
+}
+
+/// A rational number backed by `i64`.
+pub type Rational64 = Rational<i64>;
+/// A rational number backed by `i128`.
+pub type Rational128 = Rational<i128>;
+/// An arbitrary-precision rational number.
+pub type BigRational = Rational<BigInt>;
+
+/// The error returned when parsing a `Rational` from a string fails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseRationalError;
+
+impl fmt::Display for ParseRationalError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "invalid rational number literal")
+  }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+fn overflow() -> ! {
+  panic!("rational arithmetic overflowed");
+}
+
+// Returns the floored quotient and the remainder, which lies in `0..d`, for a
+// positive `d`. Neither can overflow.
+fn div_rem_floor<I: Integer>(n: &I, d: &I) -> (I, I) {
+  let (q, r) = (n.clone() / d.clone(), n.clone() % d.clone());
+  if r < I::zero() {
+    (q - I::one(), r + d.clone())
+  } else {
+    (q, r)
+  }
+}
+
+impl<I: Integer> Rational<I> {
+  /// Creates the fraction `numer / denom` in lowest terms. Panics if `denom` is zero
+  /// or the normalization overflows.
+  pub fn new(numer: I, denom: I) -> Rational<I> {
+    Rational::checked_new(numer, denom).unwrap_or_else(|| overflow())
+  }
+
+  /// Like `new`, but returns `None` if the normalization overflows.
+  pub fn checked_new(numer: I, denom: I) -> Option<Rational<I>> {
+    if denom.is_zero() {
+      panic!("denominator of a rational number must not be zero");
+    }
+    let g = numer.checked_gcd(&denom)?;
+    let (mut numer, mut denom) = (numer / g.clone(), denom / g);
+    if denom < I::zero() {
+      numer = I::zero().checked_sub(&numer)?;
+      denom = I::zero().checked_sub(&denom)?;
+    }
+    Some(Rational { numer, denom })
+  }
+
+  /// Creates the fraction `n / 1`.
+  pub fn from_integer(n: I) -> Rational<I> {
+    Rational {
+      numer: n,
+      denom: I::one(),
+    }
+  }
+
+  /// The numerator in lowest terms.
+  pub fn numer(&self) -> &I {
+    &self.numer
+  }
+
+  /// The denominator in lowest terms, which is always positive.
+  pub fn denom(&self) -> &I {
+    &self.denom
+  }
+
+  /// Returns `true` if the denominator is one.
+  pub fn is_integer(&self) -> bool {
+    self.denom == I::one()
+  }
+
+  /// Returns the largest integer less than or equal to `self`.
+  pub fn floor(&self) -> I {
+    div_rem_floor(&self.numer, &self.denom).0
+  }
+
+  /// Returns the integer nearest to `self`, rounding halves up.
+  pub fn round(&self) -> I {
+    let (q, r) = div_rem_floor(&self.numer, &self.denom);
+    // The fractional part r / denom is at least a half.
+    if r >= self.denom.clone() - r.clone() {
+      q + I::one()
+    } else {
+      q
+    }
+  }
+
+  /// Converts to the nearest `f64`, possibly losing precision.
+  pub fn to_f64(&self) -> f64 {
+    self.numer.to_f64() / self.denom.to_f64()
+  }
+
+  /// Returns `1 / self`. Panics if `self` is zero.
+  pub fn recip(&self) -> Rational<I> {
+    Rational::new(self.denom.clone(), self.numer.clone())
+  }
+
+  pub fn checked_add(&self, rhs: &Rational<I>) -> Option<Rational<I>> {
+    let g = self.denom.checked_gcd(&rhs.denom)?;
+    let l = self.denom.clone() / g.clone();
+    let r = rhs.denom.clone() / g;
+    let numer = self
+      .numer
+      .checked_mul(&r)?
+      .checked_add(&rhs.numer.checked_mul(&l)?)?;
+    Rational::checked_new(numer, self.denom.checked_mul(&r)?)
+  }
+
+  pub fn checked_sub(&self, rhs: &Rational<I>) -> Option<Rational<I>> {
+    let neg = Rational {
+      numer: I::zero().checked_sub(&rhs.numer)?,
+      denom: rhs.denom.clone(),
+    };
+    self.checked_add(&neg)
+  }
+
+  pub fn checked_mul(&self, rhs: &Rational<I>) -> Option<Rational<I>> {
+    let g1 = self.numer.checked_gcd(&rhs.denom)?;
+    let g2 = rhs.numer.checked_gcd(&self.denom)?;
+    if g1.is_zero() || g2.is_zero() {
+      return Some(Rational::zero());
+    }
+    let numer = (self.numer.clone() / g1.clone()).checked_mul(&(rhs.numer.clone() / g2.clone()))?;
+    let denom = (self.denom.clone() / g2).checked_mul(&(rhs.denom.clone() / g1))?;
+    Rational::checked_new(numer, denom)
+  }
+
+  /// Returns `None` on overflow. Panics if `rhs` is zero.
+  pub fn checked_div(&self, rhs: &Rational<I>) -> Option<Rational<I>> {
+    if rhs.numer.is_zero() {
+      panic!("attempt to divide by zero");
+    }
+    let inv = Rational::checked_new(rhs.denom.clone(), rhs.numer.clone())?;
+    self.checked_mul(&inv)
+  }
+}
+
+impl<I: Integer> From<I> for Rational<I> {
+  fn from(n: I) -> Rational<I> {
+    Rational::from_integer(n)
+  }
+}
+
+impl<I: Integer> PartialOrd for Rational<I> {
+  fn partial_cmp(&self, other: &Rational<I>) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+// Compares the integer parts, and if these agree, the reciprocals of the
+// fractional parts in reverse order, as in the continued fraction expansions.
+// Contrary to cross-multiplying, this never overflows.
+impl<I: Integer> Ord for Rational<I> {
+  fn cmp(&self, other: &Rational<I>) -> Ordering {
+    let (mut a, mut b) = (self.numer.clone(), self.denom.clone());
+    let (mut c, mut d) = (other.numer.clone(), other.denom.clone());
+    let mut reverse = false;
+    loop {
+      let (q1, r1) = div_rem_floor(&a, &b);
+      let (q2, r2) = div_rem_floor(&c, &d);
+      let ordering = match q1.cmp(&q2) {
+        Ordering::Equal => match (r1.is_zero(), r2.is_zero()) {
+          (true, true) => Ordering::Equal,
+          (true, false) => Ordering::Less,
+          (false, true) => Ordering::Greater,
+          (false, false) => {
+            (a, b, c, d) = (b, r1, d, r2);
+            reverse = !reverse;
+            continue;
+          }
+        },
+        ordering => ordering,
+      };
+      return if reverse { ordering.reverse() } else { ordering };
+    }
+  }
+}
+
+macro_rules! rational_op {
+  ($Trait:ident, $method:ident, $checked:ident) => {
+    impl<I: Integer> $Trait<Rational<I>> for Rational<I> {
+      type Output = Rational<I>;
+      fn $method(self, rhs: Rational<I>) -> Rational<I> {
+        self.$checked(&rhs).unwrap_or_else(|| overflow())
+      }
+    }
+    impl<I: Integer> $Trait<&Rational<I>> for Rational<I> {
+      type Output = Rational<I>;
+      fn $method(self, rhs: &Rational<I>) -> Rational<I> {
+        self.$checked(rhs).unwrap_or_else(|| overflow())
+      }
+    }
+    impl<I: Integer> $Trait<Rational<I>> for &Rational<I> {
+      type Output = Rational<I>;
+      fn $method(self, rhs: Rational<I>) -> Rational<I> {
+        self.$checked(&rhs).unwrap_or_else(|| overflow())
+      }
+    }
+    impl<I: Integer> $Trait<&Rational<I>> for &Rational<I> {
+      type Output = Rational<I>;
+      fn $method(self, rhs: &Rational<I>) -> Rational<I> {
+        self.$checked(rhs).unwrap_or_else(|| overflow())
+      }
+    }
+  };
+}
+rational_op!(Add, add, checked_add);
+rational_op!(Sub, sub, checked_sub);
+rational_op!(Mul, mul, checked_mul);
+rational_op!(Div, div, checked_div);
+
+impl<I: Integer> Neg for Rational<I> {
+  type Output = Rational<I>;
+  fn neg(self) -> Rational<I> {
+    Rational {
+      numer: I::zero().checked_sub(&self.numer).unwrap_or_else(|| overflow()),
+      denom: self.denom,
+    }
+  }
+}
+
+impl<I: Integer> Neg for &Rational<I> {
+  type Output = Rational<I>;
+  fn neg(self) -> Rational<I> {
+    -self.clone()
+  }
+}
+
+impl<I: Integer> Zero for Rational<I> {
+  fn zero() -> Rational<I> {
+    Rational::from_integer(I::zero())
+  }
+  fn is_zero(&self) -> bool {
+    self.numer.is_zero()
+  }
+}
+
+impl<I: Integer> One for Rational<I> {
+  fn one() -> Rational<I> {
+    Rational::from_integer(I::one())
+  }
+}
+
+impl<I: Integer> Semiring for Rational<I> {}
+impl<I: Integer> Ring for Rational<I> {}
+impl<I: Integer> Field for Rational<I> {}
+
+impl<I: Integer + fmt::Display> fmt::Display for Rational<I> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.is_integer() {
+      f.pad(&self.numer.to_string())
+    } else {
+      f.pad(&format!("{}/{}", self.numer, self.denom))
+    }
+  }
+}
+
+/// Accepts integers (`"-3"`), fractions (`"3/4"`) and decimals (`"0.25"`).
+impl<I: Integer + FromStr> FromStr for Rational<I> {
+  type Err = ParseRationalError;
+  fn from_str(s: &str) -> Result<Rational<I>, ParseRationalError> {
+    let parse = |t: &str| t.trim().parse::<I>().map_err(|_| ParseRationalError);
+    if let Some(pos) = s.find('/') {
+      let denom = parse(&s[pos + 1..])?;
+      if denom.is_zero() {
+        return Err(ParseRationalError);
+      }
+      return Rational::checked_new(parse(&s[..pos])?, denom).ok_or(ParseRationalError);
+    }
+    if let Some(pos) = s.find('.') {
+      let frac = s[pos + 1..].trim();
+      if !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseRationalError);
+      }
+      let digits = format!("{}{}", &s[..pos].trim(), frac);
+      let ten = parse("10")?;
+      let mut denom = I::one();
+      for _ in 0..frac.len() {
+        denom = denom.checked_mul(&ten).ok_or(ParseRationalError)?;
+      }
+      return Rational::checked_new(parse(&digits)?, denom).ok_or(ParseRationalError);
+    }
+    Ok(Rational::from_integer(parse(s)?))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_normalization() {
+    let r = Rational64::new(6, -4);
+    assert_eq!((*r.numer(), *r.denom()), (-3, 2));
+    assert_eq!(Rational64::new(0, -5), Rational64::zero());
+    assert_eq!(Rational64::checked_new(i64::MIN, 2), Some(Rational64::new(i64::MIN / 2, 1)));
+    assert_eq!(Rational64::checked_new(i64::MIN, i64::MIN), None);
+    assert_eq!(Rational64::checked_new(i64::MIN, -1), None);
+  }
+
+  #[test]
+  fn test_arithmetic() {
+    let a = Rational64::new(1, 6);
+    let b = Rational64::new(1, 3);
+    assert_eq!(a + b, Rational64::new(1, 2));
+    assert_eq!(a - b, Rational64::new(-1, 6));
+    assert_eq!(a * b, Rational64::new(1, 18));
+    assert_eq!(a / b, Rational64::new(1, 2));
+    assert_eq!(-a, Rational64::new(-1, 6));
+    assert!(a < b);
+  }
+
+  #[test]
+  fn test_overflow_detection() {
+    let big = Rational64::new(i64::MAX, 1);
+    assert_eq!(big.checked_add(&Rational64::one()), None);
+    assert_eq!(big.checked_mul(&Rational64::new(2, 1)), None);
+    assert_eq!(big.checked_mul(&Rational64::new(1, 2)), Some(Rational64::new(i64::MAX, 2)));
+  }
+
+  #[test]
+  #[should_panic(expected = "overflowed")]
+  fn test_overflow_panics() {
+    let _ = Rational128::new(i128::MAX, 1) + Rational128::one();
+  }
+
+  #[test]
+  fn test_big_rational() {
+    let mut r = BigRational::one();
+    for _ in 0..100 {
+      r = r * BigRational::new(BigInt::from(3), BigInt::from(2));
+    }
+    assert_eq!(r.numer(), &BigInt::from(3).pow(100));
+    assert_eq!(r.denom(), &BigInt::from(2).pow(100));
+  }
+
+  #[test]
+  fn test_floor_round() {
+    assert_eq!(Rational64::new(-7, 2).floor(), -4);
+    assert_eq!(Rational64::new(7, 2).floor(), 3);
+    assert_eq!(Rational64::new(7, 2).round(), 4);
+    assert_eq!(Rational64::new(-7, 3).round(), -2);
+    assert_eq!(Rational64::new(-5, 2).round(), -2);
+    assert_eq!(Rational64::new(i64::MAX, 1).round(), i64::MAX);
+    assert_eq!(Rational64::new(i64::MAX, 2).round(), 1 << 62);
+    assert_eq!(Rational64::new(i64::MIN, 1).round(), i64::MIN);
+    assert_eq!(Rational64::new(i64::MIN + 1, 2).round(), -(1 << 62) + 1);
+    assert_eq!(Rational64::new(i64::MIN, 3).floor(), i64::MIN / 3 - 1);
+  }
+
+  #[test]
+  fn test_order_extremes() {
+    let max = Rational64::new(i64::MAX, 1);
+    let min = Rational64::new(i64::MIN, 1);
+    assert!(Rational64::new(i64::MAX, 2) > Rational64::new(-i64::MAX, 3));
+    assert!(min < max && min < Rational64::new(i64::MIN + 1, 1));
+    assert!(Rational64::new(i64::MAX, i64::MAX - 1) < Rational64::new(i64::MAX - 1, i64::MAX - 2));
+    assert!(Rational64::new(1, i64::MAX) < Rational64::new(1, i64::MAX - 1));
+    assert!(Rational64::new(-1, i64::MAX) > Rational64::new(-1, i64::MAX - 1));
+    let third = Rational64::new(i64::MAX / 3, i64::MAX);
+    assert_eq!(third.cmp(&third), Ordering::Equal);
+  }
+
+  #[test]
+  fn test_parse_display() {
+    assert_eq!("3/-6".parse::<Rational64>(), Ok(Rational64::new(-1, 2)));
+    assert_eq!("-1.25".parse::<Rational64>(), Ok(Rational64::new(-5, 4)));
+    assert_eq!("7".parse::<BigRational>().unwrap().to_string(), "7");
+    assert_eq!(Rational64::new(2, 4).to_string(), "1/2");
+    assert!("1/0".parse::<Rational64>().is_err());
+  }
+}
@@ -1,4 +1,125 @@
+use super::num::{Field, One, Real, RealField, Ring, Semiring, Zero};
+use core::ops::{Add, Div, Neg, Sub};
+
 /// The struct `Scalar` is intended to wrap scalar types like `f64`
 /// in order to allow the implementation of arithmetic operations between matrix types.
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, PartialOrd, Debug, Clone, Copy)]
 pub struct Scalar<T>(pub T);
+
+macro_rules! scalar_op {
+  ($Trait:ident, $method:ident, $LHS:ty, $RHS:ty, $ScalarType:tt ) => {
+    impl<$ScalarType: $Trait<Output = $ScalarType> + Clone> $Trait<$RHS> for $LHS {
+      type Output = Scalar<$ScalarType>;
+      fn $method(self, rhs: $RHS) -> Self::Output {
+        return Scalar(self.0.clone().$method(rhs.0.clone()));
+      }
+    }
+  };
+}
+scalar_op!(Add, add, Scalar<T>, Scalar<T>, T);
+scalar_op!(Add, add, &Scalar<T>, Scalar<T>, T);
+scalar_op!(Add, add, &Scalar<T>, &Scalar<T>, T);
+scalar_op!(Add, add, Scalar<T>, &Scalar<T>, T);
+scalar_op!(Sub, sub, Scalar<T>, Scalar<T>, T);
+scalar_op!(Sub, sub, &Scalar<T>, Scalar<T>, T);
+scalar_op!(Sub, sub, &Scalar<T>, &Scalar<T>, T);
+scalar_op!(Sub, sub, Scalar<T>, &Scalar<T>, T);
+scalar_op!(Div, div, Scalar<T>, Scalar<T>, T);
+scalar_op!(Div, div, &Scalar<T>, Scalar<T>, T);
+scalar_op!(Div, div, &Scalar<T>, &Scalar<T>, T);
+scalar_op!(Div, div, Scalar<T>, &Scalar<T>, T);
+
+impl<T: Neg<Output = T>> Neg for Scalar<T> {
+  type Output = Scalar<T>;
+  fn neg(self) -> Self::Output {
+    Scalar(-self.0)
+  }
+}
+
+impl<T: Zero + Clone> Zero for Scalar<T> {
+  fn zero() -> Scalar<T> {
+    Scalar(T::zero())
+  }
+  fn is_zero(&self) -> bool {
+    self.0.is_zero()
+  }
+}
+
+impl<T: One + Clone> One for Scalar<T> {
+  fn one() -> Scalar<T> {
+    Scalar(T::one())
+  }
+}
+
+impl<T: Semiring> Semiring for Scalar<T> {}
+impl<T: Ring> Ring for Scalar<T> {
+  fn is_pivot(&self) -> bool {
+    self.0.is_pivot()
+  }
+}
+impl<T: Field> Field for Scalar<T> {}
+
+impl<T: RealField> RealField for Scalar<T> {
+  fn sqrt(self) -> Scalar<T> {
+    Scalar(self.0.sqrt())
+  }
+  fn abs(self) -> Scalar<T> {
+    Scalar(self.0.abs())
+  }
+  fn epsilon() -> Scalar<T> {
+    Scalar(T::epsilon())
+  }
+}
+
+impl<T: Real> Real for Scalar<T> {
+  fn from_f64(v: f64) -> Scalar<T> {
+    Scalar(T::from_f64(v))
+  }
+  fn to_f64(self) -> f64 {
+    self.0.to_f64()
+  }
+  fn exp(self) -> Scalar<T> {
+    Scalar(self.0.exp())
+  }
+  fn ln(self) -> Scalar<T> {
+    Scalar(self.0.ln())
+  }
+  fn sin(self) -> Scalar<T> {
+    Scalar(self.0.sin())
+  }
+  fn cos(self) -> Scalar<T> {
+    Scalar(self.0.cos())
+  }
+  fn tan(self) -> Scalar<T> {
+    Scalar(self.0.tan())
+  }
+  fn tanh(self) -> Scalar<T> {
+    Scalar(self.0.tanh())
+  }
+  fn powi(self, n: i32) -> Scalar<T> {
+    Scalar(self.0.powi(n))
+  }
+  fn powf(self, n: Scalar<T>) -> Scalar<T> {
+    Scalar(self.0.powf(n.0))
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::op_ref)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_scalar_ops() {
+    assert_eq!(Scalar(1) + Scalar(2), Scalar(3));
+    assert_eq!(&Scalar(1) - &Scalar(2), Scalar(-1));
+    assert_eq!(Scalar(6.0) / &Scalar(2.0), Scalar(3.0));
+    assert_eq!(-Scalar(2), Scalar(-2));
+  }
+
+  #[test]
+  fn test_scalar_real_field() {
+    assert_eq!(Scalar::<f64>::one() + Scalar::zero(), Scalar(1.0));
+    assert_eq!(Scalar(-4.0).abs().sqrt(), Scalar(2.0));
+  }
+}
@@ -0,0 +1,309 @@
+use super::num::{One, Semiring, Zero};
+use core::ops::{Add, Mul};
+
+/// Scalar types having a value which represents positive infinity. For the
+/// integer types this is the largest value.
+pub trait Infinity: Copy + PartialOrd {
+  fn infinity() -> Self;
+}
+
+/// Scalar types having a value which represents negative infinity. For the
+/// signed integer types this is the smallest value. Unsigned types have no such
+/// value besides zero, which is already the multiplicative identity of `MaxPlus`.
+pub trait NegInfinity: Infinity {
+  fn neg_infinity() -> Self;
+}
+
+macro_rules! infinity_impl {
+  ($($T:ty, $inf:expr);*) => {
+    $(
+      impl Infinity for $T {
+        fn infinity() -> $T {
+          $inf
+        }
+      }
+    )*
+  };
+}
+infinity_impl!(f32, f32::INFINITY; f64, f64::INFINITY; i8, i8::MAX; i16, i16::MAX; i32, i32::MAX);
+infinity_impl!(i64, i64::MAX; i128, i128::MAX; isize, isize::MAX; u8, u8::MAX; u16, u16::MAX);
+infinity_impl!(u32, u32::MAX; u64, u64::MAX; u128, u128::MAX; usize, usize::MAX);
+
+macro_rules! neg_infinity_impl {
+  ($($T:ty, $neg_inf:expr);*) => {
+    $(
+      impl NegInfinity for $T {
+        fn neg_infinity() -> $T {
+          $neg_inf
+        }
+      }
+    )*
+  };
+}
+neg_infinity_impl!(f32, f32::NEG_INFINITY; f64, f64::NEG_INFINITY; i8, i8::MIN; i16, i16::MIN);
+neg_infinity_impl!(i32, i32::MIN; i64, i64::MIN; i128, i128::MIN; isize, isize::MIN);
+
+/// A semiring structure imposed on the scalar type `T` by wrapping it.
+/// This allows `Matrix::semiring_mul` to multiply plain matrices over the semiring.
+pub trait SemiringWrapper<T>: Semiring + From<T> {
+  /// Returns the wrapped scalar.
+  fn into_inner(self) -> T;
+}
+
+macro_rules! semiring_wrapper {
+  ($(#[$doc:meta])* $Name:ident, $($bounds:tt)*) => {
+    $(#[$doc])*
+    #[derive(PartialEq, PartialOrd, Debug, Clone, Copy)]
+    pub struct $Name<T>(pub T);
+
+    impl<T> From<T> for $Name<T> {
+      fn from(t: T) -> $Name<T> {
+        $Name(t)
+      }
+    }
+
+    impl<T: $($bounds)*> Semiring for $Name<T> {}
+
+    impl<T: $($bounds)*> SemiringWrapper<T> for $Name<T> {
+      fn into_inner(self) -> T {
+        self.0
+      }
+    }
+  };
+}
+
+semiring_wrapper!(
+  /// The tropical semiring using `min` as addition and `+` as multiplication.
+  /// Its zero is infinity. Matrix products compute shortest paths.
+  MinPlus,
+  Infinity + Semiring
+);
+
+semiring_wrapper!(
+  /// The semiring using `max` as addition and `+` as multiplication.
+  /// Its zero is negative infinity. Matrix products compute longest paths.
+  MaxPlus,
+  NegInfinity + Semiring
+);
+
+semiring_wrapper!(
+  /// The semiring using `max` as addition and `*` as multiplication on non-negative
+  /// numbers. Matrix products compute most reliable paths.
+  MaxTimes,
+  PartialOrd + Semiring
+);
+
+impl<T: Infinity + Semiring> Add for MinPlus<T> {
+  type Output = MinPlus<T>;
+  // Addition in this semiring is the minimum.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn add(self, rhs: MinPlus<T>) -> MinPlus<T> {
+    if rhs.0 < self.0 {
+      rhs
+    } else {
+      self
+    }
+  }
+}
+
+impl<T: Infinity + Semiring> Mul for MinPlus<T> {
+  type Output = MinPlus<T>;
+  // Multiplication in this semiring is the sum, with infinity being absorbing.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn mul(self, rhs: MinPlus<T>) -> MinPlus<T> {
+    if self.0 == T::infinity() || rhs.0 == T::infinity() {
+      MinPlus(T::infinity())
+    } else {
+      MinPlus(self.0 + rhs.0)
+    }
+  }
+}
+
+impl<T: Infinity + Semiring> Zero for MinPlus<T> {
+  fn zero() -> MinPlus<T> {
+    MinPlus(T::infinity())
+  }
+  fn is_zero(&self) -> bool {
+    self.0 == T::infinity()
+  }
+}
+
+impl<T: Infinity + Semiring> One for MinPlus<T> {
+  fn one() -> MinPlus<T> {
+    MinPlus(T::zero())
+  }
+}
+
+impl<T: NegInfinity + Semiring> Add for MaxPlus<T> {
+  type Output = MaxPlus<T>;
+  // Addition in this semiring is the maximum.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn add(self, rhs: MaxPlus<T>) -> MaxPlus<T> {
+    if rhs.0 > self.0 {
+      rhs
+    } else {
+      self
+    }
+  }
+}
+
+impl<T: NegInfinity + Semiring> Mul for MaxPlus<T> {
+  type Output = MaxPlus<T>;
+  // Multiplication in this semiring is the sum, with negative infinity being absorbing.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn mul(self, rhs: MaxPlus<T>) -> MaxPlus<T> {
+    if self.0 == T::neg_infinity() || rhs.0 == T::neg_infinity() {
+      MaxPlus(T::neg_infinity())
+    } else {
+      MaxPlus(self.0 + rhs.0)
+    }
+  }
+}
+
+impl<T: NegInfinity + Semiring> Zero for MaxPlus<T> {
+  fn zero() -> MaxPlus<T> {
+    MaxPlus(T::neg_infinity())
+  }
+  fn is_zero(&self) -> bool {
+    self.0 == T::neg_infinity()
+  }
+}
+
+impl<T: NegInfinity + Semiring> One for MaxPlus<T> {
+  fn one() -> MaxPlus<T> {
+    MaxPlus(T::zero())
+  }
+}
+
+impl<T: PartialOrd + Semiring> Add for MaxTimes<T> {
+  type Output = MaxTimes<T>;
+  // Addition in this semiring is the maximum.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn add(self, rhs: MaxTimes<T>) -> MaxTimes<T> {
+    if rhs.0 > self.0 {
+      rhs
+    } else {
+      self
+    }
+  }
+}
+
+impl<T: PartialOrd + Semiring> Mul for MaxTimes<T> {
+  type Output = MaxTimes<T>;
+  fn mul(self, rhs: MaxTimes<T>) -> MaxTimes<T> {
+    MaxTimes(self.0 * rhs.0)
+  }
+}
+
+impl<T: PartialOrd + Semiring> Zero for MaxTimes<T> {
+  fn zero() -> MaxTimes<T> {
+    MaxTimes(T::zero())
+  }
+  fn is_zero(&self) -> bool {
+    self.0.is_zero()
+  }
+}
+
+impl<T: PartialOrd + Semiring> One for MaxTimes<T> {
+  fn one() -> MaxTimes<T> {
+    MaxTimes(T::one())
+  }
+}
+
+/// The Boolean semiring using `or` as addition and `and` as multiplication.
+/// Matrix products compute reachability.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub struct Boolean(pub bool);
+
+impl From<bool> for Boolean {
+  fn from(b: bool) -> Boolean {
+    Boolean(b)
+  }
+}
+
+impl Add for Boolean {
+  type Output = Boolean;
+  // Addition in this semiring is the disjunction.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn add(self, rhs: Boolean) -> Boolean {
+    Boolean(self.0 || rhs.0)
+  }
+}
+
+impl Mul for Boolean {
+  type Output = Boolean;
+  // Multiplication in this semiring is the conjunction.
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn mul(self, rhs: Boolean) -> Boolean {
+    Boolean(self.0 && rhs.0)
+  }
+}
+
+impl Zero for Boolean {
+  fn zero() -> Boolean {
+    Boolean(false)
+  }
+  fn is_zero(&self) -> bool {
+    !self.0
+  }
+}
+
+impl One for Boolean {
+  fn one() -> Boolean {
+    Boolean(true)
+  }
+}
+
+impl Semiring for Boolean {}
+
+impl SemiringWrapper<bool> for Boolean {
+  fn into_inner(self) -> bool {
+    self.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::Matrix;
+  use super::*;
+
+  #[test]
+  fn test_min_plus() {
+    assert_eq!(MinPlus(3.0) + MinPlus(2.0), MinPlus(2.0));
+    assert_eq!(MinPlus(3) * MinPlus(2), MinPlus(5));
+    assert_eq!(MinPlus(3) * MinPlus::zero(), MinPlus::zero());
+    assert_eq!(MinPlus(3.0) * MinPlus::one(), MinPlus(3.0));
+  }
+
+  #[test]
+  fn test_max_plus() {
+    assert_eq!(MaxPlus(3) + MaxPlus(2), MaxPlus(3));
+    assert_eq!(MaxPlus(-3.0) * MaxPlus(2.0), MaxPlus(-1.0));
+    assert_eq!(MaxPlus(3) + MaxPlus::zero(), MaxPlus(3));
+    assert_ne!(MaxPlus::<i32>::zero(), MaxPlus::one());
+  }
+
+  #[test]
+  fn test_max_plus_matrix() {
+    let m = Matrix::new(vec![vec![0, 5], vec![i32::MIN, 0]]);
+    assert_eq!(m.semiring_mul::<MaxPlus<i32>>(&m), m);
+    let identity = Matrix::new(vec![vec![0, i32::MIN], vec![i32::MIN, 0]]);
+    assert_eq!(m.semiring_pow::<MaxPlus<i32>>(0), identity);
+    let min_plus = Matrix::new(vec![vec![0u32, 5], vec![u32::MAX, 0]]);
+    let identity = Matrix::new(vec![vec![0, u32::MAX], vec![u32::MAX, 0]]);
+    assert_eq!(min_plus.semiring_pow::<MinPlus<u32>>(0), identity);
+  }
+
+  #[test]
+  fn test_max_times() {
+    assert_eq!(MaxTimes(0.5) + MaxTimes(0.25), MaxTimes(0.5));
+    assert_eq!(MaxTimes(0.5) * MaxTimes(0.25), MaxTimes(0.125));
+  }
+
+  #[test]
+  fn test_boolean() {
+    assert_eq!(Boolean(true) + Boolean(false), Boolean(true));
+    assert_eq!(Boolean(true) * Boolean(false), Boolean(false));
+    assert!(Boolean::from(true).into_inner());
+  }
+}
@@ -0,0 +1,18 @@
+//! Building blocks for training small neural networks on `Matrix` data.
+//!
+//! Samples are stored as the rows of a matrix. A `Sequential` network of `Dense`
+//! layers maps a batch of inputs to a batch of outputs, a loss function yields
+//! the gradient with respect to these outputs, and after `backward` an
+//! `Optimizer` updates the parameters in place.
+mod activation;
+mod batch;
+mod init;
+mod layer;
+mod loss;
+mod optim;
+pub use activation::Activation;
+pub use batch::{batches, shuffled_batches, Batches};
+pub use init::{he_normal, normal, uniform, xavier_uniform, Rng};
+pub use layer::{Dense, Sequential};
+pub use loss::{cross_entropy, log_softmax, mse, one_hot, softmax};
+pub use optim::{Adam, Optimizer, Sgd};
@@ -0,0 +1,98 @@
+use crate::matrix::func::Func;
+use crate::matrix::num::Real;
+
+/// The activation functions applied element-wise by a layer.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Activation<T> {
+  Identity,
+  Relu,
+  LeakyRelu(T),
+  Sigmoid,
+  Tanh,
+}
+
+impl<T: Real> Activation<T> {
+  /// Evaluates the activation function at `x`.
+  pub fn apply(&self, x: T) -> T {
+    match *self {
+      Activation::Identity => x,
+      Activation::Relu => {
+        if x > T::zero() {
+          x
+        } else {
+          T::zero()
+        }
+      }
+      Activation::LeakyRelu(alpha) => {
+        if x > T::zero() {
+          x
+        } else {
+          alpha * x
+        }
+      }
+      Activation::Sigmoid => T::one() / (T::one() + (-x).exp()),
+      Activation::Tanh => x.tanh(),
+    }
+  }
+
+  /// Evaluates the derivative of the activation function at `x`.
+  pub fn derivative(&self, x: T) -> T {
+    match *self {
+      Activation::Identity => T::one(),
+      Activation::Relu => {
+        if x > T::zero() {
+          T::one()
+        } else {
+          T::zero()
+        }
+      }
+      Activation::LeakyRelu(alpha) => {
+        if x > T::zero() {
+          T::one()
+        } else {
+          alpha
+        }
+      }
+      Activation::Sigmoid => {
+        let s = self.apply(x);
+        s * (T::one() - s)
+      }
+      Activation::Tanh => {
+        let t = x.tanh();
+        T::one() - t * t
+      }
+    }
+  }
+}
+
+impl<T: Real + 'static> Activation<T> {
+  /// Returns the activation function, to be applied as `f % m`.
+  pub fn func(&self) -> Func<T> {
+    let a = *self;
+    Func::new(move |x| a.apply(x))
+  }
+
+  /// Returns the derivative of the activation function, to be applied as `df % m`.
+  pub fn derivative_func(&self) -> Func<T> {
+    let a = *self;
+    Func::new(move |x| a.derivative(x))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::Matrix;
+
+  #[test]
+  fn test_activation() {
+    assert_eq!(Activation::Relu.apply(-2.0f32), 0.0);
+    assert_eq!(Activation::LeakyRelu(0.1f32).apply(-2.0), -0.2);
+    assert_eq!(Activation::Sigmoid.apply(0.0f64), 0.5);
+    assert_eq!(Activation::Sigmoid.derivative(0.0f64), 0.25);
+    assert_eq!(Activation::Tanh.derivative(0.0f64), 1.0);
+    let m = Matrix::new(vec![vec![-1.0f32, 2.0]]);
+    assert_eq!(Activation::Relu.func() % &m, Matrix::new(vec![vec![0.0, 2.0]]));
+    assert_eq!(Activation::Relu.derivative_func() % &m, Matrix::new(vec![vec![0.0, 1.0]]));
+  }
+}
@@ -0,0 +1,87 @@
+use super::init::Rng;
+use crate::Matrix;
+
+/// An iterator over mini-batches of corresponding rows of two matrices,
+/// usually the inputs and the targets. The last batch may be smaller.
+pub struct Batches<'a, T: Clone + PartialEq> {
+  x: &'a Matrix<T>,
+  y: &'a Matrix<T>,
+  order: Vec<usize>,
+  batch_size: usize,
+  pos: usize,
+}
+
+impl<'a, T: Clone + PartialEq> Iterator for Batches<'a, T> {
+  type Item = (Matrix<T>, Matrix<T>);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.pos >= self.order.len() {
+      return None;
+    }
+    let end = usize::min(self.pos + self.batch_size, self.order.len());
+    let rows = &self.order[self.pos..end];
+    self.pos = end;
+    Some((self.x.select_rows(rows), self.y.select_rows(rows)))
+  }
+}
+
+/// Iterates over mini-batches of `batch_size` rows in their original order.
+pub fn batches<'a, T: Clone + PartialEq>(
+  x: &'a Matrix<T>,
+  y: &'a Matrix<T>,
+  batch_size: usize,
+) -> Batches<'a, T> {
+  if x.n_rows() != y.n_rows() {
+    panic!("dimensions do not match!");
+  }
+  if batch_size == 0 {
+    panic!("batch size must be positive!");
+  }
+  Batches {
+    x,
+    y,
+    order: (0..x.n_rows()).collect(),
+    batch_size,
+    pos: 0,
+  }
+}
+
+/// Iterates over mini-batches of `batch_size` rows in a random order.
+pub fn shuffled_batches<'a, T: Clone + PartialEq>(
+  x: &'a Matrix<T>,
+  y: &'a Matrix<T>,
+  batch_size: usize,
+  rng: &mut Rng,
+) -> Batches<'a, T> {
+  let mut b = batches(x, y, batch_size);
+  rng.shuffle(&mut b.order);
+  b
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_batches() {
+    let x = Matrix::new(vec![vec![0, 0], vec![1, 1], vec![2, 2]]);
+    let y = Matrix::new(vec![vec![0], vec![1], vec![2]]);
+    let b = batches(&x, &y, 2).collect::<Vec<_>>();
+    assert_eq!(b.len(), 2);
+    assert_eq!(b[0].0, Matrix::new(vec![vec![0, 0], vec![1, 1]]));
+    assert_eq!(b[1].1, Matrix::new(vec![vec![2]]));
+  }
+
+  #[test]
+  fn test_shuffled_batches() {
+    let x = Matrix::create_from_data((0..10).collect(), 10, 1);
+    let mut rng = Rng::new(3);
+    let mut seen = vec![];
+    for (bx, by) in shuffled_batches(&x, &x, 3, &mut rng) {
+      assert_eq!(bx, by);
+      seen.extend(bx.iter().map(|(e, _, _)| *e));
+    }
+    seen.sort_unstable();
+    assert_eq!(seen, (0..10).collect::<Vec<i32>>());
+  }
+}
@@ -0,0 +1,112 @@
+use crate::matrix::num::Real;
+use crate::Matrix;
+
+/// A small deterministic pseudo-random number generator (xorshift64*), seeded
+/// by SplitMix64. It is intended for weight initialization and shuffling, not
+/// for cryptographic purposes.
+#[derive(Debug, Clone)]
+pub struct Rng {
+  state: u64,
+}
+
+impl Rng {
+  /// Creates a generator from the given seed.
+  pub fn new(seed: u64) -> Rng {
+    let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    Rng {
+      state: if z == 0 { 1 } else { z },
+    }
+  }
+
+  /// Returns the next 64 random bits.
+  pub fn next_u64(&mut self) -> u64 {
+    self.state ^= self.state >> 12;
+    self.state ^= self.state << 25;
+    self.state ^= self.state >> 27;
+    self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+  }
+
+  /// Returns a number uniformly distributed in `[0, 1)`.
+  pub fn uniform(&mut self) -> f64 {
+    (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+  }
+
+  /// Returns a standard normally distributed number, using the Box-Muller transform.
+  pub fn normal(&mut self) -> f64 {
+    let u = 1.0 - self.uniform();
+    let v = self.uniform();
+    (-2.0 * u.ln()).sqrt() * (2.0 * core::f64::consts::PI * v).cos()
+  }
+
+  /// Shuffles the slice in place (Fisher-Yates).
+  pub fn shuffle<E>(&mut self, v: &mut [E]) {
+    for i in (1..v.len()).rev() {
+      let j = (self.next_u64() % (i as u64 + 1)) as usize;
+      v.swap(i, j);
+    }
+  }
+}
+
+/// Creates a matrix with elements uniformly distributed in `[low, high)`.
+pub fn uniform<T: Real>(n_rows: usize, n_cols: usize, low: T, high: T, rng: &mut Rng) -> Matrix<T> {
+  let data = (0..n_rows * n_cols)
+    .map(|_| low + (high - low) * T::from_f64(rng.uniform()))
+    .collect();
+  Matrix::create_from_data(data, n_rows, n_cols)
+}
+
+/// Creates a matrix with normally distributed elements.
+pub fn normal<T: Real>(n_rows: usize, n_cols: usize, mean: T, std: T, rng: &mut Rng) -> Matrix<T> {
+  let data = (0..n_rows * n_cols)
+    .map(|_| mean + std * T::from_f64(rng.normal()))
+    .collect();
+  Matrix::create_from_data(data, n_rows, n_cols)
+}
+
+/// Creates an `n_in` x `n_out` weight matrix by Glorot/Xavier uniform initialization,
+/// suited for sigmoid and tanh activations.
+pub fn xavier_uniform<T: Real>(n_in: usize, n_out: usize, rng: &mut Rng) -> Matrix<T> {
+  let limit = T::from_f64((6.0 / (n_in + n_out) as f64).sqrt());
+  uniform(n_in, n_out, -limit, limit, rng)
+}
+
+/// Creates an `n_in` x `n_out` weight matrix by He normal initialization,
+/// suited for ReLU activations.
+pub fn he_normal<T: Real>(n_in: usize, n_out: usize, rng: &mut Rng) -> Matrix<T> {
+  let std = T::from_f64((2.0 / n_in as f64).sqrt());
+  normal(n_in, n_out, T::zero(), std, rng)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_rng() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    assert_eq!(a.next_u64(), b.next_u64());
+    let samples = (0..10000).map(|_| a.uniform()).collect::<Vec<f64>>();
+    assert!(samples.iter().all(|&u| (0.0..1.0).contains(&u)));
+    let mean = samples.iter().sum::<f64>() / 10000.0;
+    assert!((mean - 0.5).abs() < 0.02);
+    let mut v = (0..10).collect::<Vec<usize>>();
+    a.shuffle(&mut v);
+    v.sort_unstable();
+    assert_eq!(v, (0..10).collect::<Vec<usize>>());
+  }
+
+  #[test]
+  fn test_initializers() {
+    let mut rng = Rng::new(7);
+    let w: Matrix<f32> = xavier_uniform(20, 30, &mut rng);
+    assert_eq!((w.n_rows(), w.n_cols()), (20, 30));
+    assert!(w.iter().all(|(e, _, _)| e.abs() <= (6.0f32 / 50.0).sqrt()));
+    let h: Matrix<f64> = he_normal(200, 50, &mut rng);
+    let var = h.iter().map(|(e, _, _)| e * e).sum::<f64>() / 10000.0;
+    assert!((var - 0.01).abs() < 0.001);
+  }
+}
@@ -0,0 +1,229 @@
+use super::activation::Activation;
+use super::init::{he_normal, xavier_uniform, Rng};
+use super::optim::Optimizer;
+use crate::matrix::num::Real;
+use crate::Matrix;
+
+/// A fully connected layer computing `activation(x * weights + bias)` for a batch
+/// `x` holding one sample per row. The bias is a row added to every sample.
+#[derive(Debug, Clone)]
+pub struct Dense<T: Real> {
+  pub weights: Matrix<T>,
+  pub bias: Matrix<T>,
+  pub activation: Activation<T>,
+  input: Option<Matrix<T>>,
+  pre_activation: Option<Matrix<T>>,
+  grad_weights: Matrix<T>,
+  grad_bias: Matrix<T>,
+}
+
+impl<T: Real> Dense<T> {
+  /// Creates a layer with `n_in` inputs and `n_out` outputs. The weights are
+  /// initialized by He initialization for ReLU activations and by Xavier
+  /// initialization otherwise, the bias is zero.
+  pub fn new(n_in: usize, n_out: usize, activation: Activation<T>, rng: &mut Rng) -> Dense<T> {
+    let weights = match activation {
+      Activation::Relu | Activation::LeakyRelu(_) => he_normal(n_in, n_out, rng),
+      _ => xavier_uniform(n_in, n_out, rng),
+    };
+    Dense::from_weights(weights, Matrix::zeros(1, n_out), activation)
+  }
+
+  /// Creates a layer from an `n_in` x `n_out` weight matrix and a 1 x `n_out` bias.
+  pub fn from_weights(weights: Matrix<T>, bias: Matrix<T>, activation: Activation<T>) -> Dense<T> {
+    if bias.n_rows() != 1 || bias.n_cols() != weights.n_cols() {
+      panic!("dimensions do not match!");
+    }
+    let grad_weights = Matrix::zeros(weights.n_rows(), weights.n_cols());
+    let grad_bias = Matrix::zeros(1, weights.n_cols());
+    Dense {
+      weights,
+      bias,
+      activation,
+      input: None,
+      pre_activation: None,
+      grad_weights,
+      grad_bias,
+    }
+  }
+
+  /// Computes the output for the batch `x` without recording anything for `backward`.
+  pub fn predict(&self, x: &Matrix<T>) -> Matrix<T> {
+    let a = self.activation;
+    self.affine(x).map(|z| a.apply(z))
+  }
+
+  /// Computes the output for the batch `x` and keeps what `backward` needs.
+  pub fn forward(&mut self, x: &Matrix<T>) -> Matrix<T> {
+    let z = self.affine(x);
+    let a = self.activation;
+    let out = z.map(|z| a.apply(z));
+    self.input = Some(x.clone());
+    self.pre_activation = Some(z);
+    out
+  }
+
+  /// Takes the gradient of the loss with respect to the output of the last
+  /// `forward` call, stores the gradients of the parameters and returns the
+  /// gradient with respect to the input.
+  pub fn backward(&mut self, grad_output: &Matrix<T>) -> Matrix<T> {
+    let (x, z) = match (&self.input, &self.pre_activation) {
+      (Some(x), Some(z)) => (x, z),
+      _ => panic!("forward must be called before backward!"),
+    };
+    let a = self.activation;
+    let dz = grad_output % &z.map(|z| a.derivative(z));
+    self.grad_weights = &x.trans() * &dz;
+    let mut grad_bias = Matrix::zeros(1, dz.n_cols());
+    for (e, _, j) in dz.iter() {
+      grad_bias.set(0, j, grad_bias.get(0, j) + *e);
+    }
+    self.grad_bias = grad_bias;
+    &dz * &self.weights.trans()
+  }
+
+  /// Returns the gradient with respect to the weights computed by `backward`.
+  pub fn grad_weights(&self) -> &Matrix<T> {
+    &self.grad_weights
+  }
+
+  /// Returns the gradient with respect to the bias computed by `backward`.
+  pub fn grad_bias(&self) -> &Matrix<T> {
+    &self.grad_bias
+  }
+
+  /// Returns the parameters paired with their gradients, as expected by `Optimizer::step`.
+  pub fn params(&mut self) -> Vec<(&mut Matrix<T>, &Matrix<T>)> {
+    vec![
+      (&mut self.weights, &self.grad_weights),
+      (&mut self.bias, &self.grad_bias),
+    ]
+  }
+
+  fn affine(&self, x: &Matrix<T>) -> Matrix<T> {
+    let mut z = x * &self.weights;
+    for (e, _, j) in z.iter_mut() {
+      *e = *e + self.bias.get(0, j);
+    }
+    z
+  }
+}
+
+/// A network applying its layers one after another.
+#[derive(Debug, Clone)]
+pub struct Sequential<T: Real> {
+  pub layers: Vec<Dense<T>>,
+}
+
+impl<T: Real> Sequential<T> {
+  pub fn new(layers: Vec<Dense<T>>) -> Sequential<T> {
+    Sequential { layers }
+  }
+
+  /// Computes the output for the batch `x` without recording anything for `backward`.
+  pub fn predict(&self, x: &Matrix<T>) -> Matrix<T> {
+    self.layers.iter().fold(x.clone(), |h, layer| layer.predict(&h))
+  }
+
+  /// Computes the output for the batch `x` and keeps what `backward` needs.
+  pub fn forward(&mut self, x: &Matrix<T>) -> Matrix<T> {
+    self.layers.iter_mut().fold(x.clone(), |h, layer| layer.forward(&h))
+  }
+
+  /// Propagates the gradient of the loss with respect to the output back through
+  /// all layers and returns the gradient with respect to the input.
+  pub fn backward(&mut self, grad_output: &Matrix<T>) -> Matrix<T> {
+    self
+      .layers
+      .iter_mut()
+      .rev()
+      .fold(grad_output.clone(), |g, layer| layer.backward(&g))
+  }
+
+  /// Updates all parameters by the gradients computed by `backward`.
+  pub fn step<O: Optimizer<T>>(&mut self, optimizer: &mut O) {
+    let params = self.layers.iter_mut().flat_map(|l| l.params()).collect();
+    optimizer.step(params);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::batch::shuffled_batches;
+  use super::super::loss::{cross_entropy, mse, one_hot, softmax};
+  use super::super::optim::{Adam, Sgd};
+  use super::*;
+
+  #[test]
+  fn test_dense_gradients() {
+    let w = Matrix::new(vec![vec![0.2, -0.5], vec![0.7, 0.1], vec![-0.3, 0.4]]);
+    let b = Matrix::new(vec![vec![0.1, -0.2]]);
+    let x = Matrix::new(vec![vec![1.0, 2.0, -1.0], vec![0.5, -0.5, 2.0]]);
+    let t = Matrix::new(vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+    let mut layer = Dense::from_weights(w.clone(), b.clone(), Activation::Tanh);
+    let (_, g) = mse(&layer.forward(&x), &t);
+    let grad_x = layer.backward(&g);
+    let loss = |w: &Matrix<f64>, b: &Matrix<f64>, x: &Matrix<f64>| {
+      mse(&Dense::from_weights(w.clone(), b.clone(), Activation::Tanh).predict(x), &t).0
+    };
+    let h = 1e-6;
+    for &(i, j) in &[(0, 0), (1, 1), (2, 0)] {
+      let (mut plus, mut minus) = (w.clone(), w.clone());
+      plus.set(i, j, w.get(i, j) + h);
+      minus.set(i, j, w.get(i, j) - h);
+      let numeric = (loss(&plus, &b, &x) - loss(&minus, &b, &x)) / (2.0 * h);
+      assert!((layer.grad_weights().get(i, j) - numeric).abs() < 1e-8);
+    }
+    let (mut plus, mut minus) = (b.clone(), b.clone());
+    plus.set(0, 1, b.get(0, 1) + h);
+    minus.set(0, 1, b.get(0, 1) - h);
+    let numeric = (loss(&w, &plus, &x) - loss(&w, &minus, &x)) / (2.0 * h);
+    assert!((layer.grad_bias().get(0, 1) - numeric).abs() < 1e-8);
+    let (mut plus, mut minus) = (x.clone(), x.clone());
+    plus.set(1, 2, x.get(1, 2) + h);
+    minus.set(1, 2, x.get(1, 2) - h);
+    let numeric = (loss(&w, &b, &plus) - loss(&w, &b, &minus)) / (2.0 * h);
+    assert!((grad_x.get(1, 2) - numeric).abs() < 1e-8);
+  }
+
+  #[test]
+  fn test_train_xor() {
+    let mut rng = Rng::new(1);
+    let x = Matrix::new(vec![vec![0.0f32, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]]);
+    let labels = [0, 1, 1, 0];
+    let y = one_hot(&labels, 2);
+    let mut net = Sequential::new(vec![
+      Dense::new(2, 8, Activation::Tanh, &mut rng),
+      Dense::new(8, 2, Activation::Identity, &mut rng),
+    ]);
+    let mut adam = Adam::new(0.05);
+    for _ in 0..300 {
+      for (bx, by) in shuffled_batches(&x, &y, 2, &mut rng) {
+        let logits = net.forward(&bx);
+        let (_, grad) = cross_entropy(&logits, &by);
+        net.backward(&grad);
+        net.step(&mut adam);
+      }
+    }
+    let p = softmax(&net.predict(&x));
+    for (i, &label) in labels.iter().enumerate() {
+      assert!(p.get(i, label) > 0.9);
+    }
+  }
+
+  #[test]
+  fn test_train_regression_with_sgd() {
+    let mut rng = Rng::new(5);
+    let x = Matrix::create_from_data((0..20).map(|i| i as f32 / 10.0 - 1.0).collect(), 20, 1);
+    let y = x.map(|v| 3.0 * v - 0.5);
+    let mut net = Sequential::new(vec![Dense::new(1, 1, Activation::Identity, &mut rng)]);
+    let mut sgd = Sgd::with_momentum(0.05, 0.5);
+    for _ in 0..200 {
+      let (_, grad) = mse(&net.forward(&x), &y);
+      net.backward(&grad);
+      net.step(&mut sgd);
+    }
+    assert!((net.layers[0].weights.get(0, 0) - 3.0).abs() < 1e-3);
+    assert!((net.layers[0].bias.get(0, 0) + 0.5).abs() < 1e-3);
+  }
+}
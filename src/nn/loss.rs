@@ -0,0 +1,120 @@
+use crate::matrix::num::Real;
+use crate::Matrix;
+
+fn count<T: Real>(n: usize) -> T {
+  T::from_f64(n as f64)
+}
+
+/// Applies the softmax function to every row.
+pub fn softmax<T: Real>(m: &Matrix<T>) -> Matrix<T> {
+  let mut out = log_softmax(m);
+  for (e, _, _) in out.iter_mut() {
+    *e = e.exp();
+  }
+  out
+}
+
+/// Applies the logarithm of the softmax function to every row. This is computed
+/// without overflow, by shifting each row by its maximum.
+pub fn log_softmax<T: Real>(m: &Matrix<T>) -> Matrix<T> {
+  let n_cols = m.n_cols();
+  let mut out = m.clone();
+  for i in 0..m.n_rows() {
+    let row = (0..n_cols).map(|j| m.get(i, j)).collect::<Vec<T>>();
+    let max = row.iter().fold(row[0], |acc, &x| if x > acc { x } else { acc });
+    let sum = row.iter().fold(T::zero(), |acc, &x| acc + (x - max).exp());
+    let log_sum = max + sum.ln();
+    for (j, &x) in row.iter().enumerate() {
+      out.set(i, j, x - log_sum);
+    }
+  }
+  out
+}
+
+/// Computes the cross-entropy between the softmax of `logits` and the target
+/// distributions `targets`, e.g. one-hot encoded labels, averaged over the rows.
+/// Returns the loss together with its gradient with respect to `logits`.
+pub fn cross_entropy<T: Real>(logits: &Matrix<T>, targets: &Matrix<T>) -> (T, Matrix<T>) {
+  if logits.n_rows() != targets.n_rows() || logits.n_cols() != targets.n_cols() {
+    panic!("dimensions do not match!");
+  }
+  let n = count::<T>(logits.n_rows());
+  let log_p = log_softmax(logits);
+  let loss = (&log_p % targets)
+    .iter()
+    .fold(T::zero(), |acc, (e, _, _)| acc - *e)
+    / n;
+  let grad = (&softmax(logits) - targets) * (T::one() / n);
+  (loss, grad)
+}
+
+/// Computes the mean squared error over all elements. Returns the loss together
+/// with its gradient with respect to `predictions`.
+pub fn mse<T: Real>(predictions: &Matrix<T>, targets: &Matrix<T>) -> (T, Matrix<T>) {
+  if predictions.n_rows() != targets.n_rows() || predictions.n_cols() != targets.n_cols() {
+    panic!("dimensions do not match!");
+  }
+  let n = count::<T>(predictions.n_rows() * predictions.n_cols());
+  let diff = predictions - targets;
+  let loss = diff.iter().fold(T::zero(), |acc, (e, _, _)| acc + *e * *e) / n;
+  let grad = diff * (T::from_f64(2.0) / n);
+  (loss, grad)
+}
+
+/// Encodes the class labels as the rows of a matrix having `n_classes` columns.
+pub fn one_hot<T: Real>(labels: &[usize], n_classes: usize) -> Matrix<T> {
+  let mut m = Matrix::zeros(labels.len(), n_classes);
+  for (i, &label) in labels.iter().enumerate() {
+    m.set(i, label, T::one());
+  }
+  m
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>) {
+    for ((x, _, _), (y, _, _)) in a.iter().zip(b.iter()) {
+      assert!((x - y).abs() < 1e-12, "{:?} != {:?}", a, b);
+    }
+  }
+
+  #[test]
+  fn test_softmax() {
+    let m = Matrix::new(vec![vec![0.0, 2f64.ln()], vec![1000.0, 1000.0]]);
+    let s = softmax(&m);
+    assert_close(&s, &Matrix::new(vec![vec![1.0 / 3.0, 2.0 / 3.0], vec![0.5, 0.5]]));
+    let l = log_softmax(&m);
+    assert_close(&l.get_row(1), &Matrix::new(vec![vec![-(2f64.ln()), -(2f64.ln())]]));
+  }
+
+  #[test]
+  fn test_cross_entropy() {
+    let logits = Matrix::new(vec![vec![0.0, 2f64.ln()], vec![0.0, 0.0]]);
+    let targets: Matrix<f64> = one_hot(&[1, 0], 2);
+    let (loss, grad) = cross_entropy(&logits, &targets);
+    assert!((loss - ((1.5f64).ln() + 2f64.ln()) / 2.0).abs() < 1e-12);
+    assert_close(
+      &grad,
+      &Matrix::new(vec![vec![1.0 / 6.0, -1.0 / 6.0], vec![-0.25, 0.25]]),
+    );
+  }
+
+  #[test]
+  fn test_mse() {
+    let p = Matrix::new(vec![vec![1.0f32, 2.0], vec![3.0, 4.0]]);
+    let t = Matrix::new(vec![vec![1.0f32, 0.0], vec![3.0, 5.0]]);
+    let (loss, grad) = mse(&p, &t);
+    assert_eq!(loss, 1.25);
+    assert_eq!(grad, Matrix::new(vec![vec![0.0, 1.0], vec![0.0, -0.5]]));
+  }
+
+  #[test]
+  #[should_panic(expected = "dimensions do not match!")]
+  fn test_mse_dimensions() {
+    let p = Matrix::new(vec![vec![1.0f32, 2.0, 3.0, 4.0]]);
+    let t = Matrix::new(vec![vec![1.0f32, 0.0], vec![3.0, 5.0]]);
+    mse(&p, &t);
+  }
+}
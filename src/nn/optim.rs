@@ -0,0 +1,136 @@
+use crate::matrix::num::Real;
+use crate::Matrix;
+
+/// Updates parameters in place from their gradients.
+pub trait Optimizer<T: Real> {
+  /// Performs one update of every parameter, given as pairs of the parameter
+  /// and its gradient. The pairs must be passed in the same order each step.
+  fn step(&mut self, params: Vec<(&mut Matrix<T>, &Matrix<T>)>);
+}
+
+/// Stochastic gradient descent with optional momentum.
+#[derive(Debug, Clone)]
+pub struct Sgd<T: Real> {
+  pub learning_rate: T,
+  pub momentum: T,
+  velocity: Vec<Matrix<T>>,
+}
+
+impl<T: Real> Sgd<T> {
+  /// Creates plain gradient descent with the given learning rate.
+  pub fn new(learning_rate: T) -> Sgd<T> {
+    Sgd::with_momentum(learning_rate, T::zero())
+  }
+
+  /// Creates gradient descent with momentum.
+  pub fn with_momentum(learning_rate: T, momentum: T) -> Sgd<T> {
+    Sgd {
+      learning_rate,
+      momentum,
+      velocity: vec![],
+    }
+  }
+}
+
+impl<T: Real> Optimizer<T> for Sgd<T> {
+  fn step(&mut self, params: Vec<(&mut Matrix<T>, &Matrix<T>)>) {
+    for (k, (p, g)) in params.into_iter().enumerate() {
+      if self.velocity.len() == k {
+        self.velocity.push(Matrix::zeros(g.n_rows(), g.n_cols()));
+      }
+      let v = &mut self.velocity[k];
+      for ((v, _, _), (g, _, _)) in v.iter_mut().zip(g.iter()) {
+        *v = self.momentum * *v + *g;
+      }
+      for ((p, _, _), (v, _, _)) in p.iter_mut().zip(v.iter()) {
+        *p = *p - self.learning_rate * *v;
+      }
+    }
+  }
+}
+
+/// The Adam optimizer of Kingma and Ba.
+#[derive(Debug, Clone)]
+pub struct Adam<T: Real> {
+  pub learning_rate: T,
+  pub beta1: T,
+  pub beta2: T,
+  pub epsilon: T,
+  t: i32,
+  m: Vec<Matrix<T>>,
+  v: Vec<Matrix<T>>,
+}
+
+impl<T: Real> Adam<T> {
+  /// Creates the optimizer with the usual defaults `beta1 = 0.9`, `beta2 = 0.999`
+  /// and `epsilon = 1e-8`.
+  pub fn new(learning_rate: T) -> Adam<T> {
+    Adam {
+      learning_rate,
+      beta1: T::from_f64(0.9),
+      beta2: T::from_f64(0.999),
+      epsilon: T::from_f64(1e-8),
+      t: 0,
+      m: vec![],
+      v: vec![],
+    }
+  }
+}
+
+impl<T: Real> Optimizer<T> for Adam<T> {
+  fn step(&mut self, params: Vec<(&mut Matrix<T>, &Matrix<T>)>) {
+    self.t += 1;
+    let one = T::one();
+    let c1 = one - self.beta1.powi(self.t);
+    let c2 = one - self.beta2.powi(self.t);
+    for (k, (p, g)) in params.into_iter().enumerate() {
+      if self.m.len() == k {
+        self.m.push(Matrix::zeros(g.n_rows(), g.n_cols()));
+        self.v.push(Matrix::zeros(g.n_rows(), g.n_cols()));
+      }
+      let (m, v) = (&mut self.m[k], &mut self.v[k]);
+      for (((m, _, _), (v, _, _)), (g, _, _)) in m.iter_mut().zip(v.iter_mut()).zip(g.iter()) {
+        *m = self.beta1 * *m + (one - self.beta1) * *g;
+        *v = self.beta2 * *v + (one - self.beta2) * *g * *g;
+      }
+      for (((p, _, _), (m, _, _)), (v, _, _)) in p.iter_mut().zip(m.iter()).zip(v.iter()) {
+        let m_hat = *m / c1;
+        let v_hat = *v / c2;
+        *p = *p - self.learning_rate * m_hat / (v_hat.sqrt() + self.epsilon);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Minimizes |p - target|^2 and returns the final parameter.
+  fn minimize<O: Optimizer<f64>>(opt: &mut O, steps: usize) -> Matrix<f64> {
+    let target = Matrix::new(vec![vec![1.0, -2.0]]);
+    let mut p = Matrix::zeros(1, 2);
+    for _ in 0..steps {
+      let g = (&p - &target) * 2.0;
+      opt.step(vec![(&mut p, &g)]);
+    }
+    p
+  }
+
+  #[test]
+  fn test_sgd() {
+    let p = minimize(&mut Sgd::new(0.25), 1);
+    assert_eq!(p, Matrix::new(vec![vec![0.5, -1.0]]));
+    let p = minimize(&mut Sgd::with_momentum(0.1, 0.9), 300);
+    assert!((p.get(0, 0) - 1.0).abs() < 1e-6 && (p.get(0, 1) + 2.0).abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_adam() {
+    let p = minimize(&mut Adam::new(0.1), 1);
+    // The first Adam step moves every parameter by the learning rate.
+    assert!((p.get(0, 0) - 0.1).abs() < 1e-6 && (p.get(0, 1) + 0.1).abs() < 1e-6);
+    let p = minimize(&mut Adam::new(0.05), 2000);
+    assert!((p.get(0, 0) - 1.0).abs() < 1e-3 && (p.get(0, 1) + 2.0).abs() < 1e-3);
+  }
+}